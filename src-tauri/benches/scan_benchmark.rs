@@ -1,4 +1,11 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dup_detector_lib::hasher::{hash_file_with_options, HashOptions};
+use dup_detector_lib::types::ReadStrategy;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Synthetic file sizes for the read strategy benchmark.
+const FILE_SIZES: [usize; 4] = [4 * 1024, 1024 * 1024, 32 * 1024 * 1024, 128 * 1024 * 1024];
 
 fn benchmark_placeholder(c: &mut Criterion) {
     c.bench_function("placeholder", |b| {
@@ -10,5 +17,56 @@ fn benchmark_placeholder(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_placeholder);
+/// Creates a temporary file of `size` bytes with non-repeating content.
+fn synthetic_file(size: usize) -> NamedTempFile {
+    let mut file = NamedTempFile::new().expect("create temp file");
+    let chunk: Vec<u8> = (0..1024 * 1024).map(|i| (i * 31 % 251) as u8).collect();
+
+    let mut remaining = size;
+    while remaining > 0 {
+        let n = remaining.min(chunk.len());
+        file.write_all(&chunk[..n]).expect("write temp file");
+        remaining -= n;
+    }
+    file.flush().expect("flush temp file");
+    file
+}
+
+/// Compares read strategies (with and without page cache hints) across file sizes.
+fn benchmark_read_strategies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_file_read_strategy");
+    group.sample_size(10);
+
+    for size in FILE_SIZES {
+        let file = synthetic_file(size);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for read_strategy in [
+            ReadStrategy::Buffered,
+            ReadStrategy::Direct,
+            ReadStrategy::Mmap,
+        ] {
+            for avoid_page_cache in [false, true] {
+                let options = HashOptions {
+                    read_strategy,
+                    avoid_page_cache,
+                    ..Default::default()
+                };
+                let name = if avoid_page_cache {
+                    format!("{:?}+fadvise", read_strategy)
+                } else {
+                    format!("{:?}", read_strategy)
+                };
+
+                group.bench_with_input(BenchmarkId::new(name, size), &options, |b, options| {
+                    b.iter(|| hash_file_with_options(file.path(), options).expect("hash file"))
+                });
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_placeholder, benchmark_read_strategies);
 criterion_main!(benches);
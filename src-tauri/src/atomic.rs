@@ -0,0 +1,75 @@
+//! Atomic replacement of files on disk.
+//!
+//! Stores, reports and manifests are all replaced the same way: the new
+//! contents go to a uniquely named temporary file in the destination's
+//! directory, which is then renamed over the destination.
+
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Writes `path` through a temporary file renamed into place.
+///
+/// A crash or error mid-write never leaves `path` truncated or half
+/// overwritten, and no other file in the directory is touched. The temporary
+/// file is removed if writing fails.
+///
+/// # Arguments
+/// * `path` - File to create or replace; its directory must already exist
+/// * `write` - Writes the new contents to a buffered writer
+pub fn write_atomic<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut tmp = NamedTempFile::new_in(dir)?;
+    {
+        let mut writer = BufWriter::new(&mut tmp);
+        write(&mut writer)?;
+        writer.flush()?;
+    }
+    tmp.persist(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.json");
+        let neighbour = dir.path().join("notes.tmp");
+        fs::write(&path, b"old").unwrap();
+        fs::write(&neighbour, b"keep me").unwrap();
+
+        write_atomic(&path, |w| w.write_all(b"new")).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read(&neighbour).unwrap(), b"keep me");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_original() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.json");
+        fs::write(&path, b"old").unwrap();
+
+        let result = write_atomic(&path, |w| {
+            w.write_all(b"partial")?;
+            Err(io::Error::other("interrupted"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,579 @@
+//! Scan checkpointing for the Duplicate File Detector.
+//!
+//! Periodically persists the state of a running scan (options, discovered
+//! files and completed hashes) so an interrupted scan can be resumed after a
+//! crash or restart without re-hashing files that were already processed.
+
+use crate::atomic::write_atomic;
+use crate::error::{ScannerError, ScannerResult};
+use crate::hasher::HashResult;
+use crate::types::{FileEntry, InterruptedScan, ScanOptions};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Minimum time between checkpoint writes while hashing.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// File extension used for checkpoint files.
+const CHECKPOINT_EXTENSION: &str = "json";
+
+/// A hash that was computed before the scan was interrupted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedHash {
+    /// The file as it was when it was hashed.
+    pub file: FileEntry,
+
+    /// Hash of the file content, in the scan's hash algorithm.
+    pub hash: String,
+}
+
+/// Persisted state of an in-progress scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanCheckpoint {
+    /// Unique checkpoint ID (the ID of the scan that created it).
+    pub id: String,
+
+    /// Options the scan was started with.
+    pub options: ScanOptions,
+
+    /// Creation time as seconds since the Unix epoch.
+    pub created_at: u64,
+
+    /// Last write time as seconds since the Unix epoch.
+    pub updated_at: u64,
+
+    /// All files discovered by the directory scan.
+    pub files: Vec<FileEntry>,
+
+    /// Hashes computed so far.
+    pub completed_hashes: Vec<CompletedHash>,
+}
+
+impl ScanCheckpoint {
+    /// Creates a new checkpoint for a scan that has finished discovering files.
+    pub fn new(id: impl Into<String>, options: ScanOptions, files: Vec<FileEntry>) -> Self {
+        let now = unix_now();
+        Self {
+            id: id.into(),
+            options,
+            created_at: now,
+            updated_at: now,
+            files,
+            completed_hashes: Vec::new(),
+        }
+    }
+
+    /// Returns a lightweight summary suitable for listing in the UI.
+    pub fn summary(&self) -> InterruptedScan {
+        InterruptedScan {
+            id: self.id.clone(),
+            root_paths: self.options.root_paths.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            files_discovered: self.files.len() as u64,
+            hashes_completed: self.completed_hashes.len() as u64,
+        }
+    }
+
+    /// Splits files into those whose hash can be reused and those that still need hashing.
+    ///
+    /// A stored hash is only reused when the file's size and modification time
+    /// are unchanged since it was hashed.
+    ///
+    /// # Returns
+    /// A tuple of (reused (FileEntry, hash) pairs, files still to hash).
+    pub fn split_completed(
+        &self,
+        files: Vec<FileEntry>,
+    ) -> (Vec<(FileEntry, String)>, Vec<FileEntry>) {
+        let index: HashMap<&str, &CompletedHash> = self
+            .completed_hashes
+            .iter()
+            .map(|c| (c.file.path.as_str(), c))
+            .collect();
+
+        let mut reused = Vec::new();
+        let mut pending = Vec::new();
+
+        for file in files {
+            match index.get(file.path.as_str()) {
+                Some(done)
+                    if done.file.size == file.size && done.file.modified == file.modified =>
+                {
+                    let hash = done.hash.clone();
+                    reused.push((file, hash));
+                }
+                _ => pending.push(file),
+            }
+        }
+
+        debug!(
+            "Checkpoint {}: reusing {} hashes, {} files left to hash",
+            self.id,
+            reused.len(),
+            pending.len()
+        );
+
+        (reused, pending)
+    }
+}
+
+/// Directory-backed storage for scan checkpoints.
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    /// Directory holding one JSON file per checkpoint.
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    /// Creates a store rooted at the given directory.
+    /// The directory is created lazily on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Writes a checkpoint to disk, atomically replacing any previous version.
+    pub fn save(&self, checkpoint: &ScanCheckpoint) -> ScannerResult<()> {
+        let path = self.path_for(&checkpoint.id)?;
+        fs::create_dir_all(&self.dir)?;
+
+        let json =
+            serde_json::to_vec(checkpoint).map_err(|e| ScannerError::Checkpoint(e.to_string()))?;
+
+        write_atomic(&path, |w| w.write_all(&json))?;
+
+        debug!(
+            "Saved checkpoint {} ({} hashes)",
+            checkpoint.id,
+            checkpoint.completed_hashes.len()
+        );
+        Ok(())
+    }
+
+    /// Loads a checkpoint by ID.
+    pub fn load(&self, id: &str) -> ScannerResult<ScanCheckpoint> {
+        let path = self.path_for(id)?;
+        let data = fs::read(&path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                ScannerError::Checkpoint(format!("{} not found", id))
+            } else {
+                ScannerError::Io(e)
+            }
+        })?;
+
+        serde_json::from_slice(&data).map_err(|e| ScannerError::Checkpoint(e.to_string()))
+    }
+
+    /// Lists all stored checkpoints, most recently updated first.
+    ///
+    /// Unreadable or corrupt checkpoint files are skipped with a warning.
+    pub fn list(&self) -> ScannerResult<Vec<InterruptedScan>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut scans = Vec::new();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(CHECKPOINT_EXTENSION) {
+                continue;
+            }
+
+            let loaded = fs::read(&path).map_err(|e| e.to_string()).and_then(|data| {
+                serde_json::from_slice::<ScanCheckpoint>(&data).map_err(|e| e.to_string())
+            });
+
+            match loaded {
+                Ok(checkpoint) => scans.push(checkpoint.summary()),
+                Err(e) => warn!("Skipping unreadable checkpoint {}: {}", path.display(), e),
+            }
+        }
+
+        scans.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+        Ok(scans)
+    }
+
+    /// Removes a checkpoint. Removing a checkpoint that doesn't exist is not an error.
+    pub fn remove(&self, id: &str) -> ScannerResult<()> {
+        let path = self.path_for(id)?;
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the file path for a checkpoint, rejecting IDs that could escape the store.
+    fn path_for(&self, id: &str) -> ScannerResult<PathBuf> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        if !valid {
+            return Err(ScannerError::Checkpoint(format!("invalid id: {}", id)));
+        }
+
+        Ok(self.dir.join(format!("{}.{}", id, CHECKPOINT_EXTENSION)))
+    }
+}
+
+/// Records hash results for a running scan and flushes them to disk periodically.
+///
+/// Safe to share across Rayon worker threads. The checkpoint is owned by a
+/// dedicated writer thread: hashing threads only queue their results on a
+/// channel, and serializing and writing the checkpoint happen on the writer
+/// thread, so a write never holds up hashing.
+#[derive(Debug)]
+pub struct CheckpointWriter {
+    /// ID of the checkpoint being written.
+    id: String,
+
+    /// Queue of the writer thread.
+    sender: Sender<WriterCommand>,
+}
+
+/// Work for the checkpoint writer thread.
+#[derive(Debug)]
+enum WriterCommand {
+    /// Adds a finished hash; written with the next periodic flush.
+    Record(CompletedHash),
+
+    /// Writes the checkpoint now and acknowledges once it is on disk.
+    Save(Sender<()>),
+
+    /// Deletes the checkpoint, acknowledges, and stops the thread.
+    Discard(Sender<()>),
+}
+
+impl CheckpointWriter {
+    /// Creates a writer for the given checkpoint, flushing at most every
+    /// [`CHECKPOINT_INTERVAL`].
+    pub fn new(store: CheckpointStore, checkpoint: ScanCheckpoint) -> Self {
+        Self::with_interval(store, checkpoint, CHECKPOINT_INTERVAL)
+    }
+
+    /// Creates a writer with the given minimum time between periodic writes.
+    pub fn with_interval(
+        store: CheckpointStore,
+        checkpoint: ScanCheckpoint,
+        interval: Duration,
+    ) -> Self {
+        let id = checkpoint.id.clone();
+        let (sender, receiver) = mpsc::channel();
+
+        let spawned = thread::Builder::new()
+            .name("checkpoint-writer".to_string())
+            .spawn(move || run_writer(store, checkpoint, interval, receiver));
+        if let Err(e) = spawned {
+            // Commands go nowhere; the scan just isn't resumable
+            warn!("Failed to start checkpoint writer for {}: {}", id, e);
+        }
+
+        Self { id, sender }
+    }
+
+    /// Returns the ID of the checkpoint being written.
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Queues a finished hash for the next periodic write.
+    ///
+    /// Failed hashes are not recorded so they are retried on resume.
+    pub fn record(&self, result: &HashResult) {
+        if let Ok(hash) = &result.hash {
+            let _ = self.sender.send(WriterCommand::Record(CompletedHash {
+                file: result.file.clone(),
+                hash: hash.clone(),
+            }));
+        }
+    }
+
+    /// Writes the checkpoint now, including every hash recorded so far, and
+    /// waits for the write to finish. Failures are logged and otherwise
+    /// ignored, since losing a checkpoint must never fail the scan itself.
+    pub fn save(&self) {
+        self.send_and_wait(WriterCommand::Save);
+    }
+
+    /// Deletes the checkpoint from disk once the scan no longer needs it.
+    ///
+    /// Hashes recorded afterwards are dropped, so the checkpoint is never
+    /// written again.
+    pub fn discard(&self) {
+        self.send_and_wait(WriterCommand::Discard);
+    }
+
+    /// Sends a command to the writer thread and waits for its acknowledgement.
+    fn send_and_wait(&self, command: impl FnOnce(Sender<()>) -> WriterCommand) {
+        let (done, acknowledged) = mpsc::channel();
+        if self.sender.send(command(done)).is_ok() {
+            // Fails without blocking if the writer thread is gone
+            let _ = acknowledged.recv();
+        }
+    }
+}
+
+/// Body of the checkpoint writer thread.
+///
+/// Runs until the checkpoint is discarded or the [`CheckpointWriter`] is
+/// dropped. Recorded hashes are written once `interval` has passed since the
+/// last write.
+fn run_writer(
+    store: CheckpointStore,
+    mut checkpoint: ScanCheckpoint,
+    interval: Duration,
+    commands: Receiver<WriterCommand>,
+) {
+    let mut last_save = Instant::now();
+    let mut dirty = false;
+
+    loop {
+        // Nothing to write: sleep until the next command
+        let command = if dirty {
+            commands.recv_timeout(interval.saturating_sub(last_save.elapsed()))
+        } else {
+            commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        };
+
+        match command {
+            Ok(WriterCommand::Record(completed)) => {
+                checkpoint.completed_hashes.push(completed);
+                dirty = true;
+            }
+            Ok(WriterCommand::Save(done)) => {
+                write_checkpoint(&store, &mut checkpoint);
+                last_save = Instant::now();
+                dirty = false;
+                let _ = done.send(());
+            }
+            Ok(WriterCommand::Discard(done)) => {
+                if let Err(e) = store.remove(&checkpoint.id) {
+                    warn!("Failed to remove checkpoint {}: {}", checkpoint.id, e);
+                }
+                let _ = done.send(());
+                return;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        if dirty && last_save.elapsed() >= interval {
+            write_checkpoint(&store, &mut checkpoint);
+            last_save = Instant::now();
+            dirty = false;
+        }
+    }
+}
+
+/// Stamps and writes a checkpoint, logging failures.
+fn write_checkpoint(store: &CheckpointStore, checkpoint: &mut ScanCheckpoint) {
+    checkpoint.updated_at = unix_now();
+    if let Err(e) = store.save(checkpoint) {
+        warn!("Failed to save checkpoint {}: {}", checkpoint.id, e);
+    }
+}
+
+/// Returns the current time as seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file(path: &str, size: u64, modified: &str) -> FileEntry {
+        FileEntry::new(path.to_string(), size, Some(modified.to_string()))
+    }
+
+    fn sample_checkpoint(id: &str) -> ScanCheckpoint {
+        let options = ScanOptions {
+            root_paths: vec!["/data".to_string()],
+            ..Default::default()
+        };
+        ScanCheckpoint::new(
+            id,
+            options,
+            vec![
+                file("/data/a.txt", 10, "100"),
+                file("/data/b.txt", 10, "200"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path());
+
+        let mut checkpoint = sample_checkpoint("scan_1");
+        checkpoint.completed_hashes.push(CompletedHash {
+            file: file("/data/a.txt", 10, "100"),
+            hash: "abc".to_string(),
+        });
+
+        store.save(&checkpoint).unwrap();
+        let loaded = store.load("scan_1").unwrap();
+
+        assert_eq!(loaded.id, "scan_1");
+        assert_eq!(loaded.options.root_paths, vec!["/data".to_string()]);
+        assert_eq!(loaded.files.len(), 2);
+        assert_eq!(loaded.completed_hashes, checkpoint.completed_hashes);
+    }
+
+    #[test]
+    fn test_load_missing_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path());
+
+        let result = store.load("scan_missing");
+        assert!(matches!(result, Err(ScannerError::Checkpoint(_))));
+    }
+
+    #[test]
+    fn test_invalid_id_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path());
+
+        assert!(matches!(
+            store.load("../etc/passwd"),
+            Err(ScannerError::Checkpoint(_))
+        ));
+        assert!(store.remove("").is_err());
+    }
+
+    #[test]
+    fn test_list_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path().join("checkpoints"));
+
+        // Listing a store that was never written to is empty, not an error
+        assert!(store.list().unwrap().is_empty());
+
+        store.save(&sample_checkpoint("scan_1")).unwrap();
+        store.save(&sample_checkpoint("scan_2")).unwrap();
+        fs::write(
+            temp_dir.path().join("checkpoints/corrupt.json"),
+            b"{not json",
+        )
+        .unwrap();
+
+        let scans = store.list().unwrap();
+        assert_eq!(scans.len(), 2);
+        assert!(scans.iter().all(|s| s.files_discovered == 2));
+
+        store.remove("scan_1").unwrap();
+        store.remove("scan_1").unwrap(); // Idempotent
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_split_completed_reuses_unchanged_files() {
+        let mut checkpoint = sample_checkpoint("scan_1");
+        checkpoint.completed_hashes = vec![
+            CompletedHash {
+                file: file("/data/a.txt", 10, "100"),
+                hash: "hash_a".to_string(),
+            },
+            CompletedHash {
+                file: file("/data/b.txt", 10, "200"),
+                hash: "hash_b".to_string(),
+            },
+        ];
+
+        let current = vec![
+            file("/data/a.txt", 10, "100"), // Unchanged
+            file("/data/b.txt", 10, "999"), // Modified since hashing
+            file("/data/c.txt", 10, "300"), // Never hashed
+        ];
+
+        let (reused, pending) = checkpoint.split_completed(current);
+
+        assert_eq!(reused.len(), 1);
+        assert_eq!(reused[0].0.path, "/data/a.txt");
+        assert_eq!(reused[0].1, "hash_a");
+
+        let pending_paths: Vec<&str> = pending.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(pending_paths, vec!["/data/b.txt", "/data/c.txt"]);
+    }
+
+    #[test]
+    fn test_writer_records_and_flushes() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path());
+        let writer = CheckpointWriter::with_interval(
+            store.clone(),
+            sample_checkpoint("scan_1"),
+            Duration::ZERO,
+        );
+
+        writer.record(&HashResult::success(
+            file("/data/a.txt", 10, "100"),
+            "hash_a".to_string(),
+        ));
+        writer.record(&HashResult::failure(
+            file("/data/b.txt", 10, "200"),
+            "denied".to_string(),
+        ));
+
+        // The writer thread flushes on its own; give it a moment
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let loaded = loop {
+            match store.load("scan_1") {
+                Ok(loaded) if !loaded.completed_hashes.is_empty() => break loaded,
+                _ if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+                _ => panic!("checkpoint was not flushed"),
+            }
+        };
+        assert_eq!(loaded.completed_hashes.len(), 1);
+        assert_eq!(loaded.completed_hashes[0].hash, "hash_a");
+
+        writer.discard();
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_leaves_writing_to_writer_thread() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(temp_dir.path());
+        let writer = CheckpointWriter::new(store.clone(), sample_checkpoint("scan_1"));
+        writer.save();
+
+        // Within the interval, recording only queues the hash
+        writer.record(&HashResult::success(
+            file("/data/a.txt", 10, "100"),
+            "hash_a".to_string(),
+        ));
+        assert!(store.load("scan_1").unwrap().completed_hashes.is_empty());
+
+        writer.save();
+        let loaded = store.load("scan_1").unwrap();
+        assert_eq!(loaded.completed_hashes.len(), 1);
+
+        // Nothing is written once the checkpoint is discarded
+        writer.discard();
+        writer.record(&HashResult::success(
+            file("/data/b.txt", 10, "200"),
+            "hash_b".to_string(),
+        ));
+        writer.save();
+        assert!(store.list().unwrap().is_empty());
+    }
+}
@@ -2,32 +2,64 @@
 //!
 //! These commands are invoked from the Svelte frontend via Tauri's IPC.
 
-use crate::duplicates::find_duplicates;
+use crate::checkpoint::{CheckpointStore, CheckpointWriter, ScanCheckpoint};
+use crate::duplicates::{
+    find_audio_duplicates, find_duplicate_directories, find_duplicates, find_name_conflicts,
+    overlap_matrix, select_groups, summarize_folders, ReferenceRoots, SizeBuckets,
+};
 use crate::error::ScannerError;
+use crate::hasher::audio::{hash_audio_files, is_audio};
 use crate::hasher::{
-    extract_hash_errors, extract_successful_hashes, hash_files_parallel_with_cancel,
+    extract_hash_errors, extract_successful_hashes, hash_files_parallel_observed, HashOptions,
+};
+use crate::history::{wasted_space_trends, HistoryStore};
+use crate::manifest::{
+    export_manifest as write_manifest, load_manifest, match_manifests, verify_manifest,
 };
-use crate::scanner::{group_by_size, scan_directories};
-use crate::state::AppState;
+use crate::report::export_report as write_report;
+use crate::report::fdupes::{parse_fdupes, verify_groups};
+use crate::scanner::empty::{is_empty_tree, remove_empty_tree, DirectoryListing};
+use crate::scanner::{group_by_size, refresh_files, scan_directories, ScanOutput};
+use crate::session::{diff_results, mark_stale, SavedSession, SessionStore};
+use crate::similarity::chunks::DEFAULT_OVERLAP_THRESHOLD;
+use crate::similarity::images::DEFAULT_IMAGE_DISTANCE;
+use crate::similarity::text::DEFAULT_TEXT_SIMILARITY;
+use crate::similarity::{analyze_chunk_overlap, find_similar_images, find_similar_text};
+use crate::state::{AppState, ComputedHashes};
 use crate::types::{
-    DeleteError, DeleteResult, ScanError, ScanOptions, ScanPhase, ScanProgress, ScanResult,
+    DeleteError, DeleteResult, DuplicateGroup, FileEntry, FolderSummary, GroupPage, GroupQuery,
+    InterruptedScan, LoadedSession, ManifestFormat, ReportFormat, RootTrend, ScanDiff, ScanError,
+    ScanHistoryEntry, ScanOptions, ScanPhase, ScanProgress, ScanResult, SessionSummary,
+    VerifyResult,
 };
 use log::{debug, error, info, warn};
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tauri::{AppHandle, Emitter, State};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Rate limiting for progress events (max events per second).
 const PROGRESS_RATE_LIMIT_MS: u64 = 100;
 
+/// Subdirectory of the app data directory holding scan checkpoints.
+const CHECKPOINT_DIR: &str = "checkpoints";
+
+/// Subdirectory of the app data directory holding saved sessions.
+const SESSION_DIR: &str = "sessions";
+
+/// File in the app data directory holding the scan history.
+const HISTORY_FILE: &str = "scan_history.json";
+
 /// Event names for frontend communication.
 mod events {
     pub const SCAN_PROGRESS: &str = "scan_progress";
     pub const SCAN_FINISHED: &str = "scan_finished";
     pub const SCAN_ERROR: &str = "scan_error";
     pub const SCAN_CANCELLED: &str = "scan_cancelled";
+    pub const DUPLICATE_GROUP_FOUND: &str = "duplicate_group_found";
+    pub const DUPLICATE_GROUP_SUMMARY: &str = "duplicate_group_summary";
 }
 
 /// Starts a duplicate file scan with the given options.
@@ -38,7 +70,9 @@ mod events {
 /// 3. Hash files in size groups (parallel)
 /// 4. Group files by hash to find duplicates
 ///
-/// Progress events are emitted throughout the process.
+/// Progress events are emitted throughout the process. The scan state is
+/// checkpointed to disk so it can be resumed with [`resume_scan`] if the
+/// application exits before the scan completes.
 #[tauri::command]
 pub async fn start_scan(
     options: ScanOptions,
@@ -47,6 +81,203 @@ pub async fn start_scan(
 ) -> Result<ScanResult, String> {
     info!("Starting scan with {} root paths", options.root_paths.len());
 
+    run_scan(options, None, &app_handle, state.inner())
+}
+
+/// Lists scans that were interrupted before completion and can be resumed.
+#[tauri::command]
+pub async fn list_interrupted_scans(app_handle: AppHandle) -> Result<Vec<InterruptedScan>, String> {
+    let store = checkpoint_store(&app_handle)?;
+    let scans = store.list()?;
+
+    debug!("Found {} interrupted scans", scans.len());
+    Ok(scans)
+}
+
+/// Resumes an interrupted scan from its checkpoint.
+///
+/// The checkpointed file list is re-stat'ed instead of walking the roots
+/// again, and files whose hash was already computed are skipped as long as
+/// their size and modification time are unchanged.
+#[tauri::command]
+pub async fn resume_scan(
+    checkpoint_id: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ScanResult, String> {
+    info!("Resuming scan from checkpoint {}", checkpoint_id);
+
+    let checkpoint = checkpoint_store(&app_handle)?.load(&checkpoint_id)?;
+    let options = checkpoint.options.clone();
+
+    run_scan(options, Some(checkpoint), &app_handle, state.inner())
+}
+
+/// Deletes the checkpoint of an interrupted scan without resuming it.
+#[tauri::command]
+pub async fn discard_interrupted_scan(
+    checkpoint_id: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    info!("Discarding checkpoint {}", checkpoint_id);
+
+    checkpoint_store(&app_handle)?.remove(&checkpoint_id)?;
+    Ok(())
+}
+
+/// Saves a finished scan so it can be reopened later without rescanning.
+///
+/// # Arguments
+/// * `result` - The scan result to save (None = the last result kept in the backend)
+/// * `options` - Options the scan was run with
+/// * `name` - Optional name shown in the session list
+///
+/// # Returns
+/// A summary of the saved session.
+#[tauri::command]
+pub async fn save_session(
+    result: Option<ScanResult>,
+    options: ScanOptions,
+    name: Option<String>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SessionSummary, String> {
+    let result = result_or_last(result, &state)?;
+    let session = SavedSession::new(generate_session_id(), name, options, result);
+    info!("Saving session {}", session.id);
+
+    session_store(&app_handle)?.save(&session)?;
+    Ok(session.summary())
+}
+
+/// Lists saved scan sessions, most recently saved first.
+#[tauri::command]
+pub async fn list_sessions(app_handle: AppHandle) -> Result<Vec<SessionSummary>, String> {
+    let sessions = session_store(&app_handle)?.list()?;
+
+    debug!("Found {} saved sessions", sessions.len());
+    Ok(sessions)
+}
+
+/// Reopens a saved session.
+///
+/// Every file in the result is checked against the disk, and files that are
+/// missing or whose size or modification time changed are marked stale. The
+/// session's reference roots become active again; state left by the last
+/// scan (computed hashes, directory layout) is cleared since it doesn't
+/// belong to the reopened result.
+///
+/// # Returns
+/// The session with stale files marked.
+#[tauri::command]
+pub async fn load_session(
+    session_id: String,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<LoadedSession, String> {
+    if state.is_scanning() {
+        return Err(ScannerError::ScanInProgress.into());
+    }
+
+    info!("Loading session {}", session_id);
+    let session = session_store(&app_handle)?.load(&session_id)?;
+    let summary = session.summary();
+    let SavedSession {
+        options,
+        mut result,
+        ..
+    } = session;
+
+    let stale_paths = mark_stale(&mut result);
+    let stale_files = stale_paths.len() as u64;
+    if stale_files > 0 {
+        warn!(
+            "Session {}: {} files changed since it was saved",
+            session_id, stale_files
+        );
+    }
+
+    state.set_reference_roots(ReferenceRoots::new(&options.reference_roots));
+    state.set_last_hashes(None);
+    state.set_directory_listing(None);
+    let result = keep_result(&state, result, &options);
+    state.set_stale_paths(stale_paths);
+
+    Ok(LoadedSession {
+        summary,
+        options,
+        result,
+        stale_files,
+    })
+}
+
+/// Compares the duplicate groups of two saved sessions.
+///
+/// # Arguments
+/// * `base_session_id` - The older session
+/// * `session_id` - The newer session
+///
+/// # Returns
+/// New, grown, shrunk, moved and resolved groups, and the change in wasted space.
+#[tauri::command]
+pub async fn diff_sessions(
+    base_session_id: String,
+    session_id: String,
+    app_handle: AppHandle,
+) -> Result<ScanDiff, String> {
+    info!("Comparing session {} with {}", session_id, base_session_id);
+
+    let store = session_store(&app_handle)?;
+    let before = store.load(&base_session_id)?;
+    let after = store.load(&session_id)?;
+
+    Ok(diff_results(&before.result, &after.result)?)
+}
+
+/// Lists completed scans from the scan history, most recent first.
+#[tauri::command]
+pub async fn list_scan_history(app_handle: AppHandle) -> Result<Vec<ScanHistoryEntry>, String> {
+    let entries = history_store(&app_handle)?.list()?;
+
+    debug!("Found {} scans in history", entries.len());
+    Ok(entries)
+}
+
+/// Returns the wasted space over time of every root in the scan history.
+///
+/// # Returns
+/// One trend per root, sorted by path, each with its points oldest first.
+#[tauri::command]
+pub async fn scan_history_trends(app_handle: AppHandle) -> Result<Vec<RootTrend>, String> {
+    let entries = history_store(&app_handle)?.list()?;
+    Ok(wasted_space_trends(&entries))
+}
+
+/// Removes all scans from the scan history.
+#[tauri::command]
+pub async fn clear_scan_history(app_handle: AppHandle) -> Result<(), String> {
+    info!("Clearing scan history");
+
+    history_store(&app_handle)?.clear()?;
+    Ok(())
+}
+
+/// Deletes a saved session.
+#[tauri::command]
+pub async fn delete_session(session_id: String, app_handle: AppHandle) -> Result<(), String> {
+    info!("Deleting session {}", session_id);
+
+    session_store(&app_handle)?.remove(&session_id)?;
+    Ok(())
+}
+
+/// Runs the scan pipeline, optionally continuing from a checkpoint.
+fn run_scan(
+    options: ScanOptions,
+    resume: Option<ScanCheckpoint>,
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<ScanResult, String> {
     // Try to start the scan
     let scan_id = state
         .try_start_scan()
@@ -57,86 +288,169 @@ pub async fn start_scan(
     let start_time = Instant::now();
     let mut all_errors: Vec<ScanError> = Vec::new();
 
-    // Helper to check cancellation and emit cancelled event
-    let check_cancel = |state: &AppState, app_handle: &AppHandle| -> bool {
-        if state.is_cancel_requested() {
-            let _ = app_handle.emit(events::SCAN_CANCELLED, ());
-            true
-        } else {
-            false
-        }
-    };
-
-    // Phase 1: Scan directories
-    emit_progress(&app_handle, 0, None, ScanPhase::Counting, None);
-
-    let scan_output = match scan_directories(&options) {
-        Ok(output) => output,
+    // Load reference manifests up front so a bad path fails before any work
+    let manifests = match options
+        .reference_manifests
+        .iter()
+        .map(|path| load_manifest(Path::new(path)))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(manifests) => manifests,
         Err(e) => {
-            error!("Scan failed: {}", e);
+            error!("Failed to load manifest: {}", e);
             state.finish_scan();
             let _ = app_handle.emit(events::SCAN_ERROR, e.to_string());
             return Err(e.into());
         }
     };
 
-    if check_cancel(&state, &app_handle) {
-        state.finish_scan();
-        return Err(ScannerError::Cancelled.into());
-    }
+    // Phase 1: Scan directories, or re-check the files of an interrupted scan
+    emit_progress(app_handle, 0, None, ScanPhase::Counting, None);
+
+    let scan_output = match &resume {
+        Some(checkpoint) => refresh_files(&checkpoint.files, &options),
+        None => match scan_directories(&options) {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Scan failed: {}", e);
+                state.finish_scan();
+                let _ = app_handle.emit(events::SCAN_ERROR, e.to_string());
+                return Err(e.into());
+            }
+        },
+    };
+
+    stop_if_cancelled(state, app_handle, &None)?;
 
     let total_files = scan_output.files.len() as u64;
+    let listing = DirectoryListing::from_scan(&scan_output);
     all_errors.extend(scan_output.errors);
 
     info!("Found {} files in scan", total_files);
 
     // Phase 2: Group by size
     emit_progress(
-        &app_handle,
+        app_handle,
         total_files,
         Some(total_files),
         ScanPhase::Grouping,
         None,
     );
 
-    let size_groups = group_by_size(scan_output.files);
-    let files_to_hash: Vec<_> = size_groups.into_values().flatten().collect();
-    let files_to_hash_count = files_to_hash.len() as u64;
+    let checkpoint = match resume {
+        Some(mut checkpoint) => {
+            checkpoint.files = scan_output.files.clone();
+            checkpoint
+        }
+        None => ScanCheckpoint::new(scan_id.clone(), options.clone(), scan_output.files.clone()),
+    };
+
+    // Empty files all have the same content, so they are reported on their own
+    let (empty_files, sized_files): (Vec<FileEntry>, Vec<FileEntry>) =
+        scan_output.files.iter().cloned().partition(|f| f.size == 0);
+
+    // Audio files matched by payload skip the size filter: tags change their size
+    let (audio_files, other_files): (Vec<FileEntry>, Vec<FileEntry>) =
+        if options.match_audio_content {
+            sized_files.into_iter().partition(|f| is_audio(&f.path))
+        } else {
+            (Vec::new(), sized_files)
+        };
+
+    // Files with a unique size can't be duplicates, but a full manifest needs
+    // them too, audio files included
+    let candidates: Vec<_> = if options.hash_all_files {
+        other_files
+            .into_iter()
+            .chain(empty_files.iter().cloned())
+            .chain(audio_files.iter().cloned())
+            .collect()
+    } else {
+        group_by_size(other_files).into_values().flatten().collect()
+    };
+    let files_to_hash_count = candidates.len() as u64;
 
     info!(
         "{} files in size groups (potential duplicates)",
         files_to_hash_count
     );
 
-    if check_cancel(&state, &app_handle) {
-        state.finish_scan();
-        return Err(ScannerError::Cancelled.into());
+    // Skip files hashed before an interruption, unless they changed since
+    let (reused_hashes, files_to_hash) = checkpoint.split_completed(candidates);
+    let reused_count = reused_hashes.len() as u64;
+
+    if reused_count > 0 {
+        info!("Reusing {} hashes from checkpoint", reused_count);
     }
 
+    // Persist the discovered files so an interruption from here on is resumable
+    let writer = match checkpoint_store(app_handle) {
+        Ok(store) => {
+            let writer = CheckpointWriter::new(store, checkpoint);
+            writer.save();
+            Some(writer)
+        }
+        Err(e) => {
+            warn!("Scan checkpoints disabled: {}", e);
+            None
+        }
+    };
+
+    stop_if_cancelled(state, app_handle, &writer)?;
+
     // Phase 3: Hash files in parallel with cancellation support
     let hashed_count = Arc::new(AtomicU64::new(0));
-    let last_emit = Arc::new(AtomicU64::new(0));
+    let throttle = ProgressThrottle::new();
 
     let handle_clone = app_handle.clone();
-    let state_ref_for_progress = state.inner();
-    let state_ref_for_cancel = state.inner();
+    let writer_ref = writer.as_ref();
+    let hash_options = HashOptions::from_scan_options(&options);
+
+    // Groups are final once every file of their size is hashed, and the
+    // hasher finishes size buckets one by one, so each group is sent to the
+    // UI as soon as it is confirmed while the rest of the scan continues.
+    // Paged results only send each group's summary; the groups themselves are
+    // fetched from the backend once the scan is done.
+    let reference = ReferenceRoots::new(&options.reference_roots);
+    // Audio files content-hashed for a full manifest are grouped by payload instead
+    let by_payload = |file: &FileEntry| options.match_audio_content && is_audio(&file.path);
+    let buckets = SizeBuckets::new(
+        files_to_hash.iter().filter(|f| !by_payload(f)),
+        reused_hashes.iter().filter(|(f, _)| !by_payload(f)),
+    );
+    let emit_groups = |handle: &AppHandle, groups: Vec<DuplicateGroup>| {
+        for group in reference.filter_groups(groups) {
+            let _ = if options.page_results {
+                handle.emit(events::DUPLICATE_GROUP_SUMMARY, group.summary())
+            } else {
+                handle.emit(events::DUPLICATE_GROUP_FOUND, &group)
+            };
+        }
+    };
+    emit_groups(app_handle, buckets.take_complete());
 
-    let hash_results = hash_files_parallel_with_cancel(
+    let hash_results = hash_files_parallel_observed(
         files_to_hash,
-        move |count| {
-            // Rate-limit progress emissions
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64;
-
-            let last = last_emit.load(Ordering::Relaxed);
-            if now - last >= PROGRESS_RATE_LIMIT_MS {
-                last_emit.store(now, Ordering::Relaxed);
+        &hash_options,
+        move |result, count| {
+            if let Some(writer) = writer_ref {
+                writer.record(result);
+            }
+
+            if !by_payload(&result.file) {
+                let groups = buckets.record(result);
+                if !groups.is_empty() && !state.is_cancel_requested() {
+                    emit_groups(&handle_clone, groups);
+                }
+            }
+
+            let count = reused_count + count;
+
+            if throttle.ready() {
                 hashed_count.store(count, Ordering::Relaxed);
 
                 // Check for cancellation during hashing
-                if !state_ref_for_progress.is_cancel_requested() {
+                if !state.is_cancel_requested() {
                     emit_progress(
                         &handle_clone,
                         count,
@@ -147,48 +461,278 @@ pub async fn start_scan(
                 }
             }
         },
-        move || state_ref_for_cancel.is_cancel_requested(),
+        || state.is_cancel_requested(),
     );
 
     // Check if cancelled during hashing
-    if check_cancel(&state, &app_handle) {
-        state.finish_scan();
-        return Err(ScannerError::Cancelled.into());
-    }
+    stop_if_cancelled(state, app_handle, &writer)?;
 
     // Collect hash errors
     for (path, error) in extract_hash_errors(&hash_results) {
         all_errors.push(ScanError::new(path, error));
     }
 
-    let successful_hashes = extract_successful_hashes(hash_results);
+    let mut successful_hashes = reused_hashes;
+    successful_hashes.extend(extract_successful_hashes(hash_results));
     info!("{} files successfully hashed", successful_hashes.len());
 
+    // Match audio files by their payload, ignoring tags. Their content keys
+    // take the place of content hashes when comparing directories.
+    let (audio_groups, audio_content_hashes) = if audio_files.is_empty() {
+        (Vec::new(), Vec::new())
+    } else {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Hashing,
+            Some("Matching audio content".to_string()),
+        );
+
+        let audio_results =
+            hash_audio_files(audio_files, &hash_options, || state.is_cancel_requested());
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        let mut audio_hashes = Vec::with_capacity(audio_results.len());
+        for result in audio_results {
+            match result.hash {
+                Ok(hash) => audio_hashes.push((result.file, hash)),
+                Err(e) => all_errors.push(ScanError::new(result.file.path, e)),
+            }
+        }
+        let content_hashes: Vec<(FileEntry, String)> = audio_hashes
+            .iter()
+            .map(|(file, hash)| (file.clone(), hash.content_key()))
+            .collect();
+        (find_audio_duplicates(audio_hashes), content_hashes)
+    };
+
     // Phase 4: Find duplicates
     emit_progress(
-        &app_handle,
+        app_handle,
         files_to_hash_count,
         Some(files_to_hash_count),
         ScanPhase::Finalizing,
         None,
     );
 
-    let duplicate_groups = find_duplicates(successful_hashes);
+    let directories = if audio_content_hashes.is_empty() {
+        find_duplicate_directories(
+            &scan_output.files,
+            &successful_hashes,
+            &options.scan_roots(),
+        )
+    } else {
+        let mut directory_hashes = successful_hashes.clone();
+        directory_hashes.extend(audio_content_hashes);
+        find_duplicate_directories(&scan_output.files, &directory_hashes, &options.scan_roots())
+    };
+
+    // Optional: match against reference manifests
+    let manifest_matches = if manifests.is_empty() {
+        Vec::new()
+    } else {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Finalizing,
+            Some("Matching against manifests".to_string()),
+        );
+
+        let (matches, errors) = match_manifests(
+            &manifests,
+            &scan_output.files,
+            &hash_options,
+            &successful_hashes,
+            || state.is_cancel_requested(),
+        );
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        all_errors.extend(errors);
+        matches
+    };
+
+    // Optional: same name, different content
+    let name_conflicts = if options.find_name_conflicts {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Finalizing,
+            Some("Comparing file names".to_string()),
+        );
+
+        let (conflicts, errors) = find_name_conflicts(
+            &scan_output.files,
+            &options.scan_roots(),
+            options.name_conflict_key,
+            &hash_options,
+            &successful_hashes,
+            || state.is_cancel_requested(),
+        );
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        all_errors.extend(errors);
+        conflicts
+    } else {
+        Vec::new()
+    };
+
+    // Optional: near-duplicate images
+    let similar_images = if options.find_similar_images {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Finalizing,
+            Some("Comparing images".to_string()),
+        );
+
+        let similar = find_similar_images(
+            &scan_output.files,
+            &hash_options,
+            options.image_hash_algorithm,
+            options
+                .image_similarity_distance
+                .unwrap_or(DEFAULT_IMAGE_DISTANCE),
+            &successful_hashes,
+            || state.is_cancel_requested(),
+        );
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        all_errors.extend(similar.errors);
+        similar.groups
+    } else {
+        Vec::new()
+    };
+
+    // Optional: near-duplicate text
+    let similar_text = if options.find_similar_text {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Finalizing,
+            Some("Comparing text files".to_string()),
+        );
+
+        let similar = find_similar_text(
+            &scan_output.files,
+            &hash_options,
+            options
+                .text_similarity_threshold
+                .unwrap_or(DEFAULT_TEXT_SIMILARITY),
+            &successful_hashes,
+            || state.is_cancel_requested(),
+        );
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        all_errors.extend(similar.errors);
+        similar.groups
+    } else {
+        Vec::new()
+    };
+
+    // Optional: partial overlap via content-defined chunks
+    let chunk_analysis = if options.analyze_chunk_overlap {
+        emit_progress(
+            app_handle,
+            files_to_hash_count,
+            Some(files_to_hash_count),
+            ScanPhase::Finalizing,
+            Some("Comparing file chunks".to_string()),
+        );
+
+        let (analysis, errors) = analyze_chunk_overlap(
+            &scan_output.files,
+            &hash_options,
+            options
+                .chunk_overlap_threshold
+                .unwrap_or(DEFAULT_OVERLAP_THRESHOLD),
+            &successful_hashes,
+            || state.is_cancel_requested(),
+        );
+
+        stop_if_cancelled(state, app_handle, &writer)?;
+
+        all_errors.extend(errors);
+        Some(analysis)
+    } else {
+        None
+    };
+
+    state.set_last_hashes(Some(ComputedHashes {
+        algorithm: options.hash_algorithm,
+        hashes: successful_hashes.clone(),
+    }));
+
+    let mut duplicate_groups = find_duplicates(
+        successful_hashes
+            .into_iter()
+            .filter(|(f, _)| !by_payload(f))
+            .collect(),
+    );
+    if !audio_groups.is_empty() {
+        duplicate_groups.extend(audio_groups);
+        duplicate_groups.sort_by_key(|g| std::cmp::Reverse(g.wasted_space()));
+    }
 
     let duration_ms = start_time.elapsed().as_millis() as u64;
 
-    let result = ScanResult::new(duplicate_groups, total_files, all_errors, duration_ms);
+    // Overlap covers all content, so it is computed before reference filtering
+    let overlap = overlap_matrix(&duplicate_groups, &scan_output.files, &options.scan_roots());
+
+    // Narrow results to files that also exist under a reference root
+    let duplicate_groups = reference.filter_groups(duplicate_groups);
+    let (directory_groups, directory_subsets) =
+        reference.filter_directories(directories.groups, directories.subsets);
+    let similar_images = reference.filter_similar(similar_images);
+    let similar_text = reference.filter_similar(similar_text);
+    let empty_files: Vec<FileEntry> = empty_files
+        .into_iter()
+        .filter(|f| !reference.contains(&f.path))
+        .collect();
+    let empty_directories: Vec<String> = listing
+        .empty_directories(&HashSet::new())
+        .into_iter()
+        .filter(|dir| !reference.contains(dir))
+        .collect();
+    state.set_reference_roots(reference);
+    state.set_directory_listing(Some(listing));
+
+    let result = ScanResult::new(duplicate_groups, total_files, all_errors, duration_ms)
+        .with_directories(directory_groups, directory_subsets)
+        .with_similar_images(similar_images)
+        .with_similar_text(similar_text)
+        .with_chunk_analysis(chunk_analysis)
+        .with_manifest_matches(manifest_matches)
+        .with_hash_algorithm(options.hash_algorithm)
+        .with_empty(empty_files, empty_directories)
+        .with_name_conflicts(name_conflicts)
+        .with_overlap_matrix(overlap);
 
     info!(
-        "Scan complete in {}ms: {} duplicate groups, {} wasted bytes",
+        "Scan complete in {}ms: {} duplicate groups, {} duplicate directory groups, {} wasted bytes",
         duration_ms,
         result.duplicate_groups.len(),
+        result.duplicate_directories.len(),
         result.total_wasted_space
     );
 
+    // The scan finished, so its checkpoint is no longer needed
+    discard_checkpoint(&writer);
+    record_history(app_handle, &scan_id, &options, &result);
+    let result = keep_result(state, result, &options);
+
     // Emit completion
     emit_progress(
-        &app_handle,
+        app_handle,
         total_files,
         Some(total_files),
         ScanPhase::Complete,
@@ -215,6 +759,10 @@ pub fn cancel_scan(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Deletes the specified files.
 ///
+/// Files under a reference root of the last scan, and files of a reopened
+/// session that changed on disk since it was saved, are refused and reported
+/// as failures.
+///
 /// # Arguments
 /// * `file_paths` - List of file paths to delete
 /// * `use_trash` - If true, move to trash/recycle bin; otherwise permanently delete
@@ -222,6 +770,7 @@ pub fn cancel_scan(state: State<'_, AppState>) -> Result<(), String> {
 pub async fn delete_files(
     file_paths: Vec<String>,
     use_trash: bool,
+    state: State<'_, AppState>,
 ) -> Result<DeleteResult, String> {
     info!(
         "Delete requested for {} files (use_trash: {})",
@@ -235,7 +784,11 @@ pub async fn delete_files(
     for path_str in file_paths {
         let path = Path::new(&path_str);
 
-        let result = if use_trash {
+        let result = if state.is_reference(&path_str) {
+            Err("File is in a reference location and cannot be deleted".to_string())
+        } else if state.is_stale(&path_str) {
+            Err("File changed since the session was saved; rescan before deleting".to_string())
+        } else if use_trash {
             trash::delete(path).map_err(|e| e.to_string())
         } else {
             std::fs::remove_file(path).map_err(|e| e.to_string())
@@ -258,10 +811,381 @@ pub async fn delete_files(
         deleted.len(),
         failed.len()
     );
+    state.remove_deleted_files(&deleted);
 
     Ok(DeleteResult::new(deleted, failed))
 }
 
+/// Finds directories that would be left empty by deleting the given files.
+///
+/// Works from the directory layout of the last completed scan, so
+/// directories that are already empty are included. Directories under a
+/// reference root are never reported.
+///
+/// # Arguments
+/// * `planned_deletions` - Paths of files about to be (or just) deleted
+///
+/// # Returns
+/// The topmost empty directories, sorted by path.
+#[tauri::command]
+pub async fn find_empty_directories(
+    planned_deletions: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let listing = state
+        .directory_listing()
+        .ok_or_else(|| "No scan results to check".to_string())?;
+
+    let removed: HashSet<&str> = planned_deletions.iter().map(String::as_str).collect();
+    Ok(listing
+        .empty_directories(&removed)
+        .into_iter()
+        .filter(|dir| !state.is_reference(dir))
+        .collect())
+}
+
+/// Removes empty directories.
+///
+/// Each directory is checked on disk first; one that holds anything other
+/// than empty directories is left alone and reported as a failure, as are
+/// directories under a reference root.
+///
+/// # Arguments
+/// * `dir_paths` - Directories to remove
+/// * `use_trash` - If true, move to trash/recycle bin; otherwise remove permanently
+#[tauri::command]
+pub async fn delete_empty_directories(
+    dir_paths: Vec<String>,
+    use_trash: bool,
+    state: State<'_, AppState>,
+) -> Result<DeleteResult, String> {
+    info!(
+        "Removal requested for {} empty directories (use_trash: {})",
+        dir_paths.len(),
+        use_trash
+    );
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+
+    for path_str in dir_paths {
+        let path = Path::new(&path_str);
+
+        let result = if state.is_reference(&path_str) {
+            Err("Directory is in a reference location and cannot be deleted".to_string())
+        } else if use_trash {
+            match is_empty_tree(path) {
+                Ok(true) => trash::delete(path).map_err(|e| e.to_string()),
+                Ok(false) => Err("Directory is not empty".to_string()),
+                Err(e) => Err(e.to_string()),
+            }
+        } else {
+            remove_empty_tree(path).map_err(|e| e.to_string())
+        };
+
+        match result {
+            Ok(()) => {
+                debug!("Removed: {}", path_str);
+                deleted.push(path_str);
+            }
+            Err(e) => {
+                warn!("Failed to remove {}: {}", path_str, e);
+                failed.push(DeleteError::new(path_str, e));
+            }
+        }
+    }
+
+    info!(
+        "Directory removal complete: {} succeeded, {} failed",
+        deleted.len(),
+        failed.len()
+    );
+
+    Ok(DeleteResult::new(deleted, failed))
+}
+
+/// Exports the hashes computed by the last completed scan as a manifest.
+///
+/// Only files the scan hashed are included; scan with `hash_all_files` to
+/// export every file.
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `format` - Checksum list or JSON with size and modification time
+///
+/// # Returns
+/// The number of files written.
+#[tauri::command]
+pub async fn export_manifest(
+    path: String,
+    format: ManifestFormat,
+    state: State<'_, AppState>,
+) -> Result<u64, String> {
+    let computed = state
+        .last_hashes()
+        .ok_or_else(|| ScannerError::Manifest("No scan results to export".to_string()))?;
+
+    info!(
+        "Exporting {} hashes to {} ({:?})",
+        computed.hashes.len(),
+        path,
+        format
+    );
+
+    Ok(write_manifest(
+        Path::new(&path),
+        format,
+        computed.algorithm,
+        &computed.hashes,
+    )?)
+}
+
+/// Adds up the duplicate files of a result per folder.
+///
+/// # Arguments
+/// * `result` - The scan result to summarize (None = the last result kept in the backend)
+/// * `root_paths` - Scan roots; each gets its own tree
+///
+/// # Returns
+/// One folder tree per root, with duplicate counts, bytes and wasted space
+/// at every level, most wasted space first.
+#[tauri::command]
+pub async fn folder_breakdown(
+    result: Option<ScanResult>,
+    root_paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FolderSummary>, String> {
+    let result = result_or_last(result, &state)?;
+    Ok(summarize_folders(&result.duplicate_groups, &root_paths))
+}
+
+/// Returns one page of the duplicate groups of the last result.
+///
+/// The last completed scan, fdupes import or reopened session is kept in
+/// the backend, so large results don't have to cross IPC in one piece.
+/// Files deleted through [`delete_files`] are left out.
+///
+/// # Arguments
+/// * `query` - Filters (path substring or wildcard, extensions, size range,
+///   minimum copies), sort key and order, and page bounds
+#[tauri::command]
+pub async fn query_groups(
+    query: GroupQuery,
+    state: State<'_, AppState>,
+) -> Result<GroupPage, String> {
+    let result = state
+        .last_result()
+        .ok_or_else(|| "No scan results to query".to_string())?;
+
+    Ok(select_groups(&result.duplicate_groups, &query))
+}
+
+/// Writes a scan result as a JSON, CSV, HTML or fdupes report.
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `format` - Versioned JSON, flat CSV, static HTML or fdupes text
+/// * `result` - The scan result to export (None = the last result kept in the backend)
+#[tauri::command]
+pub async fn export_report(
+    path: String,
+    format: ReportFormat,
+    result: Option<ScanResult>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let result = result_or_last(result, &state)?;
+    info!(
+        "Exporting {} duplicate groups to {} ({:?})",
+        result.duplicate_groups.len(),
+        path,
+        format
+    );
+
+    Ok(write_report(Path::new(&path), format, &result)?)
+}
+
+/// Imports duplicate groups from `fdupes`/`jdupes` output.
+///
+/// Listed files are re-hashed and only files that still match are kept.
+/// The result is emitted like a finished scan so it can be reviewed and acted
+/// on in the UI; progress is reported with the scan events, and
+/// [`cancel_scan`] stops it. As with [`load_session`], state left by the last
+/// scan is cleared and the import's reference roots become active.
+///
+/// # Arguments
+/// * `path` - File holding the fdupes/jdupes output
+/// * `options` - Hash algorithm and hashing limits
+#[tauri::command]
+pub async fn import_fdupes(
+    path: String,
+    options: ScanOptions,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ScanResult, String> {
+    info!("Importing fdupes output from {}", path);
+
+    let state = state.inner();
+    state
+        .try_start_scan()
+        .ok_or_else(|| String::from(ScannerError::ScanInProgress))?;
+
+    let result = run_import(&path, &options, &app_handle, state).map(|result| {
+        // Like a reopened session, the import replaces the last scan entirely
+        state.set_reference_roots(ReferenceRoots::new(&options.reference_roots));
+        state.set_last_hashes(None);
+        state.set_directory_listing(None);
+        keep_result(state, result, &options)
+    });
+    state.finish_scan();
+
+    match &result {
+        Ok(result) => {
+            let _ = app_handle.emit(events::SCAN_FINISHED, result);
+        }
+        Err(e) => {
+            let _ = app_handle.emit(events::SCAN_ERROR, e.clone());
+        }
+    }
+    result
+}
+
+/// Runs an fdupes import; the caller owns the scan slot in `state`.
+fn run_import(
+    path: &str,
+    options: &ScanOptions,
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<ScanResult, String> {
+    let start_time = Instant::now();
+    let text = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+    let groups = parse_fdupes(&text);
+
+    let total: u64 = groups.iter().map(|g| g.len() as u64).sum();
+    emit_progress(app_handle, 0, Some(total), ScanPhase::Hashing, None);
+    let throttle = ProgressThrottle::new();
+
+    let (groups, listed, errors) = verify_groups(
+        groups,
+        &HashOptions::from_scan_options(options),
+        |_, count| {
+            if throttle.ready() {
+                emit_progress(app_handle, count, Some(total), ScanPhase::Hashing, None);
+            }
+        },
+        || state.is_cancel_requested(),
+    );
+
+    if state.is_cancel_requested() {
+        let _ = app_handle.emit(events::SCAN_CANCELLED, ());
+        return Err(ScannerError::Cancelled.into());
+    }
+
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    info!(
+        "Import complete in {}ms: {} of {} listed files confirmed in {} groups",
+        duration_ms,
+        groups.iter().map(|g| g.files.len()).sum::<usize>(),
+        listed,
+        groups.len()
+    );
+
+    emit_progress(app_handle, total, Some(total), ScanPhase::Complete, None);
+    Ok(ScanResult::new(groups, listed, errors, duration_ms)
+        .with_hash_algorithm(options.hash_algorithm))
+}
+
+/// Verifies files against a baseline manifest.
+///
+/// Re-hashes every file the baseline lists and reports files whose content
+/// changed (separating likely bit rot from ordinary edits), files that went
+/// missing, and files under `options.root_paths` the baseline doesn't list.
+/// Progress is reported with the scan events, and [`cancel_scan`] stops it.
+///
+/// # Arguments
+/// * `baseline_path` - JSON manifest or checksum list to verify against
+/// * `options` - Roots and filters used to find new files, and hashing limits
+#[tauri::command]
+pub async fn verify_baseline(
+    baseline_path: String,
+    options: ScanOptions,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<VerifyResult, String> {
+    info!("Verifying against baseline {}", baseline_path);
+
+    let state = state.inner();
+    state
+        .try_start_scan()
+        .ok_or_else(|| String::from(ScannerError::ScanInProgress))?;
+
+    let result = run_verify(&baseline_path, &options, &app_handle, state);
+    state.finish_scan();
+
+    if let Err(e) = &result {
+        let _ = app_handle.emit(events::SCAN_ERROR, e.clone());
+    }
+    result
+}
+
+/// Runs a verification; the caller owns the scan slot in `state`.
+fn run_verify(
+    baseline_path: &str,
+    options: &ScanOptions,
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<VerifyResult, String> {
+    let start_time = Instant::now();
+    let manifest = load_manifest(Path::new(baseline_path))?;
+
+    emit_progress(app_handle, 0, None, ScanPhase::Counting, None);
+
+    let scanned = if options.root_paths.is_empty() {
+        ScanOutput::new()
+    } else {
+        scan_directories(options)?
+    };
+
+    if state.is_cancel_requested() {
+        let _ = app_handle.emit(events::SCAN_CANCELLED, ());
+        return Err(ScannerError::Cancelled.into());
+    }
+
+    let total = manifest.entries.len() as u64;
+    let throttle = ProgressThrottle::new();
+
+    let mut result = verify_manifest(
+        &manifest,
+        &scanned.files,
+        &HashOptions::from_scan_options(options),
+        |_, count| {
+            if throttle.ready() {
+                emit_progress(app_handle, count, Some(total), ScanPhase::Hashing, None);
+            }
+        },
+        || state.is_cancel_requested(),
+    );
+
+    if state.is_cancel_requested() {
+        let _ = app_handle.emit(events::SCAN_CANCELLED, ());
+        return Err(ScannerError::Cancelled.into());
+    }
+
+    result.errors.extend(scanned.errors);
+    result.duration_ms = start_time.elapsed().as_millis() as u64;
+
+    info!(
+        "Verification complete in {}ms: {} corrupted, {} modified, {} missing, {} new",
+        result.duration_ms,
+        result.corrupted.len(),
+        result.modified.len(),
+        result.missing.len(),
+        result.new_files.len()
+    );
+
+    emit_progress(app_handle, total, Some(total), ScanPhase::Complete, None);
+    Ok(result)
+}
+
 /// Opens a folder selection dialog and returns the selected paths.
 #[tauri::command]
 pub async fn select_folders(app_handle: AppHandle) -> Result<Vec<String>, String> {
@@ -292,6 +1216,152 @@ pub async fn select_folders(app_handle: AppHandle) -> Result<Vec<String>, String
     }
 }
 
+/// Returns the checkpoint store in the app data directory.
+fn checkpoint_store(app_handle: &AppHandle) -> Result<CheckpointStore, ScannerError> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| ScannerError::Checkpoint(e.to_string()))?;
+
+    Ok(CheckpointStore::new(dir.join(CHECKPOINT_DIR)))
+}
+
+/// Keeps a finished result for `query_groups`, and leaves the groups out of
+/// the copy sent to the frontend if `page_results` is set.
+///
+/// With paging, the groups are moved into the kept result rather than
+/// cloned, so they are held only once.
+fn keep_result(state: &AppState, mut result: ScanResult, options: &ScanOptions) -> ScanResult {
+    if !options.page_results {
+        state.set_last_result(Some(result.clone()));
+        return result;
+    }
+
+    let groups = std::mem::take(&mut result.duplicate_groups);
+    let view = result.clone();
+    result.duplicate_groups = groups;
+    state.set_last_result(Some(result));
+    view
+}
+
+/// Returns the given result, or the last one kept in the backend if the
+/// frontend only holds a paged copy.
+fn result_or_last(result: Option<ScanResult>, state: &AppState) -> Result<ScanResult, String> {
+    match result {
+        Some(result) => Ok(result),
+        None => state
+            .last_result()
+            .map(|result| (*result).clone())
+            .ok_or_else(|| "No scan results available".to_string()),
+    }
+}
+
+/// Returns the session store in the app data directory.
+fn session_store(app_handle: &AppHandle) -> Result<SessionStore, ScannerError> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| ScannerError::Session(e.to_string()))?;
+
+    Ok(SessionStore::new(dir.join(SESSION_DIR)))
+}
+
+/// Returns the scan history store in the app data directory.
+fn history_store(app_handle: &AppHandle) -> Result<HistoryStore, ScannerError> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| ScannerError::History(e.to_string()))?;
+
+    Ok(HistoryStore::new(dir.join(HISTORY_FILE)))
+}
+
+/// Adds a completed scan to the history. Failures are logged and otherwise
+/// ignored, since the history must never fail the scan itself.
+fn record_history(
+    app_handle: &AppHandle,
+    scan_id: &str,
+    options: &ScanOptions,
+    result: &ScanResult,
+) {
+    let finished_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let entry = ScanHistoryEntry::from_scan(scan_id, finished_at, options, result);
+
+    if let Err(e) = history_store(app_handle).and_then(|store| store.record(entry)) {
+        warn!("Failed to record scan {} in history: {}", scan_id, e);
+    }
+}
+
+/// Generates a unique session ID from the current time.
+fn generate_session_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    format!("session_{}", timestamp)
+}
+
+/// Removes the checkpoint of a scan that finished or was cancelled.
+fn discard_checkpoint(writer: &Option<CheckpointWriter>) {
+    if let Some(writer) = writer {
+        writer.discard();
+    }
+}
+
+/// Ends the scan if cancellation was requested.
+///
+/// Tells the frontend, removes the scan's checkpoint and frees the scan slot
+/// in `state`, so the caller only has to return the error.
+fn stop_if_cancelled(
+    state: &AppState,
+    app_handle: &AppHandle,
+    writer: &Option<CheckpointWriter>,
+) -> Result<(), String> {
+    if !state.is_cancel_requested() {
+        return Ok(());
+    }
+
+    let _ = app_handle.emit(events::SCAN_CANCELLED, ());
+    discard_checkpoint(writer);
+    state.finish_scan();
+    Err(ScannerError::Cancelled.into())
+}
+
+/// Limits progress events to one per [`PROGRESS_RATE_LIMIT_MS`].
+///
+/// Safe to share across hashing worker threads.
+#[derive(Debug, Default)]
+struct ProgressThrottle {
+    /// Time of the last allowed event, in milliseconds since the Unix epoch.
+    last_emit: AtomicU64,
+}
+
+impl ProgressThrottle {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if enough time has passed since the last event, and
+    /// counts this call as the new last event.
+    fn ready(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        if now.saturating_sub(self.last_emit.load(Ordering::Relaxed)) >= PROGRESS_RATE_LIMIT_MS {
+            self.last_emit.store(now, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+}
+
 /// Emits a progress event to the frontend.
 fn emit_progress(
     app_handle: &AppHandle,
@@ -319,5 +1389,14 @@ mod tests {
         assert_eq!(events::SCAN_FINISHED, "scan_finished");
         assert_eq!(events::SCAN_ERROR, "scan_error");
         assert_eq!(events::SCAN_CANCELLED, "scan_cancelled");
+        assert_eq!(events::DUPLICATE_GROUP_FOUND, "duplicate_group_found");
+        assert_eq!(events::DUPLICATE_GROUP_SUMMARY, "duplicate_group_summary");
+    }
+
+    #[test]
+    fn test_progress_throttle() {
+        let throttle = ProgressThrottle::new();
+        assert!(throttle.ready());
+        assert!(!throttle.ready());
     }
 }
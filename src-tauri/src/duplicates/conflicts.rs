@@ -0,0 +1,287 @@
+//! Name conflict detection.
+//!
+//! The inverse of duplicate detection: files that share a file name, or the
+//! same path relative to their scan root, but not their content. Merging the
+//! folders holding them would overwrite one version with another.
+//!
+//! Only files that share both a name and a size are hashed; a different size
+//! already proves the content differs.
+
+use crate::hasher::{hash_files_parallel_observed, HashOptions};
+use crate::types::{ConflictVersion, FileEntry, NameConflict, NameConflictKey, ScanError};
+use log::debug;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Finds files that share a name but differ in content.
+///
+/// # Arguments
+/// * `files` - All scanned files
+/// * `roots` - Scan roots, used to compute relative paths
+/// * `key` - Whether to match by file name or by relative path
+/// * `options` - Hashing options of the scan
+/// * `known_hashes` - Hashes the scan already computed with `options.algorithm`
+/// * `is_cancelled` - Returns true if the scan was cancelled
+///
+/// # Returns
+/// Conflicts sorted by name, plus files that could not be hashed. Files that
+/// could not be hashed are left out of their conflict.
+pub fn find_name_conflicts<C>(
+    files: &[FileEntry],
+    roots: &[String],
+    key: NameConflictKey,
+    options: &HashOptions,
+    known_hashes: &[(FileEntry, String)],
+    is_cancelled: C,
+) -> (Vec<NameConflict>, Vec<ScanError>)
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+
+    let mut by_name: HashMap<String, Vec<&FileEntry>> = HashMap::new();
+    for file in files {
+        if let Some(name) = conflict_key(Path::new(&file.path), key, &roots) {
+            by_name.entry(name).or_default().push(file);
+        }
+    }
+    by_name.retain(|_, files| files.len() > 1);
+
+    // Files sharing name and size need a hash to tell them apart
+    let known: HashMap<&str, &str> = known_hashes
+        .iter()
+        .map(|(file, hash)| (file.path.as_str(), hash.as_str()))
+        .collect();
+    let mut hashes: HashMap<String, String> = HashMap::new();
+    let mut to_hash = Vec::new();
+    for group in by_name.values() {
+        let shared = shared_sizes(group);
+        for file in group {
+            if !shared.contains(&file.size) {
+                continue;
+            }
+            match known.get(file.path.as_str()) {
+                Some(hash) => {
+                    hashes.insert(file.path.clone(), hash.to_string());
+                }
+                None => to_hash.push((*file).clone()),
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    for result in hash_files_parallel_observed(to_hash, options, |_, _| {}, &is_cancelled) {
+        match result.hash {
+            Ok(hash) => {
+                hashes.insert(result.file.path, hash);
+            }
+            Err(message) => errors.push(ScanError::new(result.file.path, message)),
+        }
+    }
+
+    let mut conflicts: Vec<NameConflict> = by_name
+        .into_iter()
+        .filter_map(|(name, group)| {
+            let versions = split_versions(&group, &hashes);
+            (versions.len() > 1).then(|| NameConflict::new(name, versions))
+        })
+        .collect();
+    conflicts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    debug!("Found {} name conflicts", conflicts.len());
+    (conflicts, errors)
+}
+
+/// Returns the name files are matched by, or None if the file isn't under a root.
+fn conflict_key(path: &Path, key: NameConflictKey, roots: &[PathBuf]) -> Option<String> {
+    match key {
+        NameConflictKey::FileName => path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+        NameConflictKey::RelativePath => roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .and_then(|root| path.strip_prefix(root).ok())
+            .map(|rel| rel.display().to_string()),
+    }
+}
+
+/// Splits files with the same name into versions with identical content.
+///
+/// Files that share a size with another but have no hash (hashing failed)
+/// are left out.
+fn split_versions(group: &[&FileEntry], hashes: &HashMap<String, String>) -> Vec<ConflictVersion> {
+    let shared = shared_sizes(group);
+    let mut versions: HashMap<(u64, Option<&str>), Vec<FileEntry>> = HashMap::new();
+    for file in group {
+        let hash = hashes.get(&file.path).map(String::as_str);
+        if shared.contains(&file.size) && hash.is_none() {
+            continue;
+        }
+        versions
+            .entry((file.size, hash))
+            .or_default()
+            .push((*file).clone());
+    }
+
+    let mut versions: Vec<ConflictVersion> = versions
+        .into_iter()
+        .map(|((size, hash), mut files)| {
+            files.sort_by(|a, b| {
+                Reverse(modified_secs(a))
+                    .cmp(&Reverse(modified_secs(b)))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            ConflictVersion::new(hash.map(str::to_string), size, files)
+        })
+        .collect();
+    versions.sort_by(|a, b| {
+        Reverse(modified_secs(&a.files[0]))
+            .cmp(&Reverse(modified_secs(&b.files[0])))
+            .then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+    versions
+}
+
+/// Returns the non-zero sizes held by more than one file of the group.
+///
+/// Empty files need no hash: they all have the same content.
+fn shared_sizes(group: &[&FileEntry]) -> HashSet<u64> {
+    let mut seen = HashSet::new();
+    group
+        .iter()
+        .filter(|f| f.size > 0 && !seen.insert(f.size))
+        .map(|f| f.size)
+        .collect()
+}
+
+fn modified_secs(file: &FileEntry) -> Option<u64> {
+    file.modified.as_deref().and_then(|m| m.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, rel: &str, content: &[u8], modified: u64) -> FileEntry {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        FileEntry::new(
+            path.display().to_string(),
+            content.len() as u64,
+            Some(modified.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_same_name_different_content() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![
+            write(dir.path(), "a/notes.txt", b"version one", 100),
+            write(dir.path(), "b/notes.txt", b"version two", 200),
+            write(dir.path(), "c/notes.txt", b"version one", 50),
+            write(dir.path(), "a/same.txt", b"identical", 100),
+            write(dir.path(), "b/same.txt", b"identical", 100),
+            write(dir.path(), "a/unique.txt", b"only one", 100),
+        ];
+
+        let (conflicts, errors) = find_name_conflicts(
+            &files,
+            &[dir.path().display().to_string()],
+            NameConflictKey::FileName,
+            &HashOptions::default(),
+            &[],
+            || false,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.name, "notes.txt");
+        assert_eq!(conflict.versions.len(), 2);
+        // Newest version first
+        assert_eq!(conflict.versions[0].files, vec![files[1].clone()]);
+        assert_eq!(
+            conflict.versions[1].files,
+            vec![files[0].clone(), files[2].clone()]
+        );
+        assert!(conflict.versions[0].hash.is_some());
+    }
+
+    #[test]
+    fn test_different_sizes_need_no_hash() {
+        // Paths don't exist: hashing them would fail
+        let files = vec![
+            FileEntry::new("/left/report.pdf".to_string(), 10, None),
+            FileEntry::new("/right/report.pdf".to_string(), 20, None),
+        ];
+
+        let (conflicts, errors) = find_name_conflicts(
+            &files,
+            &["/left".to_string(), "/right".to_string()],
+            NameConflictKey::FileName,
+            &HashOptions::default(),
+            &[],
+            || false,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].versions.iter().all(|v| v.hash.is_none()));
+    }
+
+    #[test]
+    fn test_relative_path_key() {
+        let files = vec![
+            FileEntry::new("/left/docs/a.txt".to_string(), 10, None),
+            FileEntry::new("/right/docs/a.txt".to_string(), 20, None),
+            FileEntry::new("/right/other/a.txt".to_string(), 30, None),
+        ];
+
+        let (conflicts, _) = find_name_conflicts(
+            &files,
+            &["/left".to_string(), "/right".to_string()],
+            NameConflictKey::RelativePath,
+            &HashOptions::default(),
+            &[],
+            || false,
+        );
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].name,
+            Path::new("docs").join("a.txt").display().to_string()
+        );
+        assert_eq!(conflicts[0].versions.len(), 2);
+    }
+
+    #[test]
+    fn test_reuses_known_hashes() {
+        let files = vec![
+            FileEntry::new("/left/a.bin".to_string(), 10, None),
+            FileEntry::new("/right/a.bin".to_string(), 10, None),
+        ];
+        let known = vec![
+            (files[0].clone(), "h1".to_string()),
+            (files[1].clone(), "h2".to_string()),
+        ];
+
+        let (conflicts, errors) = find_name_conflicts(
+            &files,
+            &[],
+            NameConflictKey::FileName,
+            &HashOptions::default(),
+            &known,
+            || false,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].versions.len(), 2);
+    }
+}
@@ -0,0 +1,552 @@
+//! Duplicate directory detection.
+//!
+//! Computes a Merkle-style content hash for every directory from the hashes
+//! and sizes of the files below it, then reports directories with identical
+//! contents and directories whose contents are fully contained in another.
+//!
+//! Directory hashes depend only on content, not on file or directory names:
+//! two folders holding the same files in the same sub-folder layout match
+//! even if entries were renamed.
+
+use crate::types::{DirectorySubset, DuplicateDirectoryGroup, FileEntry};
+use log::debug;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Result of duplicate directory analysis.
+#[derive(Debug, Default)]
+pub struct DirectoryAnalysis {
+    /// Groups of directories with identical contents.
+    pub groups: Vec<DuplicateDirectoryGroup>,
+
+    /// Directories whose contents all exist in another directory.
+    pub subsets: Vec<DirectorySubset>,
+}
+
+/// Files and subdirectories directly inside one directory.
+#[derive(Debug, Default)]
+struct DirNode {
+    /// (interned hash ID if hashed, size) for each file.
+    files: Vec<(Option<usize>, u64)>,
+
+    /// Immediate subdirectories that contain files.
+    children: BTreeSet<PathBuf>,
+}
+
+/// Aggregated contents of a directory, including everything below it.
+#[derive(Debug)]
+struct DirSummary {
+    /// Merkle hash, or None if some file below could not be hashed.
+    merkle: Option<String>,
+
+    /// Total size of all files below.
+    total_size: u64,
+
+    /// Number of files below.
+    file_count: u64,
+
+    /// Number of occurrences of each hashed file below, by interned hash ID.
+    contents: HashMap<usize, u64>,
+}
+
+/// Finds directories with identical contents and directories contained in others.
+///
+/// Only size-matched files are hashed during a scan, so a directory holding a
+/// file with a unique size has no Merkle hash. That never hides a real match:
+/// a unique file has no copy anywhere, so such a directory can't be a
+/// duplicate or a subset of another directory. Directories holding only
+/// zero-byte files are not reported either.
+///
+/// # Arguments
+/// * `files` - All files found by the scan
+/// * `hashes` - Successfully hashed files with their content hash
+/// * `roots` - Scan root directories; directories above them are not considered
+///
+/// # Returns
+/// Duplicate directory groups (sorted by wasted space, descending) and
+/// subset relations (sorted by subset size, descending).
+pub fn find_duplicate_directories(
+    files: &[FileEntry],
+    hashes: &[(FileEntry, String)],
+    roots: &[String],
+) -> DirectoryAnalysis {
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+
+    // Intern hashes so per-directory content maps stay small
+    let mut hash_ids: HashMap<&str, usize> = HashMap::new();
+    let mut hash_names: Vec<&str> = Vec::new();
+    let mut hash_by_path: HashMap<&str, usize> = HashMap::new();
+    for (file, hash) in hashes {
+        let id = *hash_ids.entry(hash.as_str()).or_insert_with(|| {
+            hash_names.push(hash.as_str());
+            hash_names.len() - 1
+        });
+        hash_by_path.insert(file.path.as_str(), id);
+    }
+
+    // Empty files aren't hashed, but all of them have the same content
+    for file in files.iter().filter(|f| f.size == 0) {
+        let id = *hash_ids.entry("").or_insert_with(|| {
+            hash_names.push("");
+            hash_names.len() - 1
+        });
+        hash_by_path.insert(file.path.as_str(), id);
+    }
+
+    let nodes = build_tree(files, &hash_by_path, &roots);
+    let summaries = summarize(&nodes, &hash_names);
+
+    let groups = identical_groups(&summaries);
+    let subsets = subset_relations(&summaries);
+
+    debug!(
+        "Directory analysis: {} directories, {} duplicate groups, {} subsets",
+        summaries.len(),
+        groups.len(),
+        subsets.len()
+    );
+
+    DirectoryAnalysis { groups, subsets }
+}
+
+/// Builds the directory tree below each root from the scanned file list.
+fn build_tree(
+    files: &[FileEntry],
+    hash_by_path: &HashMap<&str, usize>,
+    roots: &[PathBuf],
+) -> HashMap<PathBuf, DirNode> {
+    let mut nodes: HashMap<PathBuf, DirNode> = HashMap::new();
+
+    for file in files {
+        let path = Path::new(&file.path);
+
+        // Attribute the file to the innermost root containing it
+        let Some(root) = roots
+            .iter()
+            .filter(|r| path.starts_with(r))
+            .max_by_key(|r| r.components().count())
+        else {
+            continue;
+        };
+        let Some(parent) = path.parent() else {
+            continue;
+        };
+
+        let hash = hash_by_path.get(file.path.as_str()).copied();
+        nodes
+            .entry(parent.to_path_buf())
+            .or_default()
+            .files
+            .push((hash, file.size));
+
+        // Link every directory up to the root to its parent
+        let mut dir = parent;
+        while dir != root.as_path() {
+            let Some(up) = dir.parent() else {
+                break;
+            };
+            let inserted = nodes
+                .entry(up.to_path_buf())
+                .or_default()
+                .children
+                .insert(dir.to_path_buf());
+            if !inserted {
+                break; // The rest of the chain is already linked
+            }
+            dir = up;
+        }
+    }
+
+    nodes
+}
+
+/// Computes summaries bottom-up, deepest directories first.
+fn summarize(
+    nodes: &HashMap<PathBuf, DirNode>,
+    hash_names: &[&str],
+) -> HashMap<PathBuf, DirSummary> {
+    let mut order: Vec<&PathBuf> = nodes.keys().collect();
+    order.sort_by_key(|p| std::cmp::Reverse(p.components().count()));
+
+    let mut summaries: HashMap<PathBuf, DirSummary> = HashMap::with_capacity(nodes.len());
+
+    for dir in order {
+        let node = &nodes[dir];
+        let mut complete = true;
+        let mut entries: Vec<String> = Vec::with_capacity(node.files.len() + node.children.len());
+        let mut total_size = 0;
+        let mut file_count = 0;
+        let mut contents: HashMap<usize, u64> = HashMap::new();
+
+        for &(hash, size) in &node.files {
+            total_size += size;
+            file_count += 1;
+            match hash {
+                Some(id) => {
+                    entries.push(format!("f:{}:{}", hash_names[id], size));
+                    *contents.entry(id).or_default() += 1;
+                }
+                None => complete = false,
+            }
+        }
+
+        for child in &node.children {
+            let summary = &summaries[child];
+            total_size += summary.total_size;
+            file_count += summary.file_count;
+            for (&id, &count) in &summary.contents {
+                *contents.entry(id).or_default() += count;
+            }
+            match &summary.merkle {
+                Some(merkle) => entries.push(format!("d:{}", merkle)),
+                None => complete = false,
+            }
+        }
+
+        let merkle = complete.then(|| {
+            entries.sort_unstable();
+            let mut hasher = blake3::Hasher::new();
+            for entry in &entries {
+                hasher.update(entry.as_bytes());
+                hasher.update(b"\n");
+            }
+            hasher.finalize().to_hex().to_string()
+        });
+
+        summaries.insert(
+            dir.clone(),
+            DirSummary {
+                merkle,
+                total_size,
+                file_count,
+                contents,
+            },
+        );
+    }
+
+    summaries
+}
+
+/// Groups directories by Merkle hash.
+///
+/// A group is dropped when the parents of all its directories are themselves
+/// duplicates of each other, since the parent group already covers it.
+fn identical_groups(summaries: &HashMap<PathBuf, DirSummary>) -> Vec<DuplicateDirectoryGroup> {
+    let mut by_merkle: HashMap<&str, Vec<&PathBuf>> = HashMap::new();
+    for (dir, summary) in summaries {
+        if let Some(merkle) = &summary.merkle {
+            // Folders of zero-byte files waste nothing and match each other trivially
+            if summary.total_size > 0 {
+                by_merkle.entry(merkle.as_str()).or_default().push(dir);
+            }
+        }
+    }
+    by_merkle.retain(|_, dirs| dirs.len() > 1);
+
+    let merkle_of =
+        |dir: &Path| -> Option<&str> { summaries.get(dir).and_then(|s| s.merkle.as_deref()) };
+
+    let mut groups: Vec<DuplicateDirectoryGroup> = by_merkle
+        .iter()
+        .filter(|(_, dirs)| {
+            let parent_merkles: Vec<Option<&str>> = dirs
+                .iter()
+                .map(|d| d.parent().and_then(merkle_of))
+                .collect();
+            let first = parent_merkles[0];
+            let covered_by_parents = first.is_some_and(|m| by_merkle.contains_key(m))
+                && parent_merkles.iter().all(|m| *m == first);
+            !covered_by_parents
+        })
+        .map(|(merkle, dirs)| {
+            let summary = &summaries[dirs[0]];
+            let mut directories: Vec<String> =
+                dirs.iter().map(|d| d.display().to_string()).collect();
+            directories.sort();
+            DuplicateDirectoryGroup::new(
+                merkle.to_string(),
+                summary.total_size,
+                summary.file_count,
+                directories,
+            )
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.wasted_space()
+            .cmp(&a.wasted_space())
+            .then_with(|| a.directories.cmp(&b.directories))
+    });
+    groups
+}
+
+/// Finds directories whose contents are all present in another, larger directory.
+///
+/// Relations implied by a reported ancestor (a subfolder of a subset is also a
+/// subset of the same superset) and relations between a directory and its own
+/// ancestors or descendants are not reported.
+fn subset_relations(summaries: &HashMap<PathBuf, DirSummary>) -> Vec<DirectorySubset> {
+    // Inverted index: hash ID -> directories containing it
+    let mut dirs_by_hash: HashMap<usize, Vec<&PathBuf>> = HashMap::new();
+    for (dir, summary) in summaries {
+        for &id in summary.contents.keys() {
+            dirs_by_hash.entry(id).or_default().push(dir);
+        }
+    }
+
+    // Shallow directories first so ancestors are reported before descendants
+    let mut candidates: Vec<(&PathBuf, &DirSummary)> = summaries
+        .iter()
+        .filter(|(_, s)| s.merkle.is_some() && s.total_size > 0)
+        .collect();
+    candidates.sort_by(|a, b| {
+        a.0.components()
+            .count()
+            .cmp(&b.0.components().count())
+            .then_with(|| a.0.cmp(b.0))
+    });
+
+    let mut reported: HashMap<&Path, Vec<&Path>> = HashMap::new();
+    let mut subsets = Vec::new();
+
+    for (dir, summary) in candidates {
+        // Look up supersets through the least common hash in this directory
+        let Some(rarest) = summary
+            .contents
+            .keys()
+            .min_by_key(|id| dirs_by_hash.get(id).map_or(0, Vec::len))
+        else {
+            continue;
+        };
+
+        let mut supersets: Vec<&PathBuf> = dirs_by_hash[rarest]
+            .iter()
+            .copied()
+            .filter(|other| {
+                let other_summary = &summaries[*other];
+                !dir.starts_with(other)
+                    && !other.starts_with(dir)
+                    && other_summary.file_count > summary.file_count
+                    && summary.contents.iter().all(|(id, count)| {
+                        other_summary.contents.get(id).copied().unwrap_or(0) >= *count
+                    })
+            })
+            .collect();
+        supersets.sort();
+
+        for superset in supersets {
+            let implied = dir.ancestors().skip(1).any(|ancestor| {
+                reported
+                    .get(ancestor)
+                    .is_some_and(|sups| sups.iter().any(|s| superset.starts_with(s)))
+            });
+            if implied {
+                continue;
+            }
+
+            reported
+                .entry(dir.as_path())
+                .or_default()
+                .push(superset.as_path());
+            subsets.push(DirectorySubset::new(
+                dir.display().to_string(),
+                superset.display().to_string(),
+                summary.total_size,
+                summary.file_count,
+            ));
+        }
+    }
+
+    subsets.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.subset.cmp(&b.subset))
+            .then_with(|| a.superset.cmp(&b.superset))
+    });
+    subsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64) -> FileEntry {
+        FileEntry::new(path.to_string(), size, None)
+    }
+
+    fn hashed(path: &str, size: u64, hash: &str) -> (FileEntry, String) {
+        (file(path, size), hash.to_string())
+    }
+
+    fn analyze(entries: &[(FileEntry, String)], unhashed: &[FileEntry]) -> DirectoryAnalysis {
+        let mut files: Vec<FileEntry> = entries.iter().map(|(f, _)| f.clone()).collect();
+        files.extend(unhashed.iter().cloned());
+        find_duplicate_directories(&files, entries, &["/root".to_string()])
+    }
+
+    #[test]
+    fn test_identical_directories() {
+        let entries = vec![
+            hashed("/root/Photos/2019/a.jpg", 100, "ha"),
+            hashed("/root/Photos/2019/b.jpg", 200, "hb"),
+            hashed("/root/Backup/Photos/2019/a.jpg", 100, "ha"),
+            hashed("/root/Backup/Photos/2019/b.jpg", 200, "hb"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+
+        // Photos/2019 == Backup/Photos/2019, and so are their parents Photos
+        // and Backup/Photos, so only the outermost pair is reported.
+        assert_eq!(analysis.groups.len(), 1);
+        let group = &analysis.groups[0];
+        assert_eq!(
+            group.directories,
+            vec![
+                "/root/Backup/Photos".to_string(),
+                "/root/Photos".to_string()
+            ]
+        );
+        assert_eq!(group.total_size, 300);
+        assert_eq!(group.file_count, 2);
+        assert_eq!(group.wasted_space(), 300);
+    }
+
+    #[test]
+    fn test_names_do_not_affect_directory_hash() {
+        let entries = vec![
+            hashed("/root/a/x.txt", 10, "h1"),
+            hashed("/root/b/renamed.txt", 10, "h1"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+        assert_eq!(analysis.groups.len(), 1);
+    }
+
+    #[test]
+    fn test_different_contents_not_grouped() {
+        let entries = vec![
+            hashed("/root/a/x.txt", 10, "h1"),
+            hashed("/root/a/y.txt", 10, "h2"),
+            hashed("/root/b/x.txt", 10, "h1"),
+            hashed("/root/b/y.txt", 10, "h3"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+        assert!(analysis.groups.is_empty());
+    }
+
+    #[test]
+    fn test_unhashed_file_excludes_directory() {
+        let entries = vec![
+            hashed("/root/a/x.txt", 10, "h1"),
+            hashed("/root/b/x.txt", 10, "h1"),
+        ];
+        let unhashed = vec![file("/root/b/unique.bin", 12345)];
+
+        let analysis = analyze(&entries, &unhashed);
+
+        assert!(analysis.groups.is_empty());
+        // a's contents are still fully contained in b
+        assert_eq!(analysis.subsets.len(), 1);
+        assert_eq!(analysis.subsets[0].subset, "/root/a");
+        assert_eq!(analysis.subsets[0].superset, "/root/b");
+    }
+
+    #[test]
+    fn test_empty_files_match_without_hash() {
+        let entries = vec![
+            hashed("/root/a/x.txt", 10, "h1"),
+            hashed("/root/b/x.txt", 10, "h1"),
+        ];
+        let empty = vec![file("/root/a/.keep", 0), file("/root/b/.keep", 0)];
+
+        let analysis = analyze(&entries, &empty);
+
+        assert_eq!(analysis.groups.len(), 1);
+        assert_eq!(analysis.groups[0].file_count, 2);
+    }
+
+    #[test]
+    fn test_directories_of_empty_files_not_reported() {
+        let empty = vec![
+            file("/root/a/.keep", 0),
+            file("/root/b/.keep", 0),
+            file("/root/c/.keep", 0),
+            file("/root/c/.gitkeep", 0),
+        ];
+
+        let analysis = analyze(&[], &empty);
+
+        assert!(analysis.groups.is_empty());
+        assert!(analysis.subsets.is_empty());
+    }
+
+    #[test]
+    fn test_subset_detection() {
+        let entries = vec![
+            hashed("/root/small/sub/x.txt", 10, "h1"),
+            hashed("/root/small/y.txt", 20, "h2"),
+            hashed("/root/big/x.txt", 10, "h1"),
+            hashed("/root/big/deep/y.txt", 20, "h2"),
+            hashed("/root/big/z.txt", 30, "h3"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+
+        assert!(analysis.groups.is_empty());
+        // small ⊂ big is reported; small/sub ⊂ big is implied and suppressed.
+        // big/deep (just y) is also contained in small.
+        let pairs: Vec<(&str, &str)> = analysis
+            .subsets
+            .iter()
+            .map(|s| (s.subset.as_str(), s.superset.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("/root/small", "/root/big"),
+                ("/root/big/deep", "/root/small")
+            ]
+        );
+        assert_eq!(analysis.subsets[0].total_size, 30);
+        assert_eq!(analysis.subsets[0].file_count, 2);
+    }
+
+    #[test]
+    fn test_subset_respects_multiplicity() {
+        let entries = vec![
+            hashed("/root/a/1.txt", 10, "h1"),
+            hashed("/root/a/2.txt", 10, "h1"),
+            hashed("/root/b/1.txt", 10, "h1"),
+            hashed("/root/b/other.txt", 10, "h2"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+
+        // a holds two copies of h1, b only one
+        assert!(analysis.subsets.iter().all(|s| s.subset != "/root/a"));
+    }
+
+    #[test]
+    fn test_files_directly_in_root_ignored_for_grouping() {
+        let entries = vec![
+            hashed("/root/x.txt", 10, "h1"),
+            hashed("/root/y.txt", 10, "h1"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+        assert!(analysis.groups.is_empty());
+        assert!(analysis.subsets.is_empty());
+    }
+
+    #[test]
+    fn test_files_outside_roots_ignored() {
+        let entries = vec![
+            hashed("/elsewhere/a/x.txt", 10, "h1"),
+            hashed("/elsewhere/b/x.txt", 10, "h1"),
+        ];
+
+        let analysis = analyze(&entries, &[]);
+        assert!(analysis.groups.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! Folder-level breakdown of duplicate files.
+//!
+//! Duplicate files are added up per folder at every level of the tree below
+//! each scan root, so the folders holding most of the wasted space can be
+//! found by drilling down (or drawn as a treemap) instead of reading groups
+//! one by one. Only folders that hold duplicates appear in the tree.
+
+use crate::types::{DuplicateGroup, FolderSummary};
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Totals of one folder while the tree is being built.
+#[derive(Debug, Default)]
+struct FolderNode {
+    duplicate_files: u64,
+    duplicate_bytes: u64,
+    wasted_space: u64,
+    children: BTreeMap<String, FolderNode>,
+}
+
+impl FolderNode {
+    fn add(&mut self, size: u64, wasted: u64) {
+        self.duplicate_files += 1;
+        self.duplicate_bytes += size;
+        self.wasted_space += wasted;
+    }
+
+    fn into_summary(self, path: PathBuf, name: String) -> FolderSummary {
+        let mut children: Vec<FolderSummary> = self
+            .children
+            .into_iter()
+            .map(|(child, node)| node.into_summary(path.join(&child), child))
+            .collect();
+        sort_folders(&mut children);
+
+        FolderSummary {
+            path: path.display().to_string(),
+            name,
+            duplicate_files: self.duplicate_files,
+            duplicate_bytes: self.duplicate_bytes,
+            wasted_space: self.wasted_space,
+            children,
+        }
+    }
+}
+
+/// Adds up duplicate files per folder, one tree per scan root.
+///
+/// Each file counts toward the deepest root containing it. Files outside
+/// every root (for instance from an imported fdupes list) are placed under
+/// their filesystem root.
+///
+/// # Arguments
+/// * `groups` - Duplicate groups, keeper first
+/// * `roots` - Scan roots
+///
+/// # Returns
+/// One tree per root holding duplicates, most wasted space first.
+pub fn summarize_folders(groups: &[DuplicateGroup], roots: &[String]) -> Vec<FolderSummary> {
+    let mut trees: BTreeMap<PathBuf, FolderNode> = BTreeMap::new();
+
+    for group in groups {
+        for (position, file) in group.files.iter().enumerate() {
+            let path = Path::new(&file.path);
+            let Some(folder) = path.parent() else {
+                continue;
+            };
+
+            let root = roots
+                .iter()
+                .map(Path::new)
+                .filter(|root| path.starts_with(root))
+                .max_by_key(|root| root.as_os_str().len())
+                .or_else(|| folder.ancestors().last())
+                .unwrap_or(folder);
+            let wasted = if position == 0 { 0 } else { file.size };
+
+            let mut node = trees.entry(root.to_path_buf()).or_default();
+            node.add(file.size, wasted);
+            for component in folder.strip_prefix(root).unwrap_or(Path::new("")) {
+                node = node
+                    .children
+                    .entry(component.to_string_lossy().into_owned())
+                    .or_default();
+                node.add(file.size, wasted);
+            }
+        }
+    }
+
+    let mut summaries: Vec<FolderSummary> = trees
+        .into_iter()
+        .map(|(root, node)| {
+            let name = root.display().to_string();
+            node.into_summary(root, name)
+        })
+        .collect();
+    sort_folders(&mut summaries);
+
+    debug!("Summarized duplicates under {} roots", summaries.len());
+    summaries
+}
+
+/// Orders folders by wasted space, then duplicate bytes (descending), then path.
+fn sort_folders(folders: &mut [FolderSummary]) {
+    folders.sort_by(|a, b| {
+        b.wasted_space
+            .cmp(&a.wasted_space)
+            .then_with(|| b.duplicate_bytes.cmp(&a.duplicate_bytes))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileEntry;
+
+    fn group(size: u64, paths: &[&str]) -> DuplicateGroup {
+        let files = paths
+            .iter()
+            .map(|p| FileEntry::new(p.to_string(), size, None))
+            .collect();
+        DuplicateGroup::new("hash".to_string(), size, files)
+    }
+
+    #[test]
+    fn test_summarize_folders_by_level() {
+        let groups = vec![
+            group(100, &["/data/keep/a", "/data/mess/x/a", "/data/mess/y/a"]),
+            group(10, &["/data/mess/b", "/data/keep/b"]),
+        ];
+
+        let trees = summarize_folders(&groups, &["/data".to_string()]);
+
+        assert_eq!(trees.len(), 1);
+        let root = &trees[0];
+        assert_eq!(root.path, "/data");
+        assert_eq!(root.name, "/data");
+        assert_eq!(root.duplicate_files, 5);
+        assert_eq!(root.duplicate_bytes, 320);
+        assert_eq!(root.wasted_space, 210);
+
+        let names: Vec<_> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["mess", "keep"]);
+
+        let mess = &root.children[0];
+        assert_eq!(mess.path, "/data/mess");
+        assert_eq!(mess.duplicate_files, 3);
+        assert_eq!(mess.wasted_space, 200);
+        assert_eq!(mess.children.len(), 2);
+        assert_eq!(mess.children[0].path, "/data/mess/x");
+        assert!(mess.children[0].children.is_empty());
+
+        let keep = &root.children[1];
+        assert_eq!(keep.duplicate_files, 2);
+        assert_eq!(keep.duplicate_bytes, 110);
+        assert_eq!(keep.wasted_space, 10);
+    }
+
+    #[test]
+    fn test_files_go_to_deepest_root() {
+        let groups = vec![group(10, &["/a/one", "/a/b/two", "/elsewhere/three"])];
+        let roots = vec!["/a".to_string(), "/a/b".to_string()];
+
+        let trees = summarize_folders(&groups, &roots);
+
+        let paths: Vec<_> = trees
+            .iter()
+            .map(|t| (t.path.as_str(), t.duplicate_files))
+            .collect();
+        assert_eq!(paths, vec![("/", 1), ("/a/b", 1), ("/a", 1)]);
+        assert_eq!(trees[0].children[0].path, "/elsewhere");
+    }
+}
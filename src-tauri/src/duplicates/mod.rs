@@ -1,13 +1,36 @@
 //! Duplicate detection and grouping module.
 //!
-//! Groups files by their content hash to identify duplicates.
-
+//! Groups files by their content hash to identify duplicates, and audio
+//! files by their tag-independent payload hash. The submodules compare
+//! directory trees, summarize and page through the groups, and narrow them
+//! to reference roots.
+
+pub mod conflicts;
+pub mod directories;
+pub mod folders;
+pub mod overlap;
+pub mod query;
+pub mod reference;
+pub mod stream;
+
+pub use conflicts::find_name_conflicts;
+pub use directories::find_duplicate_directories;
+pub use folders::summarize_folders;
+pub use overlap::overlap_matrix;
+pub use query::select_groups;
+pub use reference::ReferenceRoots;
+pub use stream::SizeBuckets;
+
+use crate::hasher::audio::AudioHash;
 use crate::types::{DuplicateGroup, FileEntry};
 use log::debug;
 use std::collections::HashMap;
 
 /// Finds duplicate files by grouping them by hash.
 ///
+/// Empty files are skipped: all of them share one hash, and they are
+/// reported separately rather than as one large group.
+///
 /// # Arguments
 /// * `files_with_hashes` - List of (FileEntry, hash) tuples
 ///
@@ -19,7 +42,9 @@ pub fn find_duplicates(files_with_hashes: Vec<(FileEntry, String)>) -> Vec<Dupli
     let mut hash_groups: HashMap<String, Vec<FileEntry>> = HashMap::new();
 
     for (file, hash) in files_with_hashes {
-        hash_groups.entry(hash).or_default().push(file);
+        if file.size > 0 {
+            hash_groups.entry(hash).or_default().push(file);
+        }
     }
 
     // Convert to DuplicateGroups, filtering single-file groups
@@ -44,6 +69,52 @@ pub fn find_duplicates(files_with_hashes: Vec<(FileEntry, String)>) -> Vec<Dupli
     groups
 }
 
+/// Finds audio files with identical audio payloads.
+///
+/// Groups whose files differ outside the payload (tags, cover art) are
+/// flagged with `metadata_differs`, and their size is that of the smallest
+/// file, so wasted space is never overstated.
+///
+/// # Arguments
+/// * `files_with_hashes` - List of (FileEntry, AudioHash) tuples
+///
+/// # Returns
+/// A vector of DuplicateGroups keyed by payload hash, sorted by wasted space
+/// (descending). Only groups with 2+ files are included.
+pub fn find_audio_duplicates(
+    files_with_hashes: Vec<(FileEntry, AudioHash)>,
+) -> Vec<DuplicateGroup> {
+    let mut payload_groups: HashMap<String, Vec<(FileEntry, String)>> = HashMap::new();
+
+    for (file, hash) in files_with_hashes {
+        payload_groups
+            .entry(hash.payload_hash)
+            .or_default()
+            .push((file, hash.metadata_hash));
+    }
+
+    let mut groups: Vec<DuplicateGroup> = payload_groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(hash, files)| {
+            let metadata_differs = files.iter().any(|(_, m)| *m != files[0].1);
+            let size = files.iter().map(|(f, _)| f.size).min().unwrap_or(0);
+            let files = files.into_iter().map(|(f, _)| f).collect();
+            DuplicateGroup::new(hash, size, files).with_metadata_differs(metadata_differs)
+        })
+        .collect();
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.wasted_space()));
+
+    debug!(
+        "Found {} audio duplicate groups ({} with differing metadata)",
+        groups.len(),
+        groups.iter().filter(|g| g.metadata_differs).count()
+    );
+
+    groups
+}
+
 /// Calculates total wasted space across all duplicate groups.
 ///
 /// # Arguments
@@ -117,6 +188,16 @@ mod tests {
         assert!(groups.is_empty());
     }
 
+    #[test]
+    fn test_find_duplicates_skips_empty_files() {
+        let files = vec![
+            (file("/a.txt", 0), "empty".to_string()),
+            (file("/b.txt", 0), "empty".to_string()),
+        ];
+
+        assert!(find_duplicates(files).is_empty());
+    }
+
     #[test]
     fn test_find_duplicates_no_duplicates() {
         let files = vec![
@@ -168,6 +249,31 @@ mod tests {
         assert_eq!(groups[2].hash, "small");
     }
 
+    #[test]
+    fn test_find_audio_duplicates() {
+        let audio = |payload: &str, metadata: &str| AudioHash {
+            payload_hash: payload.to_string(),
+            metadata_hash: metadata.to_string(),
+            payload_size: 1000,
+        };
+        let files = vec![
+            (file("/tagged.mp3", 1200), audio("track", "tags-a")),
+            (file("/retagged.mp3", 1100), audio("track", "tags-b")),
+            (file("/copy1.flac", 900), audio("other", "same")),
+            (file("/copy2.flac", 900), audio("other", "same")),
+            (file("/unique.ogg", 500), audio("unique", "x")),
+        ];
+
+        let groups = find_audio_duplicates(files);
+
+        assert_eq!(groups.len(), 2);
+        let track = groups.iter().find(|g| g.hash == "track").unwrap();
+        assert!(track.metadata_differs);
+        assert_eq!(track.size, 1100);
+        let other = groups.iter().find(|g| g.hash == "other").unwrap();
+        assert!(!other.metadata_differs);
+    }
+
     #[test]
     fn test_calculate_total_wasted_space() {
         let groups = vec![
@@ -0,0 +1,202 @@
+//! Content overlap between scanned roots.
+//!
+//! For every pair of roots the matrix holds how many distinct files (by
+//! content) both contain and how many bytes that is. Compared with a root's
+//! total size it shows which backups fully overlap. When a single root is
+//! scanned, its top-level folders are compared instead.
+
+use crate::types::{DuplicateGroup, FileEntry, FolderOverlap, OverlapFolder, OverlapMatrix};
+use log::debug;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Computes the content shared between each pair of roots.
+///
+/// # Arguments
+/// * `groups` - Duplicate groups of the scan
+/// * `files` - All scanned files, used for each folder's totals
+/// * `roots` - Scan roots; with only one, its top-level folders are compared
+///
+/// # Returns
+/// The matrix, or None if there are fewer than two folders to compare.
+pub fn overlap_matrix(
+    groups: &[DuplicateGroup],
+    files: &[FileEntry],
+    roots: &[String],
+) -> Option<OverlapMatrix> {
+    let single_root = match roots {
+        [root] => Some(Path::new(root)),
+        _ => None,
+    };
+    let folder_of = |path: &str| -> Option<PathBuf> {
+        let path = Path::new(path);
+        match single_root {
+            Some(root) => {
+                // Files directly in the root belong to no top-level folder
+                let mut components = path.strip_prefix(root).ok()?.components();
+                let first = components.next()?;
+                components.next()?;
+                Some(root.join(first))
+            }
+            None => roots
+                .iter()
+                .map(Path::new)
+                .filter(|root| path.starts_with(root))
+                .max_by_key(|root| root.as_os_str().len())
+                .map(Path::to_path_buf),
+        }
+    };
+
+    let mut totals: BTreeMap<PathBuf, (u64, u64)> = BTreeMap::new();
+    if single_root.is_none() {
+        for root in roots {
+            totals.entry(PathBuf::from(root)).or_default();
+        }
+    }
+    for file in files {
+        if let Some(folder) = folder_of(&file.path) {
+            let (count, bytes) = totals.entry(folder).or_default();
+            *count += 1;
+            *bytes += file.size;
+        }
+    }
+
+    if totals.len() < 2 {
+        return None;
+    }
+
+    let mut shared: BTreeMap<(PathBuf, PathBuf), (u64, u64)> = BTreeMap::new();
+    for group in groups {
+        let folders: Vec<PathBuf> = group
+            .files
+            .iter()
+            .filter_map(|f| folder_of(&f.path))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        for (i, a) in folders.iter().enumerate() {
+            for b in &folders[i + 1..] {
+                let (count, bytes) = shared.entry((a.clone(), b.clone())).or_default();
+                *count += 1;
+                *bytes += group.size;
+            }
+        }
+    }
+
+    let mut pairs: Vec<FolderOverlap> = shared
+        .into_iter()
+        .map(|((a, b), (shared_files, shared_bytes))| FolderOverlap {
+            folder_a: a.display().to_string(),
+            folder_b: b.display().to_string(),
+            shared_files,
+            shared_bytes,
+        })
+        .collect();
+    // Stable sort keeps pairs with equal sizes in path order
+    pairs.sort_by_key(|p| std::cmp::Reverse(p.shared_bytes));
+
+    let folders: Vec<OverlapFolder> = totals
+        .into_iter()
+        .map(|(path, (total_files, total_bytes))| OverlapFolder {
+            path: path.display().to_string(),
+            total_files,
+            total_bytes,
+        })
+        .collect();
+
+    debug!(
+        "Overlap matrix: {} folders, {} overlapping pairs",
+        folders.len(),
+        pairs.len()
+    );
+    Some(OverlapMatrix { folders, pairs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64) -> FileEntry {
+        FileEntry::new(path.to_string(), size, None)
+    }
+
+    fn group(size: u64, paths: &[&str]) -> DuplicateGroup {
+        let files = paths.iter().map(|p| file(p, size)).collect();
+        DuplicateGroup::new("hash".to_string(), size, files)
+    }
+
+    #[test]
+    fn test_overlap_between_roots() {
+        let files = vec![
+            file("/a/x", 100),
+            file("/a/y", 10),
+            file("/b/x", 100),
+            file("/b/x copy", 100),
+            file("/c/y", 10),
+        ];
+        let groups = vec![
+            group(100, &["/a/x", "/b/x", "/b/x copy"]),
+            group(10, &["/a/y", "/c/y"]),
+        ];
+        let roots: Vec<String> = ["/a", "/b", "/c", "/d"]
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+
+        let matrix = overlap_matrix(&groups, &files, &roots).unwrap();
+
+        let folders: Vec<_> = matrix
+            .folders
+            .iter()
+            .map(|f| (f.path.as_str(), f.total_files, f.total_bytes))
+            .collect();
+        assert_eq!(
+            folders,
+            vec![("/a", 2, 110), ("/b", 2, 200), ("/c", 1, 10), ("/d", 0, 0)]
+        );
+
+        assert_eq!(
+            matrix.pairs,
+            vec![
+                FolderOverlap {
+                    folder_a: "/a".to_string(),
+                    folder_b: "/b".to_string(),
+                    shared_files: 1,
+                    shared_bytes: 100,
+                },
+                FolderOverlap {
+                    folder_a: "/a".to_string(),
+                    folder_b: "/c".to_string(),
+                    shared_files: 1,
+                    shared_bytes: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_root_compares_top_level_folders() {
+        let files = vec![
+            file("/data/2023/p.jpg", 50),
+            file("/data/backup/2023/p.jpg", 50),
+            file("/data/loose.txt", 5),
+        ];
+        let groups = vec![group(50, &["/data/2023/p.jpg", "/data/backup/2023/p.jpg"])];
+
+        let matrix = overlap_matrix(&groups, &files, &["/data".to_string()]).unwrap();
+
+        assert_eq!(matrix.folders.len(), 2);
+        assert_eq!(matrix.pairs.len(), 1);
+        assert_eq!(matrix.pairs[0].folder_a, "/data/2023");
+        assert_eq!(matrix.pairs[0].folder_b, "/data/backup");
+        assert_eq!(matrix.pairs[0].shared_bytes, 50);
+    }
+
+    #[test]
+    fn test_no_matrix_for_one_folder() {
+        let files = vec![file("/data/only/a", 1)];
+
+        assert!(overlap_matrix(&[], &files, &["/data".to_string()]).is_none());
+    }
+}
@@ -0,0 +1,275 @@
+//! Filtering, sorting and paging of duplicate groups.
+//!
+//! Large scans produce hundreds of thousands of groups, more than the UI can
+//! take in one IPC payload. The backend keeps the full result and the UI
+//! requests one page at a time, with filters and sorting applied here.
+
+use crate::scanner::filter::FileFilter;
+use crate::types::{DuplicateGroup, FileEntry, GroupPage, GroupQuery, GroupSortKey, SortOrder};
+use log::debug;
+use std::path::Path;
+
+/// Number of groups per page when the query doesn't set a limit.
+pub const DEFAULT_PAGE_SIZE: u64 = 100;
+
+/// Value a group is sorted by; every group of one query uses the same variant.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue<'a> {
+    Number(u64),
+    Text(&'a str),
+}
+
+/// Returns one page of the groups matching a query.
+///
+/// # Arguments
+/// * `groups` - All duplicate groups of the result
+/// * `query` - Filters, sort order and page bounds
+///
+/// # Returns
+/// The requested page, with the number and wasted space of all matching groups.
+pub fn select_groups(groups: &[DuplicateGroup], query: &GroupQuery) -> GroupPage {
+    let file_filter = FileFilter::new().with_include_extensions(query.extensions.clone());
+    let path_contains = query.path_contains.as_ref().map(|p| p.to_lowercase());
+
+    let file_matches = |file: &FileEntry| {
+        path_contains
+            .as_ref()
+            .is_none_or(|p| file.path.to_lowercase().contains(p))
+            && query
+                .path_glob
+                .as_ref()
+                .is_none_or(|g| wildcard_match(g, &file.path))
+            && file_filter.matches(Path::new(&file.path), file.size)
+    };
+
+    let mut matching: Vec<(SortValue, &DuplicateGroup)> = groups
+        .iter()
+        .filter(|g| query.min_size.is_none_or(|min| g.size >= min))
+        .filter(|g| query.max_size.is_none_or(|max| g.size <= max))
+        .filter(|g| {
+            query
+                .min_copies
+                .is_none_or(|min| g.files.len() as u64 >= min)
+        })
+        .filter(|g| g.files.iter().any(file_matches))
+        .map(|g| (sort_value(g, query.sort_by), g))
+        .collect();
+
+    let order = query.order.unwrap_or(match query.sort_by {
+        GroupSortKey::Path => SortOrder::Ascending,
+        _ => SortOrder::Descending,
+    });
+    matching.sort_by(|(value_a, a), (value_b, b)| {
+        let ordering = match order {
+            SortOrder::Ascending => value_a.cmp(value_b),
+            SortOrder::Descending => value_b.cmp(value_a),
+        };
+        ordering.then_with(|| a.hash.cmp(&b.hash))
+    });
+
+    let total_groups = matching.len() as u64;
+    let total_wasted_space = matching.iter().map(|(_, g)| g.wasted_space()).sum();
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let page: Vec<DuplicateGroup> = matching
+        .into_iter()
+        .skip(query.offset as usize)
+        .take(limit as usize)
+        .map(|(_, g)| g.clone())
+        .collect();
+
+    debug!(
+        "Query matched {} of {} groups, returning {} from offset {}",
+        total_groups,
+        groups.len(),
+        page.len(),
+        query.offset
+    );
+
+    GroupPage {
+        groups: page,
+        offset: query.offset,
+        total_groups,
+        total_wasted_space,
+    }
+}
+
+/// Extracts the value a group is sorted by.
+fn sort_value(group: &DuplicateGroup, key: GroupSortKey) -> SortValue<'_> {
+    match key {
+        GroupSortKey::WastedSpace => SortValue::Number(group.wasted_space()),
+        GroupSortKey::Size => SortValue::Number(group.size),
+        GroupSortKey::Count => SortValue::Number(group.files.len() as u64),
+        GroupSortKey::Path => SortValue::Text(
+            group
+                .files
+                .iter()
+                .map(|f| f.path.as_str())
+                .min()
+                .unwrap_or(""),
+        ),
+        GroupSortKey::Modified => SortValue::Number(
+            group
+                .files
+                .iter()
+                .filter_map(|f| f.modified.as_deref()?.parse().ok())
+                .max()
+                .unwrap_or(0),
+        ),
+    }
+}
+
+/// Matches text against a pattern where `*` matches any run of characters
+/// and `?` matches exactly one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(hash: &str, size: u64, files: &[(&str, u64)]) -> DuplicateGroup {
+        let files = files
+            .iter()
+            .map(|(path, modified)| {
+                FileEntry::new(path.to_string(), size, Some(modified.to_string()))
+            })
+            .collect();
+        DuplicateGroup::new(hash.to_string(), size, files)
+    }
+
+    fn sample() -> Vec<DuplicateGroup> {
+        vec![
+            group("a", 1000, &[("/photos/a.jpg", 10), ("/backup/a.jpg", 20)]),
+            group(
+                "b",
+                10,
+                &[("/docs/b.txt", 5), ("/backup/b.txt", 6), ("/old/b.txt", 7)],
+            ),
+            group("c", 500, &[("/music/c.mp3", 30), ("/backup/c.mp3", 1)]),
+        ]
+    }
+
+    fn hashes(page: &GroupPage) -> Vec<&str> {
+        page.groups.iter().map(|g| g.hash.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_query_sorts_by_wasted_space() {
+        let page = select_groups(&sample(), &GroupQuery::default());
+
+        assert_eq!(hashes(&page), vec!["a", "c", "b"]);
+        assert_eq!(page.total_groups, 3);
+        assert_eq!(page.total_wasted_space, 1520);
+    }
+
+    #[test]
+    fn test_sort_keys_and_order() {
+        let groups = sample();
+        let query = |sort_by, order| GroupQuery {
+            sort_by,
+            order,
+            ..Default::default()
+        };
+
+        let by_count = select_groups(&groups, &query(GroupSortKey::Count, None));
+        assert_eq!(hashes(&by_count)[0], "b");
+
+        let by_path = select_groups(&groups, &query(GroupSortKey::Path, None));
+        assert_eq!(hashes(&by_path), vec!["a", "b", "c"]);
+
+        let by_modified = select_groups(&groups, &query(GroupSortKey::Modified, None));
+        assert_eq!(hashes(&by_modified), vec!["c", "a", "b"]);
+
+        let smallest = select_groups(
+            &groups,
+            &query(GroupSortKey::Size, Some(SortOrder::Ascending)),
+        );
+        assert_eq!(hashes(&smallest), vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let groups = sample();
+
+        let photos = GroupQuery {
+            path_contains: Some("PHOTOS".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(hashes(&select_groups(&groups, &photos)), vec!["a"]);
+
+        let glob = GroupQuery {
+            path_glob: Some("/old/*.txt".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(hashes(&select_groups(&groups, &glob)), vec!["b"]);
+
+        let audio = GroupQuery {
+            extensions: vec![".MP3".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(hashes(&select_groups(&groups, &audio)), vec!["c"]);
+
+        let sized = GroupQuery {
+            min_size: Some(100),
+            max_size: Some(600),
+            ..Default::default()
+        };
+        assert_eq!(hashes(&select_groups(&groups, &sized)), vec!["c"]);
+
+        let copies = GroupQuery {
+            min_copies: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(hashes(&select_groups(&groups, &copies)), vec!["b"]);
+    }
+
+    #[test]
+    fn test_pagination() {
+        let groups = sample();
+        let query = GroupQuery {
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let page = select_groups(&groups, &query);
+
+        assert_eq!(hashes(&page), vec!["c"]);
+        assert_eq!(page.offset, 1);
+        assert_eq!(page.total_groups, 3);
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.jpg", "/a/b/photo.jpg"));
+        assert!(wildcard_match("/a/*/c?.txt", "/a/b/x/c1.txt"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("*.jpg", "/a/photo.jpeg"));
+        assert!(!wildcard_match("/a/?", "/a/bc"));
+    }
+}
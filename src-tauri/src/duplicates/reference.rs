@@ -0,0 +1,207 @@
+//! Reference-set filtering.
+//!
+//! Files under a reference root (an archive, a master copy) are never
+//! candidates for deletion. With reference roots configured, the scan
+//! answers "which of my other files already exist in the reference set?":
+//! only groups that mix reference and non-reference files are reported.
+
+use crate::types::{DirectorySubset, DuplicateDirectoryGroup, DuplicateGroup, SimilarityGroup};
+use std::path::{Path, PathBuf};
+
+/// Directories whose files are treated as the reference copies.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceRoots {
+    roots: Vec<PathBuf>,
+}
+
+impl ReferenceRoots {
+    /// Creates a reference set from root paths.
+    pub fn new(roots: &[String]) -> Self {
+        Self {
+            roots: roots.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    /// Returns true if no reference roots are configured.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns true if `path` is a reference root or lies inside one.
+    pub fn contains(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        self.roots.iter().any(|root| path.starts_with(root))
+    }
+
+    /// Keeps groups with both reference and non-reference files.
+    ///
+    /// Files are marked with `is_reference` and reference files are moved
+    /// to the front, so the file the UI keeps is always a reference copy.
+    /// Without reference roots, groups are returned unchanged.
+    pub fn filter_groups(&self, groups: Vec<DuplicateGroup>) -> Vec<DuplicateGroup> {
+        if self.is_empty() {
+            return groups;
+        }
+
+        groups
+            .into_iter()
+            .filter_map(|mut group| {
+                for file in &mut group.files {
+                    file.is_reference = self.contains(&file.path);
+                }
+                if !is_mixed(group.files.iter().map(|f| f.is_reference)) {
+                    return None;
+                }
+                // Stable sort keeps the existing order within each side
+                group.files.sort_by_key(|f| !f.is_reference);
+                Some(group)
+            })
+            .collect()
+    }
+
+    /// Keeps directory groups with both reference and non-reference
+    /// directories, and subsets whose superset is a reference directory
+    /// while the subset is not.
+    pub fn filter_directories(
+        &self,
+        groups: Vec<DuplicateDirectoryGroup>,
+        subsets: Vec<DirectorySubset>,
+    ) -> (Vec<DuplicateDirectoryGroup>, Vec<DirectorySubset>) {
+        if self.is_empty() {
+            return (groups, subsets);
+        }
+
+        let groups = groups
+            .into_iter()
+            .filter(|g| is_mixed(g.directories.iter().map(|d| self.contains(d))))
+            .collect();
+        let subsets = subsets
+            .into_iter()
+            .filter(|s| self.contains(&s.superset) && !self.contains(&s.subset))
+            .collect();
+
+        (groups, subsets)
+    }
+
+    /// Keeps similarity groups with both reference and non-reference files,
+    /// marking the reference files.
+    pub fn filter_similar(&self, groups: Vec<SimilarityGroup>) -> Vec<SimilarityGroup> {
+        if self.is_empty() {
+            return groups;
+        }
+
+        groups
+            .into_iter()
+            .filter_map(|mut group| {
+                for member in &mut group.members {
+                    member.file.is_reference = self.contains(&member.file.path);
+                }
+                is_mixed(group.members.iter().map(|m| m.file.is_reference)).then_some(group)
+            })
+            .collect()
+    }
+}
+
+/// Returns true if the flags contain both a reference and a non-reference entry.
+fn is_mixed(mut is_reference: impl Iterator<Item = bool> + Clone) -> bool {
+    is_reference.clone().any(|r| r) && is_reference.any(|r| !r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileEntry;
+
+    fn group(hash: &str, paths: &[&str]) -> DuplicateGroup {
+        DuplicateGroup::new(
+            hash.to_string(),
+            100,
+            paths
+                .iter()
+                .map(|p| FileEntry::new(p.to_string(), 100, None))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_contains_matches_whole_components() {
+        let reference = ReferenceRoots::new(&["/archive".to_string()]);
+
+        assert!(reference.contains("/archive"));
+        assert!(reference.contains("/archive/2020/photo.jpg"));
+        assert!(!reference.contains("/archive-old/photo.jpg"));
+        assert!(!reference.contains("/downloads/photo.jpg"));
+    }
+
+    #[test]
+    fn test_filter_groups_keeps_mixed_groups() {
+        let reference = ReferenceRoots::new(&["/archive".to_string()]);
+        let groups = vec![
+            group(
+                "mixed",
+                &["/downloads/a.jpg", "/archive/a.jpg", "/downloads/b.jpg"],
+            ),
+            group("only-downloads", &["/downloads/c.jpg", "/downloads/d.jpg"]),
+            group("only-archive", &["/archive/e.jpg", "/archive/f.jpg"]),
+        ];
+
+        let filtered = reference.filter_groups(groups);
+
+        assert_eq!(filtered.len(), 1);
+        let files = &filtered[0].files;
+        assert_eq!(files[0].path, "/archive/a.jpg");
+        assert!(files[0].is_reference);
+        assert_eq!(files[1].path, "/downloads/a.jpg");
+        assert_eq!(files[2].path, "/downloads/b.jpg");
+        assert!(files[1..].iter().all(|f| !f.is_reference));
+    }
+
+    #[test]
+    fn test_filter_groups_without_reference_roots() {
+        let groups = vec![group("h", &["/a", "/b"])];
+
+        let filtered = ReferenceRoots::default().filter_groups(groups.clone());
+
+        assert_eq!(filtered, groups);
+    }
+
+    #[test]
+    fn test_filter_directories() {
+        let reference = ReferenceRoots::new(&["/archive".to_string()]);
+        let groups = vec![
+            DuplicateDirectoryGroup::new(
+                "mixed".to_string(),
+                10,
+                1,
+                vec!["/archive/x".to_string(), "/downloads/x".to_string()],
+            ),
+            DuplicateDirectoryGroup::new(
+                "local".to_string(),
+                10,
+                1,
+                vec!["/downloads/y".to_string(), "/downloads/z".to_string()],
+            ),
+        ];
+        let subsets = vec![
+            DirectorySubset::new(
+                "/downloads/x".to_string(),
+                "/archive/all".to_string(),
+                10,
+                1,
+            ),
+            DirectorySubset::new(
+                "/archive/x".to_string(),
+                "/downloads/all".to_string(),
+                10,
+                1,
+            ),
+        ];
+
+        let (groups, subsets) = reference.filter_directories(groups, subsets);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].hash, "mixed");
+        assert_eq!(subsets.len(), 1);
+        assert_eq!(subsets[0].subset, "/downloads/x");
+    }
+}
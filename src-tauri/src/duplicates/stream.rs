@@ -0,0 +1,164 @@
+//! Confirming duplicate groups while hashing is still running.
+//!
+//! Files can only be duplicates of files of the same size, so a size bucket
+//! is final as soon as all of its files are hashed. The hasher works through
+//! the buckets one by one, largest first, so their groups can be shown for
+//! review long before the scan ends. A bucket whose files lie on several
+//! devices completes once every device has hashed its part.
+
+use crate::duplicates::find_duplicates;
+use crate::hasher::HashResult;
+use crate::types::{DuplicateGroup, FileEntry};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Files of one size that are still being hashed.
+#[derive(Debug, Default)]
+struct SizeBucket {
+    /// Files not hashed yet.
+    remaining: usize,
+
+    /// Files hashed so far, with their hashes.
+    hashed: Vec<(FileEntry, String)>,
+}
+
+/// Tracks size buckets during hashing and hands out their groups once complete.
+///
+/// Safe to share across hashing worker threads.
+#[derive(Debug)]
+pub struct SizeBuckets {
+    buckets: Mutex<HashMap<u64, SizeBucket>>,
+}
+
+impl SizeBuckets {
+    /// Creates the buckets for a hashing run.
+    ///
+    /// # Arguments
+    /// * `to_hash` - Files about to be hashed
+    /// * `reused` - Files whose hash is already known (e.g. from a checkpoint)
+    pub fn new<'a>(
+        to_hash: impl IntoIterator<Item = &'a FileEntry>,
+        reused: impl IntoIterator<Item = &'a (FileEntry, String)>,
+    ) -> Self {
+        let mut buckets: HashMap<u64, SizeBucket> = HashMap::new();
+        for file in to_hash {
+            buckets.entry(file.size).or_default().remaining += 1;
+        }
+        for (file, hash) in reused {
+            buckets
+                .entry(file.size)
+                .or_default()
+                .hashed
+                .push((file.clone(), hash.clone()));
+        }
+
+        Self {
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    /// Takes the groups of buckets that need no hashing at all.
+    pub fn take_complete(&self) -> Vec<DuplicateGroup> {
+        let mut buckets = self.buckets.lock();
+        let complete: Vec<u64> = buckets
+            .iter()
+            .filter(|(_, bucket)| bucket.remaining == 0)
+            .map(|(&size, _)| size)
+            .collect();
+
+        complete
+            .into_iter()
+            .filter_map(|size| buckets.remove(&size))
+            .flat_map(|bucket| find_duplicates(bucket.hashed))
+            .collect()
+    }
+
+    /// Records a finished hash.
+    ///
+    /// Files that failed to hash still complete their bucket; they just
+    /// aren't part of any group.
+    ///
+    /// # Returns
+    /// The groups of the file's size bucket if this was its last file,
+    /// otherwise an empty vector.
+    pub fn record(&self, result: &HashResult) -> Vec<DuplicateGroup> {
+        let size = result.file.size;
+        let complete = {
+            let mut buckets = self.buckets.lock();
+            let Some(bucket) = buckets.get_mut(&size) else {
+                return Vec::new();
+            };
+            if let Ok(hash) = &result.hash {
+                bucket.hashed.push((result.file.clone(), hash.clone()));
+            }
+            bucket.remaining = bucket.remaining.saturating_sub(1);
+            if bucket.remaining > 0 {
+                return Vec::new();
+            }
+            buckets.remove(&size)
+        };
+
+        // Group outside the lock so other workers aren't held up
+        complete
+            .map(|bucket| find_duplicates(bucket.hashed))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64) -> FileEntry {
+        FileEntry::new(path.to_string(), size, None)
+    }
+
+    #[test]
+    fn test_bucket_completes_with_last_file() {
+        let to_hash = vec![file("/a", 10), file("/b", 10), file("/c", 20)];
+        let buckets = SizeBuckets::new(&to_hash, &[]);
+
+        assert!(buckets.take_complete().is_empty());
+        assert!(buckets
+            .record(&HashResult::success(file("/a", 10), "h".to_string()))
+            .is_empty());
+        assert!(buckets
+            .record(&HashResult::success(file("/c", 20), "x".to_string()))
+            .is_empty());
+
+        let groups = buckets.record(&HashResult::success(file("/b", 10), "h".to_string()));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_failed_hash_completes_bucket() {
+        let to_hash = vec![file("/a", 10), file("/b", 10), file("/c", 10)];
+        let buckets = SizeBuckets::new(&to_hash, &[]);
+
+        buckets.record(&HashResult::success(file("/a", 10), "h".to_string()));
+        buckets.record(&HashResult::success(file("/b", 10), "h".to_string()));
+        let groups = buckets.record(&HashResult::failure(file("/c", 10), "denied".to_string()));
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_reused_hashes() {
+        let reused = vec![
+            (file("/a", 10), "h".to_string()),
+            (file("/b", 10), "h".to_string()),
+            (file("/c", 20), "x".to_string()),
+        ];
+        let buckets = SizeBuckets::new(&[file("/d", 20)], &reused);
+
+        let complete = buckets.take_complete();
+        assert_eq!(complete.len(), 1);
+        assert_eq!(complete[0].size, 10);
+
+        let groups = buckets.record(&HashResult::success(file("/d", 20), "x".to_string()));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].size, 20);
+    }
+}
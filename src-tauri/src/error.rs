@@ -48,6 +48,26 @@ pub enum ScannerError {
     /// File disappeared during scanning.
     #[error("File no longer exists: {0}")]
     FileDisappeared(String),
+
+    /// Failed to read or write a scan checkpoint.
+    #[error("Checkpoint error: {0}")]
+    Checkpoint(String),
+
+    /// Failed to read or parse a hash manifest.
+    #[error("Manifest error: {0}")]
+    Manifest(String),
+
+    /// Failed to write a scan report.
+    #[error("Report error: {0}")]
+    Report(String),
+
+    /// Failed to save, load or list a scan session.
+    #[error("Session error: {0}")]
+    Session(String),
+
+    /// Failed to read or update the scan history.
+    #[error("History error: {0}")]
+    History(String),
 }
 
 impl ScannerError {
@@ -75,6 +95,11 @@ impl ScannerError {
             ScannerError::DeleteFailed(p) => format!("Could not delete: {}", p),
             ScannerError::TrashFailed(p) => format!("Could not move to trash: {}", p),
             ScannerError::FileDisappeared(p) => format!("File was removed: {}", p),
+            ScannerError::Checkpoint(m) => format!("Could not access saved scan: {}", m),
+            ScannerError::Manifest(m) => format!("Could not read manifest: {}", m),
+            ScannerError::Report(m) => format!("Could not write report: {}", m),
+            ScannerError::Session(m) => format!("Could not access saved session: {}", m),
+            ScannerError::History(m) => format!("Could not access scan history: {}", m),
         }
     }
 }
@@ -124,6 +149,11 @@ impl From<ScannerError> for ErrorResponse {
             ScannerError::DeleteFailed(_) => "DELETE_FAILED",
             ScannerError::TrashFailed(_) => "TRASH_FAILED",
             ScannerError::FileDisappeared(_) => "FILE_DISAPPEARED",
+            ScannerError::Checkpoint(_) => "CHECKPOINT_ERROR",
+            ScannerError::Manifest(_) => "MANIFEST_ERROR",
+            ScannerError::Report(_) => "REPORT_ERROR",
+            ScannerError::Session(_) => "SESSION_ERROR",
+            ScannerError::History(_) => "HISTORY_ERROR",
         };
 
         let path = match &err {
@@ -244,6 +274,47 @@ mod tests {
         assert!(response.path.is_none());
     }
 
+    #[test]
+    fn test_checkpoint_error_response() {
+        let err = ScannerError::Checkpoint("scan_1 not found".to_string());
+        let response: ErrorResponse = err.into();
+
+        assert_eq!(response.code, "CHECKPOINT_ERROR");
+        assert!(response.message.contains("scan_1"));
+        assert!(response.path.is_none());
+    }
+
+    #[test]
+    fn test_report_error_response() {
+        let err = ScannerError::Report("/out/report.html: denied".to_string());
+        let response: ErrorResponse = err.into();
+
+        assert_eq!(response.code, "REPORT_ERROR");
+        assert!(response.message.starts_with("Could not write report"));
+    }
+
+    #[test]
+    fn test_session_error_response() {
+        let err = ScannerError::Session("session_1 not found".to_string());
+        let response: ErrorResponse = err.into();
+
+        assert_eq!(response.code, "SESSION_ERROR");
+        assert!(response
+            .message
+            .starts_with("Could not access saved session"));
+    }
+
+    #[test]
+    fn test_history_error_response() {
+        let err = ScannerError::History("expected value at line 1".to_string());
+        let response: ErrorResponse = err.into();
+
+        assert_eq!(response.code, "HISTORY_ERROR");
+        assert!(response
+            .message
+            .starts_with("Could not access scan history"));
+    }
+
     #[test]
     fn test_scanner_error_to_string() {
         let err = ScannerError::ScanInProgress;
@@ -0,0 +1,127 @@
+//! Content hash algorithms supported by the hasher.
+//!
+//! Wraps each algorithm behind a common streaming interface so read
+//! strategies don't need to know which digest they are feeding.
+
+use crate::types::HashAlgorithm;
+use md5::{Digest, Md5};
+use sha2::Sha256;
+
+/// A streaming hasher for one of the supported algorithms.
+pub enum ContentHasher {
+    /// MD5 digest.
+    Md5(Md5),
+
+    /// SHA-256 digest.
+    Sha256(Sha256),
+
+    /// BLAKE3 digest. Boxed because its state is much larger than MD5's.
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl ContentHasher {
+    /// Creates a hasher for the given algorithm.
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => ContentHasher::Md5(Md5::new()),
+            HashAlgorithm::Sha256 => ContentHasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => ContentHasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Feeds bytes into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            ContentHasher::Md5(hasher) => hasher.update(data),
+            ContentHasher::Sha256(hasher) => hasher.update(data),
+            ContentHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Feeds bytes into the hasher, splitting the work across the current
+    /// Rayon pool when the algorithm supports it (BLAKE3's tree mode).
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        match self {
+            ContentHasher::Md5(hasher) => hasher.update(data),
+            ContentHasher::Sha256(hasher) => hasher.update(data),
+            ContentHasher::Blake3(hasher) => {
+                hasher.update_rayon(data);
+            }
+        }
+    }
+
+    /// Consumes the hasher and returns the digest as a lowercase hex string.
+    pub fn finalize_hex(self) -> String {
+        match self {
+            ContentHasher::Md5(hasher) => format!("{:x}", hasher.finalize()),
+            ContentHasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            ContentHasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Returns true if the algorithm can hash a single input on multiple threads.
+pub fn supports_parallel(algorithm: HashAlgorithm) -> bool {
+    matches!(algorithm, HashAlgorithm::Blake3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(algorithm: HashAlgorithm, data: &[u8]) -> String {
+        let mut hasher = ContentHasher::new(algorithm);
+        hasher.update(data);
+        hasher.finalize_hex()
+    }
+
+    #[test]
+    fn test_md5_known_value() {
+        assert_eq!(
+            digest(HashAlgorithm::Md5, b"hello world"),
+            "5eb63bbbe01eeed093cb22bb8f5acdc3"
+        );
+    }
+
+    #[test]
+    fn test_sha256_known_value() {
+        assert_eq!(
+            digest(HashAlgorithm::Sha256, b"hello world"),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_blake3_known_value() {
+        assert_eq!(
+            digest(HashAlgorithm::Blake3, b"hello world"),
+            "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24"
+        );
+    }
+
+    #[test]
+    fn test_parallel_update_matches_sequential() {
+        let data: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 253) as u8).collect();
+
+        for algorithm in [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Blake3,
+        ] {
+            let mut parallel = ContentHasher::new(algorithm);
+            parallel.update_parallel(&data[..1024 * 1024]);
+            parallel.update_parallel(&data[1024 * 1024..]);
+
+            assert_eq!(parallel.finalize_hex(), digest(algorithm, &data));
+        }
+    }
+
+    #[test]
+    fn test_supports_parallel() {
+        assert!(!supports_parallel(HashAlgorithm::Md5));
+        assert!(!supports_parallel(HashAlgorithm::Sha256));
+        assert!(supports_parallel(HashAlgorithm::Blake3));
+    }
+}
@@ -0,0 +1,762 @@
+//! Tag-independent hashing of audio files.
+//!
+//! Locates the audio payload of MP3/AAC, FLAC, Ogg and MP4/M4A files and
+//! hashes it separately from the tag blocks around it (ID3, APE, Vorbis
+//! comments, MP4 metadata), so retagged copies of a track still match.
+//! Files whose layout isn't recognised are hashed in full.
+
+use super::algorithm::ContentHasher;
+use super::{pool, read, HashOptions, BUFFER_SIZE};
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::FileEntry;
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+
+/// File extensions hashed by audio payload (lowercase).
+pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "aac", "flac", "ogg", "oga", "opus", "m4a", "m4b"];
+
+/// Byte ranges of a file, sorted and non-overlapping.
+type ByteRanges = Vec<Range<u64>>;
+
+/// Hashes of an audio file's payload and of everything else (its tags).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioHash {
+    /// Hash of the audio payload.
+    pub payload_hash: String,
+
+    /// Hash of the bytes outside the payload (tags, headers).
+    pub metadata_hash: String,
+
+    /// Size of the audio payload in bytes.
+    pub payload_size: u64,
+}
+
+impl AudioHash {
+    /// Returns a key for the file's whole content, payload and tags alike.
+    ///
+    /// Byte-identical files share it, so it can stand in for a content hash
+    /// (e.g. when comparing directories). It never equals a plain content hash.
+    pub fn content_key(&self) -> String {
+        format!("{}:{}", self.payload_hash, self.metadata_hash)
+    }
+}
+
+/// Result of hashing a single audio file.
+#[derive(Debug, Clone)]
+pub struct AudioHashResult {
+    /// The file that was hashed.
+    pub file: FileEntry,
+
+    /// The audio hashes, or an error message.
+    pub hash: Result<AudioHash, String>,
+}
+
+/// Returns true if the path has an audio file extension.
+pub fn is_audio(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Hashes the audio payload of files that could have a payload twin.
+///
+/// Tags change file sizes, so the usual size pre-filter doesn't apply.
+/// Instead every file's payload is located first, and only files sharing a
+/// payload size with another file are hashed.
+///
+/// # Arguments
+/// * `files` - Audio files to hash
+/// * `options` - Algorithm, read strategy and resource limits to apply
+/// * `is_cancelled` - Returns true if cancellation has been requested
+///
+/// # Returns
+/// Results for files with a non-unique payload size, plus failures.
+pub fn hash_audio_files<C>(
+    files: Vec<FileEntry>,
+    options: &HashOptions,
+    is_cancelled: C,
+) -> Vec<AudioHashResult>
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    debug!("Locating audio payloads in {} files", files.len());
+
+    let run = || -> Vec<AudioHashResult> {
+        let located: Vec<(FileEntry, ScannerResult<Vec<Range<u64>>>)> = files
+            .into_par_iter()
+            .filter_map(|file| {
+                if is_cancelled() {
+                    return None;
+                }
+                let ranges = locate_payload(Path::new(&file.path));
+                Some((file, ranges))
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        let mut by_payload_size: HashMap<u64, Vec<(FileEntry, ByteRanges)>> = HashMap::new();
+        for (file, ranges) in located {
+            match ranges {
+                Ok(ranges) => by_payload_size
+                    .entry(total_len(&ranges))
+                    .or_default()
+                    .push((file, ranges)),
+                Err(e) => results.push(AudioHashResult {
+                    file,
+                    hash: Err(e.to_string()),
+                }),
+            }
+        }
+
+        let candidates: Vec<(FileEntry, Vec<Range<u64>>)> = by_payload_size
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .flatten()
+            .collect();
+
+        debug!("Hashing audio payload of {} files", candidates.len());
+
+        results.extend(
+            candidates
+                .into_par_iter()
+                .filter_map(|(file, ranges)| {
+                    if is_cancelled() {
+                        return None;
+                    }
+                    let hash =
+                        hash_audio_ranges(Path::new(&file.path), &ranges, options).map_err(|e| {
+                            warn!("Failed to hash audio {}: {}", file.path, e);
+                            e.to_string()
+                        });
+                    Some(AudioHashResult { file, hash })
+                })
+                .collect::<Vec<_>>(),
+        );
+        results
+    };
+
+    match pool::build_hash_pool(options.max_threads, options.low_priority) {
+        Ok(hash_pool) => hash_pool.install(run),
+        Err(e) => {
+            warn!("Failed to build hash pool, using global pool: {}", e);
+            run()
+        }
+    }
+}
+
+/// Computes the payload and metadata hashes of a single audio file.
+pub fn hash_audio_file(path: &Path, options: &HashOptions) -> ScannerResult<AudioHash> {
+    let ranges = locate_payload(path)?;
+    hash_audio_ranges(path, &ranges, options)
+}
+
+/// Finds the byte ranges holding a file's audio payload.
+fn locate_payload(path: &Path) -> ScannerResult<Vec<Range<u64>>> {
+    let mut file = open(path)?;
+    let len = file.metadata()?.len();
+    Ok(payload_ranges(&mut file, len)?)
+}
+
+/// Hashes the payload ranges and the bytes between them separately.
+fn hash_audio_ranges(
+    path: &Path,
+    ranges: &[Range<u64>],
+    options: &HashOptions,
+) -> ScannerResult<AudioHash> {
+    let file = open(path)?;
+    let len = file.metadata()?.len();
+    let throttle = options.throttle.as_deref();
+
+    if options.avoid_page_cache {
+        read::advise_sequential(&file);
+    }
+
+    let mut payload = ContentHasher::new(options.algorithm);
+    for range in ranges {
+        hash_range(&file, range, throttle, &mut payload)?;
+    }
+
+    let mut metadata = ContentHasher::new(options.algorithm);
+    for range in complement(ranges, len) {
+        hash_range(&file, &range, throttle, &mut metadata)?;
+    }
+
+    if options.avoid_page_cache {
+        read::advise_dont_need(&file);
+    }
+
+    Ok(AudioHash {
+        payload_hash: payload.finalize_hex(),
+        metadata_hash: metadata.finalize_hex(),
+        payload_size: total_len(ranges),
+    })
+}
+
+/// Opens a file, mapping common errors like the content hasher does.
+fn open(path: &Path) -> ScannerResult<File> {
+    File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ScannerError::FileDisappeared(path.display().to_string()),
+        io::ErrorKind::PermissionDenied => {
+            ScannerError::PermissionDenied(path.display().to_string())
+        }
+        _ => ScannerError::Io(e),
+    })
+}
+
+/// Feeds one byte range of `file` into `hasher`.
+fn hash_range(
+    mut file: &File,
+    range: &Range<u64>,
+    throttle: Option<&super::throttle::Throttle>,
+    hasher: &mut ContentHasher,
+) -> io::Result<()> {
+    file.seek(SeekFrom::Start(range.start))?;
+    read::read_chunks(
+        file.take(range.end - range.start),
+        BUFFER_SIZE,
+        throttle,
+        |chunk| hasher.update(chunk),
+    )
+}
+
+/// A payload made of one contiguous range.
+fn whole(range: Range<u64>) -> ByteRanges {
+    std::iter::once(range).collect()
+}
+
+/// Total number of bytes covered by `ranges`.
+fn total_len(ranges: &[Range<u64>]) -> u64 {
+    ranges.iter().map(|r| r.end - r.start).sum()
+}
+
+/// Returns the gaps between sorted, non-overlapping `ranges` within `0..len`.
+fn complement(ranges: &[Range<u64>], len: u64) -> Vec<Range<u64>> {
+    let mut gaps = Vec::new();
+    let mut position = 0;
+    for range in ranges {
+        if range.start > position {
+            gaps.push(position..range.start);
+        }
+        position = range.end;
+    }
+    if position < len {
+        gaps.push(position..len);
+    }
+    gaps
+}
+
+/// Returns the byte ranges of the audio payload in `reader`.
+///
+/// The container is recognised by its content rather than its extension.
+/// Unrecognised or malformed files yield the whole file as payload.
+pub fn payload_ranges<R: Read + Seek>(reader: &mut R, len: u64) -> io::Result<Vec<Range<u64>>> {
+    let start = skip_id3v2(reader, len)?;
+
+    let mut magic = [0u8; 8];
+    let have_magic = read_at(reader, start, &mut magic)?;
+
+    let ranges = if have_magic && &magic[..4] == b"fLaC" {
+        flac_payload(reader, start, len)?
+    } else if have_magic && &magic[..4] == b"OggS" {
+        ogg_payload(reader, start, len)?
+    } else if have_magic && start == 0 && &magic[4..8] == b"ftyp" {
+        mp4_payload(reader, len)?
+    } else {
+        // MPEG audio / ADTS: a raw frame stream between leading and trailing tags
+        let end = trim_trailing_tags(reader, start, len)?;
+        (start < end).then(|| whole(start..end))
+    };
+
+    Ok(ranges.unwrap_or_else(|| whole(0..len)))
+}
+
+/// Skips any ID3v2 tags at the start of the file.
+fn skip_id3v2<R: Read + Seek>(reader: &mut R, len: u64) -> io::Result<u64> {
+    let mut position = 0;
+    let mut header = [0u8; 10];
+
+    while read_at(reader, position, &mut header)? && &header[..3] == b"ID3" {
+        let has_footer = header[5] & 0x10 != 0;
+        let size = synchsafe(&header[6..10]) + 10 + if has_footer { 10 } else { 0 };
+        match position.checked_add(size).filter(|&end| end <= len) {
+            Some(end) => position = end,
+            None => break,
+        }
+    }
+
+    Ok(position)
+}
+
+/// Trims ID3v1, APEv2, Lyrics3v2 and appended ID3v2 tags from the end.
+fn trim_trailing_tags<R: Read + Seek>(reader: &mut R, start: u64, mut end: u64) -> io::Result<u64> {
+    loop {
+        let available = end - start;
+        let mut buf = [0u8; 32];
+
+        // ID3v1: fixed 128 bytes starting with "TAG"
+        if available >= 128 && read_at(reader, end - 128, &mut buf[..3])? && &buf[..3] == b"TAG" {
+            end -= 128;
+            continue;
+        }
+
+        // APEv2: 32-byte footer; size covers items and footer, plus an optional header
+        if available >= 32 && read_at(reader, end - 32, &mut buf)? && &buf[..8] == b"APETAGEX" {
+            let size = u64::from(u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]));
+            let flags = u32::from_le_bytes([buf[20], buf[21], buf[22], buf[23]]);
+            let total = size + if flags & (1 << 31) != 0 { 32 } else { 0 };
+            if total >= 32 && total <= available {
+                end -= total;
+                continue;
+            }
+        }
+
+        // Lyrics3v2: 6-digit size followed by "LYRICS200"
+        if available >= 15
+            && read_at(reader, end - 15, &mut buf[..15])?
+            && &buf[6..15] == b"LYRICS200"
+        {
+            let size = std::str::from_utf8(&buf[..6])
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok());
+            if let Some(size) = size.filter(|s| s + 15 <= available) {
+                end -= size + 15;
+                continue;
+            }
+        }
+
+        // ID3v2 appended at the end, identified by its "3DI" footer
+        if available >= 10 && read_at(reader, end - 10, &mut buf[..10])? && &buf[..3] == b"3DI" {
+            let total = synchsafe(&buf[6..10]) + 20;
+            if total <= available {
+                end -= total;
+                continue;
+            }
+        }
+
+        return Ok(end);
+    }
+}
+
+/// FLAC: the payload is every frame after the metadata blocks.
+fn flac_payload<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    len: u64,
+) -> io::Result<Option<Vec<Range<u64>>>> {
+    let mut position = start + 4;
+    let mut header = [0u8; 4];
+
+    loop {
+        if !read_at(reader, position, &mut header)? {
+            return Ok(None);
+        }
+        let last = header[0] & 0x80 != 0;
+        let size = u64::from(u32::from_be_bytes([0, header[1], header[2], header[3]]));
+        match position.checked_add(4 + size).filter(|&end| end <= len) {
+            Some(end) => position = end,
+            None => return Ok(None),
+        }
+        if last {
+            break;
+        }
+    }
+
+    let end = trim_trailing_tags(reader, position, len)?;
+    Ok((position < end).then(|| whole(position..end)))
+}
+
+/// Ogg: the payload is the body of every page after the codec header packets
+/// (identification, comments, setup) of the first logical stream.
+///
+/// Page headers are excluded because their sequence numbers and checksums
+/// change when a longer or shorter comment packet shifts the pages.
+fn ogg_payload<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    len: u64,
+) -> io::Result<Option<Vec<Range<u64>>>> {
+    let mut ranges = Vec::new();
+    let mut position = start;
+    let mut stream_serial = None;
+    let mut header_packets = None;
+    let mut packets_done = 0u64;
+
+    while position < len {
+        let mut header = [0u8; 27];
+        if !read_at(reader, position, &mut header)? || &header[..4] != b"OggS" {
+            return Ok(None);
+        }
+        let granule = u64::from_le_bytes(header[6..14].try_into().unwrap_or_default());
+        let page_serial = u32::from_le_bytes(header[14..18].try_into().unwrap_or_default());
+        let segments = usize::from(header[26]);
+
+        let mut lacing = vec![0u8; segments];
+        if !read_at(reader, position + 27, &mut lacing)? {
+            return Ok(None);
+        }
+        let body_start = position + 27 + segments as u64;
+        let body_len: u64 = lacing.iter().map(|&l| u64::from(l)).sum();
+        let body = body_start..body_start + body_len;
+        if body.end > len {
+            return Ok(None);
+        }
+
+        // Pages of other multiplexed streams are treated as metadata
+        if page_serial == *stream_serial.get_or_insert(page_serial) {
+            if header_packets.is_none() && packets_done == 0 {
+                header_packets = ogg_header_packets(reader, body_start)?;
+            }
+
+            let in_headers = match header_packets {
+                Some(count) => packets_done < count,
+                None => granule == 0,
+            };
+            if !in_headers && !body.is_empty() {
+                ranges.push(body.clone());
+            }
+
+            packets_done += lacing.iter().filter(|&&l| l < 255).count() as u64;
+        }
+
+        position = body.end;
+    }
+
+    Ok((!ranges.is_empty()).then_some(ranges))
+}
+
+/// Number of header packets for the codec whose first packet starts at `position`.
+fn ogg_header_packets<R: Read + Seek>(reader: &mut R, position: u64) -> io::Result<Option<u64>> {
+    let mut first = [0u8; 9];
+    if !read_at(reader, position, &mut first)? {
+        return Ok(None);
+    }
+
+    Ok(if &first[..7] == b"\x01vorbis" {
+        Some(3)
+    } else if &first[..8] == b"OpusHead" || &first[..5] == b"Speex" {
+        Some(2)
+    } else if &first[..5] == b"\x7fFLAC" {
+        // Mapping header, then the number of metadata packets (0 = unknown)
+        match u16::from_be_bytes([first[7], first[8]]) {
+            0 => None,
+            n => Some(1 + u64::from(n)),
+        }
+    } else {
+        None
+    })
+}
+
+/// MP4/M4A: the payload is the contents of the `mdat` boxes; tags live in `moov`.
+fn mp4_payload<R: Read + Seek>(reader: &mut R, len: u64) -> io::Result<Option<Vec<Range<u64>>>> {
+    let mut ranges = Vec::new();
+    let mut position = 0;
+
+    while position + 8 <= len {
+        let mut header = [0u8; 16];
+        if !read_at(reader, position, &mut header[..8])? {
+            return Ok(None);
+        }
+
+        let size32 = u64::from(u32::from_be_bytes([
+            header[0], header[1], header[2], header[3],
+        ]));
+        let (size, header_len) = match size32 {
+            0 => (len - position, 8),
+            1 => {
+                if !read_at(reader, position + 8, &mut header[8..16])? {
+                    return Ok(None);
+                }
+                (
+                    u64::from_be_bytes(header[8..16].try_into().unwrap_or_default()),
+                    16,
+                )
+            }
+            size => (size, 8),
+        };
+        // A corrupt 64-bit size must not wrap around past the end check
+        let Some(end) = position.checked_add(size).filter(|&end| end <= len) else {
+            return Ok(None);
+        };
+        if size < header_len {
+            return Ok(None);
+        }
+
+        if &header[4..8] == b"mdat" && size > header_len {
+            ranges.push(position + header_len..end);
+        }
+        position = end;
+    }
+
+    Ok((!ranges.is_empty()).then_some(ranges))
+}
+
+/// Reads exactly `buf.len()` bytes at `position`.
+///
+/// # Returns
+/// False if the file ends first.
+fn read_at<R: Read + Seek>(reader: &mut R, position: u64, buf: &mut [u8]) -> io::Result<bool> {
+    reader.seek(SeekFrom::Start(position))?;
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Decodes a 28-bit ID3v2 synchsafe integer.
+fn synchsafe(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |acc, &b| (acc << 7) | u64::from(b & 0x7f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    const AUDIO: &[u8] = b"\xff\xfb\x90\x64 pretend mpeg frames with audio data";
+
+    fn id3v2(payload_len: usize) -> Vec<u8> {
+        let size = payload_len as u32;
+        let mut tag = b"ID3\x04\x00\x00".to_vec();
+        tag.extend((0..4).rev().map(|i| ((size >> (7 * i)) & 0x7f) as u8));
+        tag.extend(vec![b'T'; payload_len]);
+        tag
+    }
+
+    fn id3v1(title: &str) -> Vec<u8> {
+        let mut tag = b"TAG".to_vec();
+        tag.extend(title.bytes());
+        tag.resize(128, 0);
+        tag
+    }
+
+    fn ranges_of(data: &[u8]) -> Vec<Range<u64>> {
+        payload_ranges(&mut Cursor::new(data), data.len() as u64).unwrap()
+    }
+
+    fn payload_of(data: &[u8]) -> Vec<u8> {
+        ranges_of(data)
+            .into_iter()
+            .flat_map(|r| data[r.start as usize..r.end as usize].to_vec())
+            .collect()
+    }
+
+    fn flac(comment: &str) -> Vec<u8> {
+        let mut data = b"fLaC".to_vec();
+        // STREAMINFO (type 0), 34 bytes
+        data.extend([0x00, 0, 0, 34]);
+        data.extend([7u8; 34]);
+        // VORBIS_COMMENT (type 4), last block
+        data.push(0x84);
+        data.extend(&(comment.len() as u32).to_be_bytes()[1..]);
+        data.extend(comment.bytes());
+        data.extend(AUDIO);
+        data
+    }
+
+    fn ogg_page(granule: u64, sequence: u32, packets: &[&[u8]]) -> Vec<u8> {
+        let mut lacing = Vec::new();
+        let mut body = Vec::new();
+        for packet in packets {
+            let mut remaining = packet.len();
+            while remaining >= 255 {
+                lacing.push(255);
+                remaining -= 255;
+            }
+            lacing.push(remaining as u8);
+            body.extend_from_slice(packet);
+        }
+
+        let mut page = b"OggS\x00\x00".to_vec();
+        page.extend(granule.to_le_bytes());
+        page.extend(0x1234u32.to_le_bytes());
+        page.extend(sequence.to_le_bytes());
+        page.extend([0xde, 0xad, 0xbe, 0xef]); // CRC is not checked
+        page.push(lacing.len() as u8);
+        page.extend(lacing);
+        page.extend(body);
+        page
+    }
+
+    fn ogg_vorbis(comment: &[u8]) -> Vec<u8> {
+        let mut comment_packet = b"\x03vorbis".to_vec();
+        comment_packet.extend_from_slice(comment);
+
+        let mut data = ogg_page(0, 0, &[b"\x01vorbis identification"]);
+        data.extend(ogg_page(0, 1, &[&comment_packet, b"\x05vorbis setup"]));
+        data.extend(ogg_page(
+            4096,
+            2,
+            &[b"audio packet one", b"audio packet two"],
+        ));
+        data.extend(ogg_page(8192, 3, &[b"audio packet three"]));
+        data
+    }
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(body);
+        data
+    }
+
+    fn m4a(title: &str) -> Vec<u8> {
+        let mut data = mp4_box(b"ftyp", b"M4A \x00\x00\x00\x00");
+        data.extend(mp4_box(b"moov", title.as_bytes()));
+        data.extend(mp4_box(b"mdat", AUDIO));
+        data
+    }
+
+    #[test]
+    fn test_is_audio() {
+        assert!(is_audio("/music/a.MP3"));
+        assert!(is_audio("/music/b.flac"));
+        assert!(!is_audio("/music/cover.jpg"));
+    }
+
+    #[test]
+    fn test_mp3_tags_are_skipped() {
+        let mut tagged = id3v2(200);
+        tagged.extend(AUDIO);
+        tagged.extend(id3v1("Some Title"));
+
+        let mut retagged = id3v2(31);
+        retagged.extend(AUDIO);
+
+        assert_eq!(payload_of(&tagged), AUDIO);
+        assert_eq!(payload_of(&retagged), AUDIO);
+        assert_eq!(payload_of(AUDIO), AUDIO);
+    }
+
+    #[test]
+    fn test_ape_and_lyrics_tags_are_skipped() {
+        let mut data = AUDIO.to_vec();
+        // Lyrics3v2 with a 10-byte body
+        data.extend(b"0123456789");
+        data.extend(b"000010LYRICS200");
+        // APEv2 footer only, size covers the 8-byte item and the footer
+        data.extend(b"APEITEM!");
+        let mut footer = b"APETAGEX".to_vec();
+        footer.extend(2000u32.to_le_bytes());
+        footer.extend(40u32.to_le_bytes());
+        footer.extend(0u32.to_le_bytes());
+        footer.extend(0u32.to_le_bytes());
+        footer.extend([0u8; 8]);
+        data.extend(footer);
+
+        assert_eq!(payload_of(&data), AUDIO);
+    }
+
+    #[test]
+    fn test_flac_metadata_is_skipped() {
+        assert_eq!(payload_of(&flac("ARTIST=One")), AUDIO);
+        assert_eq!(payload_of(&flac("ARTIST=Someone Else Entirely")), AUDIO);
+    }
+
+    #[test]
+    fn test_ogg_header_pages_are_skipped() {
+        let short = ogg_vorbis(b"TITLE=a");
+        let long = ogg_vorbis(&vec![b'x'; 600]);
+
+        let expected = b"audio packet oneaudio packet twoaudio packet three".to_vec();
+        assert_eq!(payload_of(&short), expected);
+        assert_eq!(payload_of(&long), expected);
+        assert_eq!(ranges_of(&short).len(), 2);
+    }
+
+    #[test]
+    fn test_mp4_mdat_only() {
+        assert_eq!(payload_of(&m4a("Title")), AUDIO);
+        assert_eq!(payload_of(&m4a("A much longer title")), AUDIO);
+    }
+
+    #[test]
+    fn test_malformed_container_falls_back_to_whole_file() {
+        let mut data = flac("x");
+        data.truncate(20);
+        assert_eq!(ranges_of(&data), whole(0..20));
+
+        let broken_ogg = b"OggS\x00truncated".to_vec();
+        assert_eq!(ranges_of(&broken_ogg), whole(0..broken_ogg.len() as u64));
+    }
+
+    #[test]
+    fn test_malformed_mp4_largesize_falls_back_to_whole_file() {
+        let mut data = mp4_box(b"ftyp", b"M4A \x00\x00\x00\x00");
+        data.extend(1u32.to_be_bytes());
+        data.extend(b"free");
+        data.extend((u64::MAX - 8).to_be_bytes());
+        data.extend(mp4_box(b"mdat", AUDIO));
+
+        assert_eq!(ranges_of(&data), whole(0..data.len() as u64));
+    }
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(complement(&[2..4, 6..8], 10), vec![0..2, 4..6, 8..10]);
+        assert!(complement(&whole(0..10), 10).is_empty());
+    }
+
+    #[test]
+    fn test_hash_audio_files_groups_retagged_copies() {
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, data: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, data).unwrap();
+            FileEntry::new(path.display().to_string(), data.len() as u64, None)
+        };
+
+        let mut tagged = id3v2(100);
+        tagged.extend(AUDIO);
+        let a = write("a.mp3", &tagged);
+        let b = write("b.mp3", AUDIO);
+        let unique = write("c.mp3", b"different audio of another length");
+
+        let results = hash_audio_files(vec![a, b, unique], &HashOptions::default(), || false);
+
+        // The track with a unique payload size is never hashed
+        assert_eq!(results.len(), 2);
+        let hashes: Vec<&AudioHash> = results.iter().map(|r| r.hash.as_ref().unwrap()).collect();
+        assert_eq!(hashes[0].payload_hash, hashes[1].payload_hash);
+        assert_ne!(hashes[0].metadata_hash, hashes[1].metadata_hash);
+        assert_eq!(hashes[0].payload_size, AUDIO.len() as u64);
+        assert_ne!(hashes[0].content_key(), hashes[1].content_key());
+    }
+
+    #[test]
+    fn test_content_key_matches_identical_files() {
+        let dir = TempDir::new().unwrap();
+        let mut tagged = id3v2(50);
+        tagged.extend(AUDIO);
+        let paths: Vec<_> = ["a.mp3", "b.mp3"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, &tagged).unwrap();
+                path
+            })
+            .collect();
+
+        let options = HashOptions::default();
+        let a = hash_audio_file(&paths[0], &options).unwrap();
+        let b = hash_audio_file(&paths[1], &options).unwrap();
+
+        assert_eq!(a.content_key(), b.content_key());
+    }
+
+    #[test]
+    fn test_hash_audio_file_missing() {
+        let result = hash_audio_file(Path::new("/nonexistent/a.mp3"), &HashOptions::default());
+        assert!(matches!(result, Err(ScannerError::FileDisappeared(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! Storage device detection for hash scheduling.
+//!
+//! Groups files by the device they live on (`st_dev`) and, on Linux, detects
+//! whether that device is a rotational disk so hashing can limit concurrent
+//! reads on spinning drives while keeping SSDs fully busy.
+
+use crate::types::FileEntry;
+use log::debug;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Files that reside on the same storage device.
+#[derive(Debug)]
+pub struct DeviceGroup {
+    /// Device ID (`st_dev`), if it could be determined.
+    pub device: Option<u64>,
+
+    /// Whether the device is a rotational disk, if known.
+    pub rotational: Option<bool>,
+
+    /// Files on this device.
+    pub files: Vec<FileEntry>,
+}
+
+impl DeviceGroup {
+    /// Returns true if the device is known to be a spinning disk.
+    pub fn is_rotational(&self) -> bool {
+        self.rotational == Some(true)
+    }
+}
+
+/// Groups files by the device they reside on.
+///
+/// Files whose device can't be determined (or on platforms without `st_dev`)
+/// end up in a single group with `device: None`.
+///
+/// # Returns
+/// Device groups ordered with rotational devices first, so their long
+/// sequential workloads start as early as possible.
+pub fn group_by_device(files: Vec<FileEntry>) -> Vec<DeviceGroup> {
+    let mut by_device: HashMap<Option<u64>, Vec<FileEntry>> = HashMap::new();
+
+    for file in files {
+        let device = device_id(Path::new(&file.path));
+        by_device.entry(device).or_default().push(file);
+    }
+
+    let mut groups: Vec<DeviceGroup> = by_device
+        .into_iter()
+        .map(|(device, files)| DeviceGroup {
+            device,
+            rotational: device.and_then(is_rotational),
+            files,
+        })
+        .collect();
+
+    groups.sort_by_key(|g| (!g.is_rotational(), g.device));
+
+    for group in &groups {
+        debug!(
+            "Device {:?}: {} files (rotational: {:?})",
+            group.device,
+            group.files.len(),
+            group.rotational
+        );
+    }
+
+    groups
+}
+
+/// Returns the ID of the device containing `path`.
+#[cfg(unix)]
+pub fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+/// Device IDs are not available on this platform.
+#[cfg(not(unix))]
+pub fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Returns whether the block device with the given ID is rotational.
+#[cfg(target_os = "linux")]
+pub fn is_rotational(device: u64) -> Option<bool> {
+    let (major, minor) = split_device_id(device);
+    rotational_from_sysfs(Path::new("/sys"), major, minor)
+}
+
+/// Rotational detection is only implemented on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn is_rotational(_device: u64) -> Option<bool> {
+    None
+}
+
+/// Splits a Linux `dev_t` into its major and minor numbers.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn split_device_id(device: u64) -> (u64, u64) {
+    let major = ((device >> 8) & 0xfff) | ((device >> 32) & !0xfff);
+    let minor = (device & 0xff) | ((device >> 12) & !0xff);
+    (major, minor)
+}
+
+/// Reads `queue/rotational` for a block device from sysfs.
+///
+/// Partitions don't have their own queue, so when the device is a partition
+/// the parent disk's queue is consulted instead.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn rotational_from_sysfs(sys_root: &Path, major: u64, minor: u64) -> Option<bool> {
+    let device_dir = sys_root
+        .join("dev/block")
+        .join(format!("{}:{}", major, minor))
+        .canonicalize()
+        .ok()?;
+
+    let candidates = [
+        device_dir.join("queue/rotational"),
+        device_dir.join("../queue/rotational"),
+    ];
+
+    candidates
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| parse_rotational(&contents))
+}
+
+/// Parses the contents of a sysfs `rotational` file.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_rotational(contents: &str) -> Option<bool> {
+    match contents.trim() {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_split_device_id() {
+        // makedev(8, 1) == 0x801 (sda1)
+        assert_eq!(split_device_id(0x801), (8, 1));
+        // makedev(259, 3) uses the extended major encoding (nvme0n1p3)
+        assert_eq!(split_device_id(0x10303), (259, 3));
+    }
+
+    #[test]
+    fn test_parse_rotational() {
+        assert_eq!(parse_rotational("1\n"), Some(true));
+        assert_eq!(parse_rotational("0\n"), Some(false));
+        assert_eq!(parse_rotational("garbage"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rotational_from_sysfs_partition() {
+        let sys = TempDir::new().unwrap();
+
+        // /sys/block/sda with queue/rotational and a partition sda1
+        let disk = sys.path().join("devices/sda");
+        fs::create_dir_all(disk.join("queue")).unwrap();
+        fs::write(disk.join("queue/rotational"), "1\n").unwrap();
+        fs::create_dir_all(disk.join("sda1")).unwrap();
+        fs::write(disk.join("sda1/partition"), "1\n").unwrap();
+
+        // /sys/block/nvme0n1 without partitions
+        let ssd = sys.path().join("devices/nvme0n1");
+        fs::create_dir_all(ssd.join("queue")).unwrap();
+        fs::write(ssd.join("queue/rotational"), "0\n").unwrap();
+
+        let dev_block = sys.path().join("dev/block");
+        fs::create_dir_all(&dev_block).unwrap();
+        std::os::unix::fs::symlink(disk.join("sda1"), dev_block.join("8:1")).unwrap();
+        std::os::unix::fs::symlink(&ssd, dev_block.join("259:0")).unwrap();
+
+        assert_eq!(rotational_from_sysfs(sys.path(), 8, 1), Some(true));
+        assert_eq!(rotational_from_sysfs(sys.path(), 259, 0), Some(false));
+        assert_eq!(rotational_from_sysfs(sys.path(), 7, 0), None);
+    }
+
+    #[test]
+    fn test_group_by_device_same_device() {
+        let temp_dir = TempDir::new().unwrap();
+        let files: Vec<FileEntry> = ["a.txt", "b.txt"]
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                fs::write(&path, b"data").unwrap();
+                FileEntry::new(path.display().to_string(), 4, None)
+            })
+            .collect();
+
+        let groups = group_by_device(files);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_group_by_device_missing_file() {
+        let files = vec![FileEntry::new("/nonexistent/file.txt".to_string(), 4, None)];
+
+        let groups = group_by_device(files);
+
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].device.is_none());
+        assert!(!groups[0].is_rotational());
+    }
+}
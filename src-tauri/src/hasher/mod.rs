@@ -1,23 +1,99 @@
-//! MD5 hashing module for the Duplicate File Detector.
+//! Content hashing module for the Duplicate File Detector.
 //!
-//! Provides buffered file hashing with parallel processing support.
+//! Provides MD5/BLAKE3 file hashing with parallel processing support.
+
+pub mod algorithm;
+pub mod audio;
+pub mod device;
+pub mod pool;
+pub mod read;
+pub mod throttle;
 
 use crate::error::{ScannerError, ScannerResult};
-use crate::types::FileEntry;
+use crate::types::{FileEntry, HashAlgorithm, ReadStrategy, ScanOptions};
+use algorithm::ContentHasher;
+use device::DeviceGroup;
 use log::{debug, warn};
-use md5::{Digest, Md5};
+use pool::HashSlots;
 use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufReader, Read};
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use throttle::Throttle;
 
-/// Buffer size for reading files (64 KB).
-/// This is a good balance between memory usage and I/O efficiency.
+/// Buffer size for the buffered read strategy (64 KB).
 const BUFFER_SIZE: usize = 64 * 1024;
 
-/// Computes the MD5 hash of a file using buffered I/O.
+/// Files at least this large are hashed on multiple threads when the
+/// algorithm supports it (256 MiB).
+const PARALLEL_HASH_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// Size of each slice handed to a parallel hasher (64 MiB).
+/// Large enough to keep every core busy, small enough to throttle smoothly.
+const PARALLEL_SLICE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Default number of concurrent hashing threads per rotational disk.
+/// Spinning disks read fastest sequentially; more readers just add seeks.
+const DEFAULT_ROTATIONAL_THREADS: usize = 1;
+
+/// Hash algorithm, read strategy and resource limits applied while hashing.
+#[derive(Debug, Clone, Default)]
+pub struct HashOptions {
+    /// Algorithm used to hash file contents.
+    pub algorithm: HashAlgorithm,
+
+    /// Maximum number of hashing threads (None uses one per CPU).
+    pub max_threads: Option<usize>,
+
+    /// Maximum number of hashing threads per rotational disk (None uses 1).
+    pub rotational_threads: Option<usize>,
+
+    /// Shared read-rate limiter, if throughput is capped.
+    pub throttle: Option<Arc<Throttle>>,
+
+    /// Whether hashing threads run at reduced CPU/I/O priority.
+    pub low_priority: bool,
+
+    /// How file contents are read.
+    pub read_strategy: ReadStrategy,
+
+    /// Whether to hint the kernel to drop hashed files from the page cache.
+    pub avoid_page_cache: bool,
+}
+
+impl HashOptions {
+    /// Builds hash options from the hashing settings in ScanOptions.
+    pub fn from_scan_options(options: &ScanOptions) -> Self {
+        Self {
+            algorithm: options.hash_algorithm,
+            max_threads: options.max_hash_threads,
+            rotational_threads: options.rotational_hash_threads,
+            throttle: options
+                .max_bytes_per_second
+                .map(|rate| Arc::new(Throttle::new(rate))),
+            low_priority: options.low_priority,
+            read_strategy: options.read_strategy,
+            avoid_page_cache: options.avoid_page_cache,
+        }
+    }
+
+    /// Returns the thread limit for hashing files on the given device.
+    fn threads_for(&self, group: &DeviceGroup) -> Option<usize> {
+        if group.is_rotational() {
+            let threads = self
+                .rotational_threads
+                .unwrap_or(DEFAULT_ROTATIONAL_THREADS);
+            Some(self.max_threads.map_or(threads, |max| threads.min(max)))
+        } else {
+            self.max_threads
+        }
+    }
+}
+
+/// Computes the MD5 hash of a file using the default read strategy.
 ///
 /// # Arguments
 /// * `path` - Path to the file to hash
@@ -25,6 +101,22 @@ const BUFFER_SIZE: usize = 64 * 1024;
 /// # Returns
 /// The MD5 hash as a lowercase hexadecimal string.
 pub fn hash_file(path: &Path) -> ScannerResult<String> {
+    hash_file_with_options(path, &HashOptions::default())
+}
+
+/// Computes the hash of a file using the algorithm, read strategy and limits in `options`.
+///
+/// Very large files are hashed on several threads of the current pool when
+/// the algorithm supports it (BLAKE3's tree mode); otherwise they are read
+/// sequentially like any other file.
+///
+/// # Arguments
+/// * `path` - Path to the file to hash
+/// * `options` - Algorithm, read strategy and resource limits to apply
+///
+/// # Returns
+/// The hash as a lowercase hexadecimal string.
+pub fn hash_file_with_options(path: &Path, options: &HashOptions) -> ScannerResult<String> {
     let file = File::open(path).map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
             ScannerError::FileDisappeared(path.display().to_string())
@@ -35,28 +127,59 @@ pub fn hash_file(path: &Path) -> ScannerResult<String> {
         }
     })?;
 
-    let mut reader = BufReader::with_capacity(BUFFER_SIZE, file);
-    let mut hasher = Md5::new();
-    let mut buffer = vec![0u8; BUFFER_SIZE];
-
-    loop {
-        let bytes_read = reader.read(&mut buffer).map_err(|e| {
-            if e.kind() == std::io::ErrorKind::PermissionDenied {
-                ScannerError::PermissionDenied(path.display().to_string())
-            } else {
-                ScannerError::Io(e)
-            }
-        })?;
-
-        if bytes_read == 0 {
-            break;
+    if options.avoid_page_cache {
+        read::advise_sequential(&file);
+    }
+
+    let mut hasher = ContentHasher::new(options.algorithm);
+    let throttle = options.throttle.as_deref();
+
+    let parallel = algorithm::supports_parallel(options.algorithm)
+        && file.metadata().map(|m| m.len()).unwrap_or(0) >= PARALLEL_HASH_THRESHOLD;
+
+    let read_result = if parallel {
+        read_parallel(&file, options, &mut hasher)
+    } else {
+        read::read_file(&file, options.read_strategy, throttle, |chunk| {
+            hasher.update(chunk)
+        })
+    };
+
+    read_result.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            ScannerError::PermissionDenied(path.display().to_string())
+        } else {
+            ScannerError::Io(e)
         }
+    })?;
 
-        hasher.update(&buffer[..bytes_read]);
+    if options.avoid_page_cache {
+        read::advise_dont_need(&file);
     }
 
-    let hash = hasher.finalize();
-    Ok(format!("{:x}", hash))
+    Ok(hasher.finalize_hex())
+}
+
+/// Feeds a file to the hasher in large slices hashed on several threads.
+///
+/// The file is only memory-mapped under [`ReadStrategy::Mmap`]; every other
+/// strategy reads the slices into a buffer, so choosing mmap stays opt-in.
+fn read_parallel(
+    file: &File,
+    options: &HashOptions,
+    hasher: &mut ContentHasher,
+) -> std::io::Result<()> {
+    let throttle = options.throttle.as_deref();
+    match options.read_strategy {
+        ReadStrategy::Mmap => read::read_mmap(file, PARALLEL_SLICE_SIZE, throttle, |slice| {
+            hasher.update_parallel(slice)
+        }),
+        ReadStrategy::Buffered | ReadStrategy::Direct => {
+            read::read_chunks(file, PARALLEL_SLICE_SIZE, throttle, |slice| {
+                hasher.update_parallel(slice)
+            })
+        }
+    }
 }
 
 /// Result of hashing a file.
@@ -125,45 +248,58 @@ where
     F: Fn(u64) + Send + Sync,
     C: Fn() -> bool + Send + Sync,
 {
-    let progress_counter = Arc::new(AtomicU64::new(0));
-    let callback = Arc::new(progress_callback);
-    let cancel_check = Arc::new(is_cancelled);
-    let cancelled = Arc::new(AtomicBool::new(false));
-
-    debug!("Starting parallel hashing of {} files", files.len());
-
-    let results: Vec<HashResult> = files
-        .into_par_iter()
-        .filter_map(|file| {
-            // Check for cancellation before processing each file
-            if cancelled.load(Ordering::Relaxed) || cancel_check() {
-                cancelled.store(true, Ordering::Relaxed);
-                return None;
-            }
-
-            let path = Path::new(&file.path);
-            let result = match hash_file(path) {
-                Ok(hash) => HashResult::success(file, hash),
-                Err(e) => {
-                    warn!("Failed to hash {}: {}", path.display(), e);
-                    HashResult::failure(file, e.to_string())
-                }
-            };
+    hash_files_parallel_observed(
+        files,
+        &HashOptions::default(),
+        |_, count| progress_callback(count),
+        is_cancelled,
+    )
+}
 
-            // Update progress
-            let count = progress_counter.fetch_add(1, Ordering::Relaxed) + 1;
-            callback(count);
+/// Hashes multiple files in parallel, reporting each result as it completes.
+///
+/// Unlike [`hash_files_parallel_with_cancel`], the callback receives the
+/// finished HashResult alongside the running count, which lets callers
+/// persist partial progress (e.g. scan checkpoints) while hashing continues.
+///
+/// Files are grouped by the device they live on and each device is hashed
+/// on its own dedicated thread pool, sized and prioritized according to
+/// `options`. Rotational disks get few threads so reads stay sequential,
+/// while other devices are hashed with full parallelism at the same time.
+/// With `max_threads` set, the pools share that many slots, so the total
+/// number of files hashed at once never exceeds it however many devices
+/// are involved.
+///
+/// On each device, files are hashed size bucket by size bucket, largest sizes
+/// first, each bucket as a unit, so a bucket can be grouped as soon as its
+/// last file is reported rather than at the end of the run.
+///
+/// # Arguments
+/// * `files` - List of files to hash
+/// * `options` - Thread, throughput and priority limits
+/// * `on_hashed` - Called after each file is hashed with its result and the current count
+/// * `is_cancelled` - Returns true if cancellation has been requested
+///
+/// # Returns
+/// A vector of HashResults for files that were processed before cancellation.
+pub fn hash_files_parallel_observed<F, C>(
+    files: Vec<FileEntry>,
+    options: &HashOptions,
+    on_hashed: F,
+    is_cancelled: C,
+) -> Vec<HashResult>
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let progress = HashProgress::new(options, on_hashed, is_cancelled);
 
-            // Check cancellation again after hashing
-            if cancel_check() {
-                cancelled.store(true, Ordering::Relaxed);
-            }
+    debug!("Starting parallel hashing of {} files", files.len());
 
-            Some(result)
-        })
-        .collect();
+    let groups = device::group_by_device(files);
+    let results = hash_device_groups(groups, options, &progress);
 
-    if cancelled.load(Ordering::Relaxed) {
+    if progress.cancelled.load(Ordering::Relaxed) {
         debug!("Parallel hashing cancelled after {} files", results.len());
     } else {
         debug!(
@@ -176,6 +312,170 @@ where
     results
 }
 
+/// Progress and cancellation state shared by all device pools.
+struct HashProgress<F, C> {
+    /// Number of files hashed so far.
+    counter: AtomicU64,
+
+    /// Set once cancellation has been observed.
+    cancelled: AtomicBool,
+
+    /// Limits files hashed at once across pools, if the thread count is capped.
+    slots: Option<HashSlots>,
+
+    /// Called after each file is hashed.
+    on_hashed: F,
+
+    /// Returns true if cancellation has been requested.
+    is_cancelled: C,
+}
+
+impl<F, C> HashProgress<F, C> {
+    fn new(options: &HashOptions, on_hashed: F, is_cancelled: C) -> Self {
+        Self {
+            counter: AtomicU64::new(0),
+            cancelled: AtomicBool::new(false),
+            slots: options.max_threads.map(HashSlots::new),
+            on_hashed,
+            is_cancelled,
+        }
+    }
+}
+
+/// Hashes each device group on its own pool, all devices at the same time.
+fn hash_device_groups<F, C>(
+    mut groups: Vec<DeviceGroup>,
+    options: &HashOptions,
+    progress: &HashProgress<F, C>,
+) -> Vec<HashResult>
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    if groups.len() == 1 {
+        let group = groups.remove(0);
+        hash_device_group(group, options, progress)
+    } else {
+        // One scoped thread per device drives that device's pool.
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = groups
+                .into_iter()
+                .map(|group| scope.spawn(|| hash_device_group(group, options, progress)))
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+}
+
+/// Hashes the files of one device on a pool sized for that device.
+///
+/// Files are hashed size bucket by size bucket, largest sizes first. Workers
+/// take whole buckets and hash each bucket's files in parallel, so a bucket
+/// is finished as a unit as soon as its files are done, while smaller buckets
+/// keep idle workers busy. Files can only be duplicates of files of the same
+/// size, so each finished bucket can be grouped without waiting for the rest.
+fn hash_device_group<F, C>(
+    group: DeviceGroup,
+    options: &HashOptions,
+    progress: &HashProgress<F, C>,
+) -> Vec<HashResult>
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let threads = options.threads_for(&group);
+
+    // Largest buckets first, so one long file doesn't end up running alone at
+    // the tail of the scan. `par_bridge` hands buckets to workers in iterator
+    // order, unlike indexed iterators which split the input up front.
+    let mut buckets: BTreeMap<Reverse<u64>, Vec<(usize, FileEntry)>> = BTreeMap::new();
+    for (index, file) in group.files.into_iter().enumerate() {
+        buckets
+            .entry(Reverse(file.size))
+            .or_default()
+            .push((index, file));
+    }
+
+    let hash_all = || -> Vec<(usize, HashResult)> {
+        buckets
+            .into_values()
+            .par_bridge()
+            .flat_map_iter(|bucket| {
+                bucket
+                    .into_par_iter()
+                    .filter_map(|(index, file)| {
+                        hash_observed(file, options, progress).map(|result| (index, result))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    };
+
+    let mut results = match pool::build_hash_pool(threads, options.low_priority) {
+        Ok(hash_pool) => hash_pool.install(hash_all),
+        Err(e) => {
+            warn!("Failed to build hash pool, using global pool: {}", e);
+            hash_all()
+        }
+    };
+
+    // Return results in input order regardless of scheduling order
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Hashes one file and reports it to `progress`.
+///
+/// # Returns
+/// The result, or None if the scan was cancelled before the file was hashed.
+fn hash_observed<F, C>(
+    file: FileEntry,
+    options: &HashOptions,
+    progress: &HashProgress<F, C>,
+) -> Option<HashResult>
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    // Check for cancellation before processing each file
+    if progress.cancelled.load(Ordering::Relaxed) || (progress.is_cancelled)() {
+        progress.cancelled.store(true, Ordering::Relaxed);
+        return None;
+    }
+
+    // Held until the result is reported, so callers see at most
+    // `max_threads` files in flight
+    let _slot = progress.slots.as_ref().map(HashSlots::acquire);
+
+    let path = Path::new(&file.path);
+    let result = match hash_file_with_options(path, options) {
+        Ok(hash) => HashResult::success(file, hash),
+        Err(e) => {
+            warn!("Failed to hash {}: {}", path.display(), e);
+            HashResult::failure(file, e.to_string())
+        }
+    };
+
+    // Update progress
+    let count = progress.counter.fetch_add(1, Ordering::Relaxed) + 1;
+    (progress.on_hashed)(&result, count);
+
+    // Check cancellation again after hashing
+    if (progress.is_cancelled)() {
+        progress.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    Some(result)
+}
+
 /// Extracts successful hashes from hash results.
 ///
 /// # Returns
@@ -207,7 +507,7 @@ pub fn extract_hash_errors(results: &[HashResult]) -> Vec<(String, String)> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::{self, File};
+    use std::fs::File;
     use std::io::Write;
     use std::sync::atomic::AtomicU64;
     use tempfile::TempDir;
@@ -329,6 +629,271 @@ mod tests {
         assert!(!results[1].is_success());
     }
 
+    #[test]
+    fn test_hash_files_parallel_observed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path1 = create_test_file(temp_dir.path(), "file1.txt", b"content1");
+        let path2 = create_test_file(temp_dir.path(), "file2.txt", b"content2");
+
+        let files = vec![
+            FileEntry::new(path1.display().to_string(), 8, None),
+            FileEntry::new(path2.display().to_string(), 8, None),
+            FileEntry::new("/nonexistent/file.txt".to_string(), 8, None),
+        ];
+
+        let observed = parking_lot::Mutex::new(Vec::new());
+
+        let results = hash_files_parallel_observed(
+            files,
+            &HashOptions::default(),
+            |result, _| observed.lock().push(result.file.path.clone()),
+            || false,
+        );
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(observed.lock().len(), 3);
+    }
+
+    #[test]
+    fn test_hash_file_with_throttle() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(temp_dir.path(), "test.txt", b"hello world");
+
+        let options = HashOptions {
+            throttle: Some(Arc::new(Throttle::new(1_000_000))),
+            ..Default::default()
+        };
+
+        let hash = hash_file_with_options(&path, &options).unwrap();
+        assert_eq!(hash, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+    }
+
+    #[test]
+    fn test_hash_file_read_strategies_agree() {
+        let temp_dir = TempDir::new().unwrap();
+        let content: Vec<u8> = (0..BUFFER_SIZE * 5 + 3).map(|i| (i % 256) as u8).collect();
+        let path = create_test_file(temp_dir.path(), "large.bin", &content);
+
+        let expected = hash_file(&path).unwrap();
+
+        for read_strategy in [
+            ReadStrategy::Buffered,
+            ReadStrategy::Direct,
+            ReadStrategy::Mmap,
+        ] {
+            let options = HashOptions {
+                read_strategy,
+                avoid_page_cache: true,
+                ..Default::default()
+            };
+            assert_eq!(hash_file_with_options(&path, &options).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_read_parallel_matches_sequential_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let content: Vec<u8> = (0..BUFFER_SIZE * 40 + 11)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let path = create_test_file(temp_dir.path(), "large.bin", &content);
+
+        let mut sequential = ContentHasher::new(HashAlgorithm::Blake3);
+        sequential.update(&content);
+        let expected = sequential.finalize_hex();
+
+        for read_strategy in [
+            ReadStrategy::Buffered,
+            ReadStrategy::Direct,
+            ReadStrategy::Mmap,
+        ] {
+            let options = HashOptions {
+                algorithm: HashAlgorithm::Blake3,
+                read_strategy,
+                ..Default::default()
+            };
+            let mut hasher = ContentHasher::new(options.algorithm);
+            read_parallel(&File::open(&path).unwrap(), &options, &mut hasher).unwrap();
+            assert_eq!(hasher.finalize_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn test_hash_file_blake3() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(temp_dir.path(), "test.txt", b"hello world");
+
+        let options = HashOptions {
+            algorithm: HashAlgorithm::Blake3,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            hash_file_with_options(&path, &options).unwrap(),
+            "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24"
+        );
+    }
+
+    #[test]
+    fn test_hash_files_parallel_preserves_order_with_mixed_sizes() {
+        let temp_dir = TempDir::new().unwrap();
+        let files: Vec<FileEntry> = [10usize, 5000, 1, 300]
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let path =
+                    create_test_file(temp_dir.path(), &format!("f{}.bin", i), &vec![7u8; size]);
+                FileEntry::new(path.display().to_string(), size as u64, None)
+            })
+            .collect();
+        let expected: Vec<String> = files.iter().map(|f| f.path.clone()).collect();
+
+        let results = hash_files_parallel(files, |_| {});
+
+        let paths: Vec<String> = results.into_iter().map(|r| r.file.path).collect();
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_hash_options_from_scan_options() {
+        let scan_options = ScanOptions {
+            hash_algorithm: HashAlgorithm::Blake3,
+            max_hash_threads: Some(2),
+            max_bytes_per_second: Some(10 * 1024 * 1024),
+            low_priority: true,
+            ..Default::default()
+        };
+
+        let options = HashOptions::from_scan_options(&scan_options);
+
+        assert_eq!(options.algorithm, HashAlgorithm::Blake3);
+        assert_eq!(options.max_threads, Some(2));
+        assert_eq!(
+            options.throttle.as_ref().map(|t| t.bytes_per_second()),
+            Some(10 * 1024 * 1024)
+        );
+        assert!(options.low_priority);
+    }
+
+    #[test]
+    fn test_hash_files_parallel_limited_threads() {
+        let temp_dir = TempDir::new().unwrap();
+        let files: Vec<FileEntry> = (0..8)
+            .map(|i| {
+                let path = create_test_file(temp_dir.path(), &format!("f{}.txt", i), b"same");
+                FileEntry::new(path.display().to_string(), 4, None)
+            })
+            .collect();
+
+        let options = HashOptions {
+            max_threads: Some(1),
+            low_priority: true,
+            ..Default::default()
+        };
+
+        let results = hash_files_parallel_observed(files, &options, |_, _| {}, || false);
+
+        assert_eq!(results.len(), 8);
+        assert!(results.iter().all(|r| r.is_success()));
+    }
+
+    #[test]
+    fn test_thread_cap_shared_across_devices() {
+        let temp_dir = TempDir::new().unwrap();
+        let ssd = |device: u64| DeviceGroup {
+            device: Some(device),
+            rotational: Some(false),
+            files: (0..6)
+                .map(|i| {
+                    let name = format!("d{}_{}.txt", device, i);
+                    let path = create_test_file(temp_dir.path(), &name, b"data");
+                    FileEntry::new(path.display().to_string(), 4, None)
+                })
+                .collect(),
+        };
+        let options = HashOptions {
+            max_threads: Some(2),
+            ..Default::default()
+        };
+
+        let active = AtomicU64::new(0);
+        let peak = AtomicU64::new(0);
+        let progress = HashProgress::new(
+            &options,
+            |_: &HashResult, _| {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                active.fetch_sub(1, Ordering::SeqCst);
+            },
+            || false,
+        );
+
+        let results = hash_device_groups(vec![ssd(1), ssd(2)], &options, &progress);
+
+        assert_eq!(results.len(), 12);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_size_buckets_hashed_as_units_largest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let files: Vec<FileEntry> = [3usize, 1, 2, 1, 3, 2, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let path = create_test_file(temp_dir.path(), &format!("f{}", i), &vec![b'x'; size]);
+                FileEntry::new(path.display().to_string(), size as u64, None)
+            })
+            .collect();
+        let group = DeviceGroup {
+            device: None,
+            rotational: Some(true),
+            files,
+        };
+
+        let order = parking_lot::Mutex::new(Vec::new());
+        let options = HashOptions::default();
+        let progress = HashProgress::new(
+            &options,
+            |result: &HashResult, _| order.lock().push(result.file.size),
+            || false,
+        );
+
+        let results = hash_device_group(group, &options, &progress);
+
+        // One thread per rotational disk: each bucket finishes before the next starts
+        assert_eq!(order.into_inner(), vec![3, 3, 2, 2, 1, 1, 1]);
+        let sizes: Vec<u64> = results.iter().map(|r| r.file.size).collect();
+        assert_eq!(sizes, vec![3, 1, 2, 1, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_threads_for_rotational_device() {
+        let rotational = DeviceGroup {
+            device: Some(0x801),
+            rotational: Some(true),
+            files: vec![],
+        };
+        let ssd = DeviceGroup {
+            device: Some(0x10300),
+            rotational: Some(false),
+            files: vec![],
+        };
+
+        let defaults = HashOptions::default();
+        assert_eq!(defaults.threads_for(&rotational), Some(1));
+        assert_eq!(defaults.threads_for(&ssd), None);
+
+        let limited = HashOptions {
+            max_threads: Some(4),
+            rotational_threads: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(limited.threads_for(&rotational), Some(4));
+        assert_eq!(limited.threads_for(&ssd), Some(4));
+    }
+
     #[test]
     fn test_extract_successful_hashes() {
         let results = vec![
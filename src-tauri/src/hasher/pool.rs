@@ -0,0 +1,181 @@
+//! Dedicated thread pool for hashing.
+//!
+//! Hashing runs on its own Rayon pool instead of the global one so the number
+//! of concurrent readers can be capped and, on Linux, the worker threads can
+//! be given a lower CPU and I/O priority. When several device pools run at
+//! once, [`HashSlots`] keeps their combined number of files in flight under
+//! the configured thread limit.
+
+use crate::error::{ScannerError, ScannerResult};
+use log::debug;
+use parking_lot::{Condvar, Mutex};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::cell::Cell;
+
+thread_local! {
+    /// Whether the current thread holds a hash slot.
+    static HOLDS_SLOT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Caps the number of files hashed at once across all hash pools.
+///
+/// A thread that already holds a slot gets another one for free: Rayon may
+/// run a second file on a thread that is waiting inside a parallel hash, and
+/// blocking there could deadlock while the thread still only hashes one file
+/// at a time.
+#[derive(Debug)]
+pub struct HashSlots {
+    /// Number of slots not currently held.
+    free: Mutex<usize>,
+
+    /// Signalled whenever a slot is released.
+    released: Condvar,
+}
+
+/// A held hash slot, released on drop.
+#[derive(Debug)]
+pub struct HashSlot<'a> {
+    /// Slots to return to, or None if the thread already held one.
+    slots: Option<&'a HashSlots>,
+}
+
+impl HashSlots {
+    /// Creates a limiter allowing `slots` files to be hashed at once (at least one).
+    pub fn new(slots: usize) -> Self {
+        Self {
+            free: Mutex::new(slots.max(1)),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Waits for a free slot and takes it.
+    pub fn acquire(&self) -> HashSlot<'_> {
+        if HOLDS_SLOT.with(Cell::get) {
+            return HashSlot { slots: None };
+        }
+
+        let mut free = self.free.lock();
+        while *free == 0 {
+            self.released.wait(&mut free);
+        }
+        *free -= 1;
+        HOLDS_SLOT.with(|holds| holds.set(true));
+
+        HashSlot { slots: Some(self) }
+    }
+}
+
+impl Drop for HashSlot<'_> {
+    fn drop(&mut self) {
+        if let Some(slots) = self.slots {
+            HOLDS_SLOT.with(|holds| holds.set(false));
+            *slots.free.lock() += 1;
+            slots.released.notify_one();
+        }
+    }
+}
+
+/// Builds a thread pool for hashing.
+///
+/// # Arguments
+/// * `max_threads` - Maximum number of hashing threads (None uses Rayon's default)
+/// * `low_priority` - Whether worker threads should run at reduced CPU/I/O priority
+///
+/// # Returns
+/// The configured thread pool.
+pub fn build_hash_pool(
+    max_threads: Option<usize>,
+    low_priority: bool,
+) -> ScannerResult<ThreadPool> {
+    let mut builder = ThreadPoolBuilder::new().thread_name(|i| format!("hasher-{}", i));
+
+    if let Some(threads) = max_threads {
+        builder = builder.num_threads(threads.max(1));
+    }
+
+    if low_priority {
+        builder = builder.start_handler(|_| lower_current_thread_priority());
+    }
+
+    let pool = builder
+        .build()
+        .map_err(|e| ScannerError::Io(std::io::Error::other(e.to_string())))?;
+
+    debug!(
+        "Built hash pool with {} threads (low priority: {})",
+        pool.current_num_threads(),
+        low_priority
+    );
+
+    Ok(pool)
+}
+
+/// Lowers the CPU and I/O priority of the calling thread.
+///
+/// On Linux both niceness and I/O priority are per-thread, so only the
+/// hashing workers are affected. Failures are ignored: running at normal
+/// priority is always an acceptable fallback.
+#[cfg(target_os = "linux")]
+fn lower_current_thread_priority() {
+    /// `IOPRIO_WHO_PROCESS` from linux/ioprio.h; with id 0 it targets the calling thread.
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    /// Idle I/O scheduling class: only served when no one else needs the disk.
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+    /// Lowest CPU scheduling priority.
+    const NICE_LOWEST: libc::c_int = 19;
+
+    // SAFETY: both calls only change scheduling attributes of the calling
+    // thread and take no pointers.
+    unsafe {
+        if libc::setpriority(libc::PRIO_PROCESS, 0, NICE_LOWEST) != 0 {
+            debug!("setpriority failed: {}", std::io::Error::last_os_error());
+        }
+
+        let ioprio = IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT;
+        if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) != 0 {
+            debug!("ioprio_set failed: {}", std::io::Error::last_os_error());
+        }
+    }
+}
+
+/// Thread priorities are not adjusted on this platform.
+#[cfg(not(target_os = "linux"))]
+fn lower_current_thread_priority() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_respects_thread_limit() {
+        let pool = build_hash_pool(Some(2), false).unwrap();
+        assert_eq!(pool.current_num_threads(), 2);
+    }
+
+    #[test]
+    fn test_pool_zero_threads_clamped() {
+        let pool = build_hash_pool(Some(0), false).unwrap();
+        assert_eq!(pool.current_num_threads(), 1);
+    }
+
+    #[test]
+    fn test_hash_slots_are_reentrant() {
+        let slots = HashSlots::new(1);
+
+        let outer = slots.acquire();
+        // A nested acquire on the same thread must not block
+        let inner = slots.acquire();
+        drop(inner);
+        drop(outer);
+
+        assert_eq!(*slots.free.lock(), 1);
+    }
+
+    #[test]
+    fn test_low_priority_pool_runs_work() {
+        let pool = build_hash_pool(Some(1), true).unwrap();
+        let sum: u64 = pool.install(|| (1..=10u64).sum());
+        assert_eq!(sum, 55);
+    }
+}
@@ -0,0 +1,207 @@
+//! Read strategies for feeding file contents to the hasher.
+//!
+//! Each strategy streams a file's bytes into a sink in chunks, applying the
+//! optional read-rate throttle after every chunk.
+
+use super::throttle::Throttle;
+use super::BUFFER_SIZE;
+use crate::types::ReadStrategy;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+/// Size of each read for the direct strategy (1 MiB).
+const DIRECT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Files at least this large are memory-mapped by the mmap strategy (16 MiB).
+/// Below this, the cost of setting up the mapping outweighs the saved copy.
+pub const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// Streams the contents of `file` into `sink` using the given strategy.
+///
+/// # Arguments
+/// * `file` - Open file to read from the start
+/// * `strategy` - How to read the file
+/// * `throttle` - Optional read-rate limiter
+/// * `sink` - Receives each chunk in order
+pub fn read_file<F>(
+    file: &File,
+    strategy: ReadStrategy,
+    throttle: Option<&Throttle>,
+    sink: F,
+) -> io::Result<()>
+where
+    F: FnMut(&[u8]),
+{
+    match strategy {
+        ReadStrategy::Buffered => read_chunks(
+            BufReader::with_capacity(BUFFER_SIZE, file),
+            BUFFER_SIZE,
+            throttle,
+            sink,
+        ),
+        ReadStrategy::Direct => read_chunks(file, DIRECT_CHUNK_SIZE, throttle, sink),
+        ReadStrategy::Mmap => {
+            if file.metadata()?.len() >= MMAP_THRESHOLD {
+                read_mmap(file, DIRECT_CHUNK_SIZE, throttle, sink)
+            } else {
+                read_chunks(file, DIRECT_CHUNK_SIZE, throttle, sink)
+            }
+        }
+    }
+}
+
+/// Reads `reader` into a reusable buffer of `chunk_size` bytes.
+pub fn read_chunks<R, F>(
+    mut reader: R,
+    chunk_size: usize,
+    throttle: Option<&Throttle>,
+    mut sink: F,
+) -> io::Result<()>
+where
+    R: Read,
+    F: FnMut(&[u8]),
+{
+    let mut buffer = vec![0u8; chunk_size];
+
+    loop {
+        let bytes_read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        sink(&buffer[..bytes_read]);
+
+        if let Some(throttle) = throttle {
+            throttle.consume(bytes_read as u64);
+        }
+    }
+
+    Ok(())
+}
+
+/// Memory-maps `file` and passes it to `sink` in `chunk_size` slices without copying.
+pub fn read_mmap<F>(
+    file: &File,
+    chunk_size: usize,
+    throttle: Option<&Throttle>,
+    mut sink: F,
+) -> io::Result<()>
+where
+    F: FnMut(&[u8]),
+{
+    // SAFETY: the mapping is read-only and dropped before returning. If another
+    // process truncates the file while it is mapped, reads past the new end can
+    // fault; that is the accepted trade-off of this opt-in strategy.
+    let mmap = unsafe { Mmap::map(file)? };
+
+    for chunk in mmap.chunks(chunk_size) {
+        sink(chunk);
+
+        if let Some(throttle) = throttle {
+            throttle.consume(chunk.len() as u64);
+        }
+    }
+
+    Ok(())
+}
+
+/// Hints to the kernel that `file` will be read sequentially once.
+#[cfg(target_os = "linux")]
+pub fn advise_sequential(file: &File) {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: posix_fadvise only inspects the descriptor, which is valid for
+    // the lifetime of `file`.
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+    }
+}
+
+/// Asks the kernel to drop `file`'s pages from the page cache after reading,
+/// so a scan doesn't evict data other applications are using.
+#[cfg(target_os = "linux")]
+pub fn advise_dont_need(file: &File) {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: see advise_sequential.
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+    }
+}
+
+/// Page cache hints are not supported on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn advise_sequential(_file: &File) {}
+
+/// Page cache hints are not supported on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn advise_dont_need(_file: &File) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn temp_file(content: &[u8]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content).unwrap();
+        file
+    }
+
+    fn collect(file: &File, strategy: ReadStrategy) -> Vec<u8> {
+        let mut out = Vec::new();
+        read_file(file, strategy, None, |chunk| out.extend_from_slice(chunk)).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_all_strategies_read_same_bytes() {
+        let content: Vec<u8> = (0..BUFFER_SIZE * 3 + 17).map(|i| (i % 251) as u8).collect();
+        let file = temp_file(&content);
+
+        for strategy in [
+            ReadStrategy::Buffered,
+            ReadStrategy::Direct,
+            ReadStrategy::Mmap,
+        ] {
+            let reopened = File::open(file.path()).unwrap();
+            assert_eq!(collect(&reopened, strategy), content, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_read_mmap_large_file() {
+        let content: Vec<u8> = (0..DIRECT_CHUNK_SIZE * 2 + 5)
+            .map(|i| (i % 7) as u8)
+            .collect();
+        let file = temp_file(&content);
+
+        let mut out = Vec::new();
+        let mut chunks = 0;
+        read_mmap(file.as_file(), DIRECT_CHUNK_SIZE, None, |chunk| {
+            chunks += 1;
+            out.extend_from_slice(chunk);
+        })
+        .unwrap();
+
+        assert_eq!(out, content);
+        assert_eq!(chunks, 3);
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let file = temp_file(b"");
+        assert!(collect(file.as_file(), ReadStrategy::Direct).is_empty());
+    }
+
+    #[test]
+    fn test_page_cache_hints_do_not_fail() {
+        let file = temp_file(b"data");
+        advise_sequential(file.as_file());
+        advise_dont_need(file.as_file());
+    }
+}
@@ -0,0 +1,117 @@
+//! Read-rate limiting for the hasher.
+//!
+//! Provides a token bucket shared by all hashing threads so the combined
+//! read throughput stays under a configured bytes-per-second cap.
+
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token-bucket rate limiter for bytes read from disk.
+///
+/// The bucket holds at most one second worth of bytes, so short bursts are
+/// allowed but sustained throughput converges to the configured rate.
+#[derive(Debug)]
+pub struct Throttle {
+    /// Maximum sustained throughput in bytes per second.
+    bytes_per_second: u64,
+
+    /// Current bucket state.
+    state: Mutex<ThrottleState>,
+}
+
+#[derive(Debug)]
+struct ThrottleState {
+    /// Bytes that can be read without waiting (negative when in debt).
+    available: f64,
+
+    /// When `available` was last refilled.
+    last_refill: Instant,
+}
+
+impl Throttle {
+    /// Creates a throttle limited to the given number of bytes per second.
+    /// A rate of zero is treated as one byte per second.
+    pub fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second.max(1);
+        Self {
+            bytes_per_second,
+            state: Mutex::new(ThrottleState {
+                available: bytes_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns the configured rate in bytes per second.
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Accounts for `bytes` that were just read, sleeping the calling
+    /// thread if the budget is exhausted.
+    pub fn consume(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Deducts `bytes` from the bucket and returns how long the caller must wait.
+    fn reserve(&self, bytes: u64) -> Duration {
+        let rate = self.bytes_per_second as f64;
+        let mut state = self.state.lock();
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.available = (state.available + elapsed * rate).min(rate);
+        state.last_refill = now;
+
+        state.available -= bytes as f64;
+
+        if state.available < 0.0 {
+            Duration::from_secs_f64(-state.available / rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_within_budget_does_not_wait() {
+        let throttle = Throttle::new(1_000_000);
+        assert_eq!(throttle.reserve(500_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_over_budget_waits_proportionally() {
+        let throttle = Throttle::new(1000);
+
+        // First second worth of bytes is free
+        assert_eq!(throttle.reserve(1000), Duration::ZERO);
+
+        // Another 500 bytes at 1000 B/s needs roughly half a second
+        let wait = throttle.reserve(500);
+        assert!(wait > Duration::from_millis(400));
+        assert!(wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_consume_sleeps_when_exhausted() {
+        let throttle = Throttle::new(1000);
+        throttle.consume(1000);
+
+        let start = Instant::now();
+        throttle.consume(100);
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn test_zero_rate_is_clamped() {
+        let throttle = Throttle::new(0);
+        assert_eq!(throttle.bytes_per_second(), 1);
+    }
+}
@@ -0,0 +1,325 @@
+//! Local history of completed scans.
+//!
+//! Every finished scan appends a small summary (options, duration, counts
+//! and wasted space per root) to one JSON file in the app data directory.
+//! Only the most recent entries are kept, so the file stays small however
+//! often scans run. Trends are computed from the stored summaries, which
+//! show whether cleanup is actually reducing the wasted space under a root.
+
+use crate::atomic::write_atomic;
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::{RootTrend, RootWaste, ScanHistoryEntry, ScanOptions, ScanResult, TrendPoint};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current version of the history file format.
+pub const HISTORY_VERSION: u32 = 1;
+
+/// Maximum number of scans kept; older entries are dropped first.
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Contents of the history file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryFile {
+    /// Format version (HISTORY_VERSION when written).
+    version: u32,
+
+    /// Recorded scans, oldest first.
+    entries: Vec<ScanHistoryEntry>,
+}
+
+impl ScanHistoryEntry {
+    /// Summarizes a completed scan for the history.
+    ///
+    /// # Arguments
+    /// * `id` - ID of the scan
+    /// * `finished_at` - When the scan finished, as seconds since the Unix epoch
+    /// * `options` - Options the scan was run with
+    /// * `result` - The scan result
+    pub fn from_scan(
+        id: impl Into<String>,
+        finished_at: u64,
+        options: &ScanOptions,
+        result: &ScanResult,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            finished_at,
+            options: options.clone(),
+            duration_ms: result.duration_ms,
+            files_scanned: result.total_files_scanned,
+            duplicate_groups: result.duplicate_groups.len() as u64,
+            duplicates_found: result.total_duplicates_found,
+            wasted_space: result.total_wasted_space,
+            error_count: result.errors.len() as u64,
+            wasted_by_root: wasted_by_root(options, result),
+        }
+    }
+}
+
+/// File-backed storage for the scan history.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    /// Path of the history file.
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// Creates a store backed by the given file.
+    /// The file and its directory are created on first record.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Appends a scan to the history, dropping the oldest entries beyond
+    /// [`MAX_HISTORY_ENTRIES`]. The file is replaced atomically.
+    pub fn record(&self, entry: ScanHistoryEntry) -> ScannerResult<()> {
+        let mut history = self.read()?;
+        history.entries.push(entry);
+        if history.entries.len() > MAX_HISTORY_ENTRIES {
+            let excess = history.entries.len() - MAX_HISTORY_ENTRIES;
+            history.entries.drain(..excess);
+        }
+        history.version = HISTORY_VERSION;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json =
+            serde_json::to_vec(&history).map_err(|e| ScannerError::History(e.to_string()))?;
+
+        write_atomic(&self.path, |w| w.write_all(&json))?;
+
+        debug!(
+            "Recorded scan in history ({} entries)",
+            history.entries.len()
+        );
+        Ok(())
+    }
+
+    /// Lists recorded scans, most recent first.
+    pub fn list(&self) -> ScannerResult<Vec<ScanHistoryEntry>> {
+        let mut entries = self.read()?.entries;
+        entries.sort_by_key(|e| std::cmp::Reverse(e.finished_at));
+        Ok(entries)
+    }
+
+    /// Removes all recorded scans. Clearing a missing history is not an error.
+    pub fn clear(&self) -> ScannerResult<()> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Reads the history file; a missing file is an empty history.
+    ///
+    /// A corrupt file is reported rather than replaced, so recording a scan
+    /// never silently discards the existing history.
+    fn read(&self) -> ScannerResult<HistoryFile> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HistoryFile::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let history: HistoryFile =
+            serde_json::from_slice(&data).map_err(|e| ScannerError::History(e.to_string()))?;
+        if history.version > HISTORY_VERSION {
+            return Err(ScannerError::History(format!(
+                "written by a newer version (format {})",
+                history.version
+            )));
+        }
+
+        Ok(history)
+    }
+}
+
+/// Builds the wasted space trend of every root found in the history.
+///
+/// # Arguments
+/// * `entries` - Recorded scans, in any order
+///
+/// # Returns
+/// One trend per root, sorted by root path, each with its points oldest first.
+pub fn wasted_space_trends(entries: &[ScanHistoryEntry]) -> Vec<RootTrend> {
+    let mut by_root: BTreeMap<&str, Vec<TrendPoint>> = BTreeMap::new();
+
+    for entry in entries {
+        for waste in &entry.wasted_by_root {
+            by_root.entry(&waste.root).or_default().push(TrendPoint {
+                scan_id: entry.id.clone(),
+                finished_at: entry.finished_at,
+                wasted_space: waste.wasted_space,
+            });
+        }
+    }
+
+    by_root
+        .into_iter()
+        .map(|(root, mut points)| {
+            points.sort_by_key(|p| p.finished_at);
+            RootTrend {
+                root: root.to_string(),
+                points,
+            }
+        })
+        .collect()
+}
+
+/// Splits a result's wasted space by the root each duplicate copy lies under.
+///
+/// Nested roots get the files under them rather than their parent's share.
+/// Every scanned root is listed, including those without duplicates.
+fn wasted_by_root(options: &ScanOptions, result: &ScanResult) -> Vec<RootWaste> {
+    let mut roots: Vec<&String> = options
+        .root_paths
+        .iter()
+        .chain(&options.reference_roots)
+        .collect();
+    roots.sort();
+    roots.dedup();
+
+    let mut wasted = vec![0u64; roots.len()];
+    for group in &result.duplicate_groups {
+        for file in group.files.iter().skip(1) {
+            let path = Path::new(&file.path);
+            let root = roots
+                .iter()
+                .enumerate()
+                .filter(|(_, root)| path.starts_with(root.as_str()))
+                .max_by_key(|(_, root)| root.len());
+            if let Some((index, _)) = root {
+                wasted[index] += file.size;
+            }
+        }
+    }
+
+    roots
+        .into_iter()
+        .zip(wasted)
+        .map(|(root, wasted_space)| RootWaste {
+            root: root.clone(),
+            wasted_space,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DuplicateGroup, FileEntry};
+    use tempfile::TempDir;
+
+    fn scan(roots: &[&str], groups: Vec<DuplicateGroup>) -> (ScanOptions, ScanResult) {
+        let options = ScanOptions {
+            root_paths: roots.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        };
+        (options, ScanResult::new(groups, 10, Vec::new(), 7))
+    }
+
+    fn group(hash: &str, size: u64, paths: &[&str]) -> DuplicateGroup {
+        let files = paths
+            .iter()
+            .map(|p| FileEntry::new(p.to_string(), size, None))
+            .collect();
+        DuplicateGroup::new(hash.to_string(), size, files)
+    }
+
+    #[test]
+    fn test_entry_splits_waste_by_root() {
+        let (options, result) = scan(
+            &["/data", "/data/photos", "/backup"],
+            vec![
+                group("a", 100, &["/data/a", "/backup/a", "/data/photos/a"]),
+                group("b", 10, &["/backup/b", "/backup/c"]),
+            ],
+        );
+
+        let entry = ScanHistoryEntry::from_scan("scan_1", 1000, &options, &result);
+
+        assert_eq!(entry.wasted_space, 210);
+        assert_eq!(entry.duplicate_groups, 2);
+        assert_eq!(
+            entry.wasted_by_root,
+            vec![
+                RootWaste {
+                    root: "/backup".to_string(),
+                    wasted_space: 110
+                },
+                RootWaste {
+                    root: "/data".to_string(),
+                    wasted_space: 0
+                },
+                RootWaste {
+                    root: "/data/photos".to_string(),
+                    wasted_space: 100
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_and_list_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let store = HistoryStore::new(dir.path().join("history").join("scans.json"));
+        assert!(store.list().unwrap().is_empty());
+
+        let (options, result) = scan(&["/data"], Vec::new());
+        store
+            .record(ScanHistoryEntry::from_scan("old", 100, &options, &result))
+            .unwrap();
+        store
+            .record(ScanHistoryEntry::from_scan("new", 200, &options, &result))
+            .unwrap();
+
+        let ids: Vec<_> = store.list().unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["new", "old"]);
+
+        store.clear().unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.clear().is_ok());
+    }
+
+    #[test]
+    fn test_corrupt_history_is_not_overwritten() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("scans.json");
+        fs::write(&path, b"not json").unwrap();
+        let store = HistoryStore::new(&path);
+
+        let (options, result) = scan(&["/data"], Vec::new());
+        let recorded = store.record(ScanHistoryEntry::from_scan("s", 1, &options, &result));
+
+        assert!(matches!(recorded, Err(ScannerError::History(_))));
+        assert_eq!(fs::read(&path).unwrap(), b"not json");
+    }
+
+    #[test]
+    fn test_wasted_space_trends() {
+        let (options, before) = scan(&["/data"], vec![group("a", 100, &["/data/a", "/data/b"])]);
+        let (_, after) = scan(&["/data"], Vec::new());
+        let entries = vec![
+            ScanHistoryEntry::from_scan("second", 200, &options, &after),
+            ScanHistoryEntry::from_scan("first", 100, &options, &before),
+        ];
+
+        let trends = wasted_space_trends(&entries);
+
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].root, "/data");
+        let points: Vec<_> = trends[0]
+            .points
+            .iter()
+            .map(|p| (p.scan_id.as_str(), p.wasted_space))
+            .collect();
+        assert_eq!(points, vec![("first", 100), ("second", 0)]);
+    }
+}
@@ -2,17 +2,30 @@
 //!
 //! This crate provides the Rust backend for the Duplicate File Detector application.
 //! It includes modules for:
-//! - Directory scanning with filtering
-//! - MD5 hashing with parallel processing
+//! - Directory scanning with filtering and empty directory detection
+//! - Scan checkpointing for resuming interrupted scans
+//! - MD5/SHA-256/BLAKE3 hashing with parallel processing
 //! - Duplicate detection and grouping
+//! - Near-duplicate image detection with perceptual hashes
+//! - Matching files against checksum manifests
+//! - JSON, CSV, HTML and fdupes report export, and fdupes import
+//! - Saving, reopening and comparing scan sessions
+//! - A local history of completed scans with wasted space trends
 //! - Tauri command handlers
 //! - Thread-safe state management
 
+pub mod atomic;
+pub mod checkpoint;
 pub mod commands;
 pub mod duplicates;
 pub mod error;
 pub mod hasher;
+pub mod history;
+pub mod manifest;
+pub mod report;
 pub mod scanner;
+pub mod session;
+pub mod similarity;
 pub mod state;
 pub mod types;
 
@@ -35,7 +48,26 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::start_scan,
             commands::cancel_scan,
+            commands::list_interrupted_scans,
+            commands::resume_scan,
+            commands::discard_interrupted_scan,
+            commands::save_session,
+            commands::list_sessions,
+            commands::load_session,
+            commands::diff_sessions,
+            commands::delete_session,
+            commands::list_scan_history,
+            commands::scan_history_trends,
+            commands::clear_scan_history,
             commands::delete_files,
+            commands::find_empty_directories,
+            commands::delete_empty_directories,
+            commands::query_groups,
+            commands::folder_breakdown,
+            commands::export_manifest,
+            commands::export_report,
+            commands::import_fdupes,
+            commands::verify_baseline,
             commands::select_folders,
         ])
         .run(tauri::generate_context!())
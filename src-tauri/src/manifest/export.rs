@@ -0,0 +1,190 @@
+//! Writers for hash manifests.
+//!
+//! Checksum lists use the GNU coreutils format, so MD5 and SHA-256 exports
+//! can be checked with `md5sum -c` / `sha256sum -c` (and BLAKE3 exports with
+//! `b3sum -c`). The JSON variant also records size and modification time,
+//! which makes it usable as an integrity baseline.
+
+use super::{ManifestDocument, ManifestRecord, MANIFEST_VERSION};
+use crate::atomic::write_atomic;
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::{FileEntry, HashAlgorithm, ManifestFormat};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes hashes to `path` in the given format.
+///
+/// The manifest replaces `path` atomically (see [`write_atomic`]).
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `format` - Checksum list or JSON
+/// * `algorithm` - Algorithm the hashes were computed with
+/// * `hashes` - (file, hash) pairs to export
+///
+/// # Returns
+/// The number of files written.
+pub fn export_manifest(
+    path: &Path,
+    format: ManifestFormat,
+    algorithm: HashAlgorithm,
+    hashes: &[(FileEntry, String)],
+) -> ScannerResult<u64> {
+    let mut sorted: Vec<&(FileEntry, String)> = hashes.iter().collect();
+    sorted.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+
+    write_atomic(path, |mut writer| match format {
+        ManifestFormat::Checksums => write_checksums(&mut writer, &sorted),
+        ManifestFormat::Json => write_json(&mut writer, algorithm, &sorted),
+    })
+    .map_err(|e| ScannerError::Manifest(format!("{}: {}", path.display(), e)))?;
+
+    Ok(sorted.len() as u64)
+}
+
+/// Writes `<hex>  <path>` lines, escaping file names like GNU coreutils.
+fn write_checksums<W: Write>(writer: &mut W, hashes: &[&(FileEntry, String)]) -> io::Result<()> {
+    for (file, hash) in hashes {
+        if file.path.contains(['\\', '\n', '\r']) {
+            writeln!(writer, "\\{}  {}", hash, escape(&file.path))?;
+        } else {
+            writeln!(writer, "{}  {}", hash, file.path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a versioned JSON manifest with size and modification time.
+fn write_json<W: Write>(
+    writer: &mut W,
+    algorithm: HashAlgorithm,
+    hashes: &[&(FileEntry, String)],
+) -> io::Result<()> {
+    let document = ManifestDocument {
+        version: MANIFEST_VERSION,
+        algorithm,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        files: hashes
+            .iter()
+            .map(|(file, hash)| ManifestRecord {
+                path: file.path.clone(),
+                size: file.size,
+                modified: file.modified.clone(),
+                hash: hash.clone(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *writer, &document)?;
+    writeln!(writer)
+}
+
+/// Escapes `\`, newlines and carriage returns in a file name.
+fn escape(path: &str) -> String {
+    path.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::load_manifest;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn hashes() -> Vec<(FileEntry, String)> {
+        vec![
+            (
+                FileEntry::new(
+                    "/data/b.txt".to_string(),
+                    11,
+                    Some("1700000000".to_string()),
+                ),
+                "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+            ),
+            (
+                FileEntry::new("/data/a\nodd\\name".to_string(), 3, None),
+                "acbd18db4cc2f85cedef654fccc4a4d8".to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_export_checksums() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("files.md5");
+
+        let count = export_manifest(
+            &path,
+            ManifestFormat::Checksums,
+            HashAlgorithm::Md5,
+            &hashes(),
+        )
+        .unwrap();
+
+        assert_eq!(count, 2);
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            "\\acbd18db4cc2f85cedef654fccc4a4d8  /data/a\\nodd\\\\name\n\
+             5eb63bbbe01eeed093cb22bb8f5acdc3  /data/b.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_exported_manifests_load_back() {
+        let dir = TempDir::new().unwrap();
+
+        for (name, format) in [
+            ("files.md5", ManifestFormat::Checksums),
+            ("files.json", ManifestFormat::Json),
+        ] {
+            let path = dir.path().join(name);
+            export_manifest(&path, format, HashAlgorithm::Md5, &hashes()).unwrap();
+
+            let manifest = load_manifest(&path).unwrap();
+            assert_eq!(manifest.algorithm, HashAlgorithm::Md5);
+            assert_eq!(manifest.entries.len(), 2);
+            assert_eq!(manifest.entries[0].path, "/data/a\nodd\\name");
+        }
+    }
+
+    #[test]
+    fn test_json_records_size_and_mtime() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+
+        export_manifest(
+            &path,
+            ManifestFormat::Json,
+            HashAlgorithm::Sha256,
+            &hashes(),
+        )
+        .unwrap();
+
+        let document: ManifestDocument =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(document.version, MANIFEST_VERSION);
+        assert_eq!(document.algorithm, HashAlgorithm::Sha256);
+        assert_eq!(document.files[1].path, "/data/b.txt");
+        assert_eq!(document.files[1].size, 11);
+        assert_eq!(document.files[1].modified.as_deref(), Some("1700000000"));
+    }
+
+    #[test]
+    fn test_export_to_missing_directory_fails() {
+        let result = export_manifest(
+            Path::new("/nonexistent/dir/files.md5"),
+            ManifestFormat::Checksums,
+            HashAlgorithm::Md5,
+            &hashes(),
+        );
+
+        assert!(matches!(result, Err(ScannerError::Manifest(_))));
+    }
+}
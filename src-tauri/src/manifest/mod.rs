@@ -0,0 +1,385 @@
+//! Hash manifests: export, verification and use as a virtual reference set.
+//!
+//! Exports the scan's hashes as `md5sum`/`sha256sum`-compatible checksum
+//! lists or JSON baselines, and verifies files against such a baseline.
+//! Loads manifests and exported scan reports, then hashes scanned files with
+//! each manifest's algorithm to find content that is already known, without
+//! the listed files being present.
+
+pub mod export;
+pub mod parse;
+pub mod verify;
+
+pub use export::export_manifest;
+pub use parse::{parse_checksums, parse_json, parse_report};
+pub use verify::verify_manifest;
+
+use crate::error::{ScannerError, ScannerResult};
+use crate::hasher::{hash_files_parallel_observed, HashOptions};
+use crate::types::{FileEntry, HashAlgorithm, ManifestMatch, ScanError};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Current version of the JSON manifest format.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// A JSON manifest: hashes with size and modification time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDocument {
+    /// Format version (MANIFEST_VERSION when written).
+    pub version: u32,
+
+    /// Algorithm the hashes were computed with.
+    pub algorithm: HashAlgorithm,
+
+    /// When the manifest was written, as seconds since the Unix epoch.
+    pub created_at: u64,
+
+    /// Hashed files, sorted by path.
+    pub files: Vec<ManifestRecord>,
+}
+
+/// A file in a JSON manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestRecord {
+    /// Absolute path to the file.
+    pub path: String,
+
+    /// File size in bytes.
+    pub size: u64,
+
+    /// Last modification time, as recorded by the scanner.
+    #[serde(default)]
+    pub modified: Option<String>,
+
+    /// Hash of the file content (lowercase hex string).
+    pub hash: String,
+}
+
+/// A file listed in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Path as written in the manifest (may be relative).
+    pub path: String,
+
+    /// Lowercase hex digest.
+    pub hash: String,
+
+    /// File size in bytes, if the manifest records it.
+    pub size: Option<u64>,
+
+    /// Last modification time, if the manifest records it.
+    pub modified: Option<String>,
+}
+
+impl ManifestEntry {
+    /// Creates a new ManifestEntry.
+    pub fn new(path: String, hash: String, size: Option<u64>) -> Self {
+        Self {
+            path,
+            hash,
+            size,
+            modified: None,
+        }
+    }
+
+    /// Sets the recorded modification time.
+    pub fn with_modified(mut self, modified: Option<String>) -> Self {
+        self.modified = modified;
+        self
+    }
+}
+
+/// A loaded manifest: one algorithm and the files it lists.
+#[derive(Debug, Clone)]
+pub struct HashManifest {
+    /// Path of the manifest file.
+    pub source: String,
+
+    /// Algorithm the digests were computed with.
+    pub algorithm: HashAlgorithm,
+
+    /// Listed files.
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl HashManifest {
+    /// Creates a new HashManifest.
+    pub fn new(source: &str, algorithm: HashAlgorithm, entries: Vec<ManifestEntry>) -> Self {
+        Self {
+            source: source.to_string(),
+            algorithm,
+            entries,
+        }
+    }
+}
+
+/// Loads a checksum list, JSON manifest or exported JSON scan report.
+///
+/// Files whose first non-whitespace character is `{` are read as JSON;
+/// anything else as a checksum list.
+pub fn load_manifest(path: &Path) -> ScannerResult<HashManifest> {
+    let source = path.display().to_string();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| ScannerError::Manifest(format!("{}: {}", source, e)))?;
+
+    let manifest = if text.trim_start().starts_with('{') {
+        parse_json(&text, &source)?
+    } else {
+        parse_checksums(&text, &source)?
+    };
+
+    info!(
+        "Loaded {} {:?} checksums from {}",
+        manifest.entries.len(),
+        manifest.algorithm,
+        source
+    );
+    Ok(manifest)
+}
+
+/// Finds scanned files whose content is listed in any of the manifests.
+///
+/// Files are hashed once per algorithm used by the manifests. Hashes the
+/// scan already computed with the same algorithm are reused. If every entry
+/// for an algorithm records its size, only files of those sizes are hashed.
+/// Empty files are never matched.
+///
+/// # Arguments
+/// * `manifests` - Loaded manifests
+/// * `files` - All scanned files
+/// * `options` - Hashing options of the scan; the algorithm is overridden per manifest
+/// * `known_hashes` - Hashes the scan computed with `options.algorithm`
+/// * `is_cancelled` - Returns true if the scan was cancelled; remaining files are skipped
+///
+/// # Returns
+/// One match per scanned file and manifest, plus files that could not be hashed.
+pub fn match_manifests<C>(
+    manifests: &[HashManifest],
+    files: &[FileEntry],
+    options: &HashOptions,
+    known_hashes: &[(FileEntry, String)],
+    is_cancelled: C,
+) -> (Vec<ManifestMatch>, Vec<ScanError>)
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let mut matches = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut algorithms: Vec<HashAlgorithm> = manifests.iter().map(|m| m.algorithm).collect();
+    algorithms.sort_by_key(|a| *a as u8);
+    algorithms.dedup();
+
+    for algorithm in algorithms {
+        let relevant: Vec<&HashManifest> = manifests
+            .iter()
+            .filter(|m| m.algorithm == algorithm)
+            .collect();
+
+        let sizes: Option<HashSet<u64>> = relevant
+            .iter()
+            .flat_map(|m| &m.entries)
+            .map(|e| e.size)
+            .collect();
+
+        let candidates: Vec<FileEntry> = files
+            .iter()
+            .filter(|f| f.size > 0)
+            .filter(|f| sizes.as_ref().is_none_or(|s| s.contains(&f.size)))
+            .cloned()
+            .collect();
+
+        let hashed = hash_with(
+            algorithm,
+            candidates,
+            options,
+            known_hashes,
+            &is_cancelled,
+            &mut errors,
+        );
+        if is_cancelled() {
+            break;
+        }
+
+        for manifest in relevant {
+            let mut known: HashMap<&str, Vec<String>> = HashMap::new();
+            for entry in &manifest.entries {
+                known
+                    .entry(entry.hash.as_str())
+                    .or_default()
+                    .push(entry.path.clone());
+            }
+
+            for (file, hash) in &hashed {
+                if let Some(paths) = known.get(hash.as_str()) {
+                    matches.push(ManifestMatch::new(
+                        file.clone(),
+                        hash.clone(),
+                        algorithm,
+                        manifest.source.clone(),
+                        paths.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        a.file
+            .path
+            .cmp(&b.file.path)
+            .then_with(|| a.manifest.cmp(&b.manifest))
+    });
+
+    debug!("{} scanned files match manifest entries", matches.len());
+    (matches, errors)
+}
+
+/// Hashes `files` with `algorithm`, reusing the scan's hashes when the
+/// algorithm matches.
+fn hash_with<C>(
+    algorithm: HashAlgorithm,
+    files: Vec<FileEntry>,
+    options: &HashOptions,
+    known_hashes: &[(FileEntry, String)],
+    is_cancelled: &C,
+    errors: &mut Vec<ScanError>,
+) -> Vec<(FileEntry, String)>
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let reusable: HashMap<&str, &str> = if algorithm == options.algorithm {
+        known_hashes
+            .iter()
+            .map(|(file, hash)| (file.path.as_str(), hash.as_str()))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut hashed = Vec::new();
+    let mut to_hash = Vec::new();
+    for file in files {
+        match reusable.get(file.path.as_str()) {
+            Some(hash) => {
+                let hash = hash.to_string();
+                hashed.push((file, hash));
+            }
+            None => to_hash.push(file),
+        }
+    }
+
+    let options = HashOptions {
+        algorithm,
+        ..options.clone()
+    };
+    for result in hash_files_parallel_observed(to_hash, &options, |_, _| {}, is_cancelled) {
+        match result.hash {
+            Ok(hash) => hashed.push((result.file, hash)),
+            Err(message) => errors.push(ScanError::new(result.file.path, message)),
+        }
+    }
+
+    hashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &[u8]) -> FileEntry {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        FileEntry::new(path.display().to_string(), content.len() as u64, None)
+    }
+
+    #[test]
+    fn test_load_and_match_sha256_manifest() {
+        let dir = TempDir::new().unwrap();
+        let known = write(&dir, "download.txt", b"hello world");
+        let unknown = write(&dir, "new.txt", b"something else");
+        let empty = write(&dir, "empty.txt", b"");
+
+        let manifest_path = dir.path().join("SHA256SUMS");
+        std::fs::write(
+            &manifest_path,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  archive/hello.txt\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  archive/empty.txt\n",
+        )
+        .unwrap();
+        let manifest = load_manifest(&manifest_path).unwrap();
+
+        let (matches, errors) = match_manifests(
+            &[manifest],
+            &[known.clone(), unknown, empty],
+            &HashOptions::default(),
+            &[],
+            || false,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].file, known);
+        assert_eq!(matches[0].algorithm, HashAlgorithm::Sha256);
+        assert_eq!(
+            matches[0].known_paths,
+            vec!["archive/hello.txt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_reuses_scan_hashes_for_same_algorithm() {
+        let dir = TempDir::new().unwrap();
+        let file = write(&dir, "a.txt", b"real content");
+        let manifest = HashManifest::new(
+            "list.md5",
+            HashAlgorithm::Md5,
+            vec![ManifestEntry::new("x".to_string(), "0".repeat(32), None)],
+        );
+        // A stale hash from the scan is trusted rather than recomputed
+        let known_hashes = vec![(file.clone(), "0".repeat(32))];
+
+        let (matches, _) = match_manifests(
+            &[manifest],
+            &[file],
+            &HashOptions::default(),
+            &known_hashes,
+            || false,
+        );
+
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[test]
+    fn test_sizes_limit_hashing() {
+        let dir = TempDir::new().unwrap();
+        let file = write(&dir, "a.txt", b"hello world");
+        let manifest = HashManifest::new(
+            "report.json",
+            HashAlgorithm::Md5,
+            vec![ManifestEntry::new(
+                "/old/a.txt".to_string(),
+                "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+                Some(999),
+            )],
+        );
+
+        let (matches, _) =
+            match_manifests(&[manifest], &[file], &HashOptions::default(), &[], || false);
+
+        // Same content, but the recorded size rules the file out before hashing
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_load_missing_manifest() {
+        let result = load_manifest(Path::new("/nonexistent/SHA256SUMS"));
+        assert!(matches!(result, Err(ScannerError::Manifest(_))));
+    }
+}
@@ -0,0 +1,324 @@
+//! Parsers for checksum manifests and exported scan reports.
+//!
+//! Understands the GNU coreutils format written by `md5sum`, `sha256sum`
+//! and `b3sum` (`<hex>  <path>`, with `*` marking binary mode and a leading
+//! `\` marking escaped file names), the BSD tag format (`SHA256 (path) = hex`),
+//! and the app's own JSON manifests and scan results.
+
+use super::{HashManifest, ManifestDocument, ManifestEntry};
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::{HashAlgorithm, ScanResult};
+
+/// Parses a checksum manifest in GNU or BSD format.
+///
+/// Blank lines and `#` comments are ignored, as are lines that don't look
+/// like checksums. GNU-format digests of 64 hex digits are read as SHA-256
+/// unless `source` is named like a BLAKE3 manifest (`.b3`, `.blake3`).
+///
+/// # Arguments
+/// * `text` - Manifest contents
+/// * `source` - Manifest file path, used for algorithm hints and errors
+///
+/// # Returns
+/// The manifest, or an error if it has no checksums or mixes algorithms.
+pub fn parse_checksums(text: &str, source: &str) -> ScannerResult<HashManifest> {
+    let mut algorithm: Option<HashAlgorithm> = None;
+    let mut entries = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((line_algorithm, hash, path)) =
+            parse_bsd_line(line).or_else(|| parse_gnu_line(line, source))
+        else {
+            continue;
+        };
+
+        match algorithm {
+            None => algorithm = Some(line_algorithm),
+            Some(existing) if existing != line_algorithm => {
+                return Err(ScannerError::Manifest(format!(
+                    "{} mixes checksum algorithms",
+                    source
+                )));
+            }
+            Some(_) => {}
+        }
+
+        entries.push(ManifestEntry::new(path, hash.to_ascii_lowercase(), None));
+    }
+
+    match algorithm {
+        Some(algorithm) => Ok(HashManifest::new(source, algorithm, entries)),
+        None => Err(ScannerError::Manifest(format!(
+            "No checksums found in {}",
+            source
+        ))),
+    }
+}
+
+/// Parses a JSON manifest, falling back to an exported scan result.
+pub fn parse_json(json: &str, source: &str) -> ScannerResult<HashManifest> {
+    match serde_json::from_str::<ManifestDocument>(json) {
+        Ok(document) => Ok(HashManifest::new(
+            source,
+            document.algorithm,
+            document
+                .files
+                .into_iter()
+                .map(|record| {
+                    ManifestEntry::new(record.path, record.hash, Some(record.size))
+                        .with_modified(record.modified)
+                })
+                .collect(),
+        )),
+        Err(_) => parse_report(json, source),
+    }
+}
+
+/// Reads the files of an exported scan result as manifest entries.
+///
+/// Groups matched by audio payload are skipped, since their hash doesn't
+/// cover the whole file. Results saved before the algorithm was recorded
+/// are assumed to use the app's defaults: MD5 for 32 hex digits, BLAKE3
+/// for 64.
+pub fn parse_report(json: &str, source: &str) -> ScannerResult<HashManifest> {
+    let report: ScanResult = serde_json::from_str(json)
+        .map_err(|e| ScannerError::Manifest(format!("{} is not a scan report: {}", source, e)))?;
+
+    let algorithm = report.hash_algorithm.or_else(|| {
+        report
+            .duplicate_groups
+            .first()
+            .and_then(|g| algorithm_for_length(g.hash.len(), HashAlgorithm::Blake3))
+    });
+    let Some(algorithm) = algorithm else {
+        return Err(ScannerError::Manifest(format!(
+            "No checksums found in {}",
+            source
+        )));
+    };
+
+    let entries = report
+        .duplicate_groups
+        .into_iter()
+        .filter(|group| !group.metadata_differs)
+        .flat_map(|group| {
+            let hash = group.hash;
+            group.files.into_iter().map(move |file| {
+                ManifestEntry::new(file.path, hash.clone(), Some(file.size))
+                    .with_modified(file.modified)
+            })
+        })
+        .collect();
+
+    Ok(HashManifest::new(source, algorithm, entries))
+}
+
+/// Parses `MD5 (path) = hex`, `SHA256 (path) = hex` or `BLAKE3 (path) = hex`.
+fn parse_bsd_line(line: &str) -> Option<(HashAlgorithm, &str, String)> {
+    let (tag, rest) = line.split_once(" (")?;
+    let algorithm = match tag {
+        "MD5" => HashAlgorithm::Md5,
+        "SHA256" => HashAlgorithm::Sha256,
+        "BLAKE3" => HashAlgorithm::Blake3,
+        _ => return None,
+    };
+
+    let (path, hash) = rest.rsplit_once(") = ")?;
+    if !is_hex(hash) || algorithm_for_length(hash.len(), algorithm) != Some(algorithm) {
+        return None;
+    }
+
+    Some((algorithm, hash, path.to_string()))
+}
+
+/// Parses `<hex>  <path>` or `<hex> *<path>`, optionally escaped with a leading `\`.
+fn parse_gnu_line<'a>(line: &'a str, source: &str) -> Option<(HashAlgorithm, &'a str, String)> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let (hash, rest) = line.split_once(' ')?;
+    let path = rest.strip_prefix([' ', '*'])?;
+    if !is_hex(hash) || path.is_empty() {
+        return None;
+    }
+
+    let wide = if is_blake3_manifest(source) {
+        HashAlgorithm::Blake3
+    } else {
+        HashAlgorithm::Sha256
+    };
+    let algorithm = algorithm_for_length(hash.len(), wide)?;
+
+    let path = if escaped {
+        unescape(path)
+    } else {
+        path.to_string()
+    };
+    Some((algorithm, hash, path))
+}
+
+/// Maps a hex digest length to an algorithm; `wide` decides between the
+/// 256-bit algorithms.
+fn algorithm_for_length(len: usize, wide: HashAlgorithm) -> Option<HashAlgorithm> {
+    match len {
+        32 => Some(HashAlgorithm::Md5),
+        64 => Some(wide),
+        _ => None,
+    }
+}
+
+/// Returns true if the manifest's file name marks it as a BLAKE3 manifest.
+fn is_blake3_manifest(source: &str) -> bool {
+    let lower = source.to_ascii_lowercase();
+    lower.ends_with(".b3") || lower.ends_with(".blake3") || lower.contains("b3sum")
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Reverses GNU escaping of `\\`, `\n` and `\r` in file names.
+fn unescape(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DuplicateGroup, FileEntry};
+
+    const MD5_HELLO: &str = "5eb63bbbe01eeed093cb22bb8f5acdc3";
+    const SHA256_HELLO: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    #[test]
+    fn test_parse_md5sum() {
+        let text = format!(
+            "# archive checksums\n{}  photos/a.jpg\n{} *bin/tool.exe\n\nnot a checksum line\n",
+            MD5_HELLO,
+            MD5_HELLO.to_uppercase()
+        );
+
+        let manifest = parse_checksums(&text, "archive.md5").unwrap();
+
+        assert_eq!(manifest.algorithm, HashAlgorithm::Md5);
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(manifest.entries[0].path, "photos/a.jpg");
+        assert_eq!(manifest.entries[1].path, "bin/tool.exe");
+        assert_eq!(manifest.entries[1].hash, MD5_HELLO);
+    }
+
+    #[test]
+    fn test_parse_sha256sum_and_b3sum() {
+        let text = format!("{}  a.txt\n", SHA256_HELLO);
+
+        assert_eq!(
+            parse_checksums(&text, "SHA256SUMS").unwrap().algorithm,
+            HashAlgorithm::Sha256
+        );
+        assert_eq!(
+            parse_checksums(&text, "files.b3").unwrap().algorithm,
+            HashAlgorithm::Blake3
+        );
+    }
+
+    #[test]
+    fn test_parse_bsd_tags() {
+        let text = format!("SHA256 (dir/my file (1).txt) = {}\n", SHA256_HELLO);
+
+        let manifest = parse_checksums(&text, "list").unwrap();
+
+        assert_eq!(manifest.algorithm, HashAlgorithm::Sha256);
+        assert_eq!(manifest.entries[0].path, "dir/my file (1).txt");
+    }
+
+    #[test]
+    fn test_parse_escaped_file_name() {
+        let text = format!("\\{}  line\\nbreak\\\\x\n", MD5_HELLO);
+
+        let manifest = parse_checksums(&text, "list.md5").unwrap();
+
+        assert_eq!(manifest.entries[0].path, "line\nbreak\\x");
+    }
+
+    #[test]
+    fn test_mixed_algorithms_rejected() {
+        let text = format!("{}  a\n{}  b\n", MD5_HELLO, SHA256_HELLO);
+
+        assert!(matches!(
+            parse_checksums(&text, "mixed"),
+            Err(ScannerError::Manifest(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_manifest_rejected() {
+        assert!(parse_checksums("# nothing here\n", "empty.md5").is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let files = vec![
+            FileEntry::new("/a/x.bin".to_string(), 5, None),
+            FileEntry::new("/b/x.bin".to_string(), 5, None),
+        ];
+        let audio =
+            DuplicateGroup::new("f".repeat(64), 9, files.clone()).with_metadata_differs(true);
+        let report = ScanResult::new(
+            vec![
+                DuplicateGroup::new(SHA256_HELLO.to_string(), 5, files),
+                audio,
+            ],
+            10,
+            Vec::new(),
+            1,
+        )
+        .with_hash_algorithm(HashAlgorithm::Sha256);
+
+        let manifest =
+            parse_report(&serde_json::to_string(&report).unwrap(), "report.json").unwrap();
+
+        assert_eq!(manifest.algorithm, HashAlgorithm::Sha256);
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(manifest.entries[0].size, Some(5));
+    }
+
+    #[test]
+    fn test_parse_report_infers_algorithm() {
+        let files = vec![
+            FileEntry::new("/a".to_string(), 5, None),
+            FileEntry::new("/b".to_string(), 5, None),
+        ];
+        let mut report = ScanResult::new(
+            vec![DuplicateGroup::new(MD5_HELLO.to_string(), 5, files)],
+            2,
+            Vec::new(),
+            1,
+        );
+        report.hash_algorithm = None;
+
+        let manifest = parse_report(&serde_json::to_string(&report).unwrap(), "old.json").unwrap();
+
+        assert_eq!(manifest.algorithm, HashAlgorithm::Md5);
+    }
+}
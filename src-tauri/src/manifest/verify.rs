@@ -0,0 +1,258 @@
+//! Integrity verification against a manifest baseline.
+//!
+//! Re-hashes every file a baseline lists and compares the result with the
+//! recorded hash. A changed hash with unchanged size and modification time
+//! points at silent corruption (bit rot); a changed hash alongside a newer
+//! modification time is an ordinary edit.
+
+use super::HashManifest;
+use crate::hasher::{hash_files_parallel_observed, HashOptions, HashResult};
+use crate::scanner::format_system_time;
+use crate::types::{FileEntry, ScanError, VerifyMismatch, VerifyResult};
+use log::debug;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Verifies files against a baseline manifest.
+///
+/// Relative paths in the manifest are resolved against the manifest's
+/// directory, as `md5sum -c` would when run from there. When the baseline
+/// does not record size and modification time (plain checksum lists),
+/// every content change is reported as modified rather than corrupted.
+///
+/// # Arguments
+/// * `manifest` - The baseline
+/// * `scanned` - Files currently under the verified roots; those not in the
+///   baseline are reported as new
+/// * `options` - Hashing options; the algorithm is taken from the manifest
+/// * `on_hashed` - Called after each file is hashed with the running count
+/// * `is_cancelled` - Returns true if verification was cancelled
+pub fn verify_manifest<F, C>(
+    manifest: &HashManifest,
+    scanned: &[FileEntry],
+    options: &HashOptions,
+    on_hashed: F,
+    is_cancelled: C,
+) -> VerifyResult
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let base_dir = Path::new(&manifest.source).parent();
+    let mut result = VerifyResult::default();
+
+    // Resolved path -> index of the (last) entry listing it
+    let mut baseline: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in manifest.entries.iter().enumerate() {
+        let path = Path::new(&entry.path);
+        let resolved = match base_dir {
+            Some(dir) if path.is_relative() => dir.join(path).display().to_string(),
+            _ => entry.path.clone(),
+        };
+        baseline.insert(resolved, index);
+    }
+
+    let mut present = Vec::new();
+    for path in baseline.keys() {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => {
+                let modified = metadata.modified().ok().and_then(format_system_time);
+                present.push(FileEntry::new(path.clone(), metadata.len(), modified));
+            }
+            Ok(_) => result
+                .errors
+                .push(ScanError::new(path.clone(), "Not a regular file")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => result.missing.push(path.clone()),
+            Err(e) => result
+                .errors
+                .push(ScanError::new(path.clone(), e.to_string())),
+        }
+    }
+
+    let options = HashOptions {
+        algorithm: manifest.algorithm,
+        ..options.clone()
+    };
+    for hashed in hash_files_parallel_observed(present, &options, on_hashed, is_cancelled) {
+        let actual = match hashed.hash {
+            Ok(hash) => hash,
+            Err(message) => {
+                result
+                    .errors
+                    .push(ScanError::new(hashed.file.path, message));
+                continue;
+            }
+        };
+
+        result.files_verified += 1;
+        let entry = &manifest.entries[baseline[&hashed.file.path]];
+        if actual == entry.hash {
+            result.unchanged += 1;
+            continue;
+        }
+
+        let untouched = entry.size == Some(hashed.file.size)
+            && entry.modified.is_some()
+            && entry.modified == hashed.file.modified;
+        let mismatch = VerifyMismatch::new(
+            hashed.file,
+            entry.hash.clone(),
+            actual,
+            entry.size,
+            entry.modified.clone(),
+        );
+        if untouched {
+            result.corrupted.push(mismatch);
+        } else {
+            result.modified.push(mismatch);
+        }
+    }
+
+    let listed: HashSet<&str> = baseline.keys().map(String::as_str).collect();
+    result.new_files = scanned
+        .iter()
+        .filter(|f| !listed.contains(f.path.as_str()) && f.path != manifest.source)
+        .cloned()
+        .collect();
+
+    result.missing.sort();
+    result
+        .corrupted
+        .sort_by(|a, b| a.file.path.cmp(&b.file.path));
+    result
+        .modified
+        .sort_by(|a, b| a.file.path.cmp(&b.file.path));
+    result.new_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    debug!(
+        "Verified {} files: {} corrupted, {} modified, {} missing, {} new",
+        result.files_verified,
+        result.corrupted.len(),
+        result.modified.len(),
+        result.missing.len(),
+        result.new_files.len()
+    );
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{export_manifest, load_manifest};
+    use crate::types::{HashAlgorithm, ManifestFormat};
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn entry(path: &Path) -> FileEntry {
+        let metadata = fs::metadata(path).unwrap();
+        let modified = metadata.modified().ok().and_then(format_system_time);
+        FileEntry::new(path.display().to_string(), metadata.len(), modified)
+    }
+
+    fn hash(path: &Path) -> String {
+        crate::hasher::hash_file(path).unwrap()
+    }
+
+    #[test]
+    fn test_verify_against_json_baseline() {
+        let dir = TempDir::new().unwrap();
+        let intact = dir.path().join("intact.txt");
+        let rotten = dir.path().join("rotten.txt");
+        let edited = dir.path().join("edited.txt");
+        let gone = dir.path().join("gone.txt");
+        for path in [&intact, &rotten, &edited, &gone] {
+            fs::write(path, b"original").unwrap();
+        }
+
+        let hashes: Vec<(FileEntry, String)> = [&intact, &rotten, &edited, &gone]
+            .iter()
+            .map(|p| (entry(p), hash(p)))
+            .collect();
+        let baseline_path = dir.path().join("baseline.json");
+        export_manifest(
+            &baseline_path,
+            ManifestFormat::Json,
+            HashAlgorithm::Md5,
+            &hashes,
+        )
+        .unwrap();
+
+        // Same size, modification time restored: looks like bit rot
+        let mtime = fs::metadata(&rotten).unwrap().modified().unwrap();
+        fs::write(&rotten, b"origina!").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&rotten)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+
+        // A regular edit moves the modification time forward
+        fs::write(&edited, b"edited content").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&edited)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+
+        fs::remove_file(&gone).unwrap();
+        let added = dir.path().join("added.txt");
+        fs::write(&added, b"new").unwrap();
+
+        let manifest = load_manifest(&baseline_path).unwrap();
+        let scanned = vec![
+            entry(&baseline_path),
+            entry(&intact),
+            entry(&rotten),
+            entry(&edited),
+            entry(&added),
+        ];
+        let result = verify_manifest(
+            &manifest,
+            &scanned,
+            &HashOptions::default(),
+            |_, _| {},
+            || false,
+        );
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.files_verified, 3);
+        assert_eq!(result.unchanged, 1);
+        assert_eq!(result.corrupted.len(), 1);
+        assert_eq!(result.corrupted[0].file.path, rotten.display().to_string());
+        assert_eq!(result.modified.len(), 1);
+        assert_eq!(result.modified[0].file.path, edited.display().to_string());
+        assert_eq!(result.missing, vec![gone.display().to_string()]);
+        assert_eq!(result.new_files.len(), 1);
+        assert_eq!(result.new_files[0].path, added.display().to_string());
+    }
+
+    #[test]
+    fn test_relative_checksum_paths_resolve_against_manifest() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let file = dir.path().join("sub").join("a.txt");
+        fs::write(&file, b"hello world").unwrap();
+
+        let manifest_path = dir.path().join("files.md5");
+        fs::write(
+            &manifest_path,
+            "ffffffffffffffffffffffffffffffff  sub/a.txt\n",
+        )
+        .unwrap();
+
+        let manifest = load_manifest(&manifest_path).unwrap();
+        let result = verify_manifest(&manifest, &[], &HashOptions::default(), |_, _| {}, || false);
+
+        // Checksum lists carry no mtime, so a mismatch can't be called bit rot
+        assert!(result.corrupted.is_empty());
+        assert_eq!(result.modified.len(), 1);
+        assert_eq!(
+            result.modified[0].actual_hash,
+            "5eb63bbbe01eeed093cb22bb8f5acdc3"
+        );
+    }
+}
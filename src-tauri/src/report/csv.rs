@@ -0,0 +1,73 @@
+//! Flat CSV report: one row per duplicate file.
+//!
+//! Columns are `group_id,hash,size,path,modified,keeper,reference`. Group IDs
+//! start at 1 in result order (most wasted space first), `modified` is in
+//! seconds since the Unix epoch, and the flags are `true`/`false`.
+
+use crate::types::ScanResult;
+use std::io::{self, Write};
+
+/// Column names written as the first row.
+pub const CSV_HEADER: &str = "group_id,hash,size,path,modified,keeper,reference";
+
+/// Writes every file of every duplicate group as a CSV row.
+pub fn write_csv<W: Write>(writer: &mut W, result: &ScanResult) -> io::Result<()> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+
+    for (index, group) in result.duplicate_groups.iter().enumerate() {
+        for (position, file) in group.files.iter().enumerate() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                index + 1,
+                group.hash,
+                file.size,
+                escape(&file.path),
+                file.modified.as_deref().unwrap_or(""),
+                position == 0,
+                file.is_reference
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes a field if it holds a comma, quote or line break (RFC 4180).
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::tests::sample_result;
+
+    #[test]
+    fn test_csv_rows() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &sample_result()).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let hash = "ab".repeat(16);
+        assert_eq!(
+            text,
+            format!(
+                "{}\n1,{hash},1024,/photos/a.jpg,1700000000,true,false\n\
+                 1,{hash},1024,\"/backup/a, copy.jpg\",,false,false\n",
+                CSV_HEADER
+            )
+        );
+    }
+
+    #[test]
+    fn test_escape_quotes() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,273 @@
+//! fdupes/jdupes-compatible text output and import.
+//!
+//! The format is one path per line, each group followed by a blank line, as
+//! printed by `fdupes -r` and `jdupes -r`. Size lines such as
+//! `1024 bytes each:` (from `-S`) are ignored on import.
+//!
+//! Imported groups are not trusted as is: every listed file is stat'ed and
+//! re-hashed, and only files that still match are kept, so stale output
+//! never leads to deleting something that is no longer a duplicate. Paths
+//! are canonicalized and symlinks refused, so one file listed twice (under
+//! the same or another name) can't pass for its own duplicate.
+
+use crate::duplicates::find_duplicates;
+use crate::error::ScannerError;
+use crate::hasher::{hash_files_parallel_observed, HashOptions, HashResult};
+use crate::scanner::format_system_time;
+use crate::types::{DuplicateGroup, FileEntry, ScanError, ScanResult};
+use log::debug;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Writes duplicate groups the way `fdupes -r` prints them.
+///
+/// Scripts reading fdupes output treat every group as byte-identical, so
+/// audio groups whose tags differ are left out.
+pub fn write_fdupes<W: Write>(writer: &mut W, result: &ScanResult) -> io::Result<()> {
+    let identical = result
+        .duplicate_groups
+        .iter()
+        .filter(|g| !g.metadata_differs);
+    for group in identical {
+        for file in &group.files {
+            writeln!(writer, "{}", file.path)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Splits fdupes/jdupes output into groups of paths.
+///
+/// Groups with fewer than two paths are dropped.
+pub fn parse_fdupes(text: &str) -> Vec<Vec<String>> {
+    let mut groups = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            groups.push(std::mem::take(&mut current));
+        } else if !is_size_line(line) {
+            current.push(line.to_string());
+        }
+    }
+    groups.push(current);
+
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Re-checks imported groups against the disk.
+///
+/// Files are stat'ed and hashed; each imported group is then split by hash,
+/// so files that changed since the tool ran drop out. Missing or unreadable
+/// files and symlinks are reported as errors. A file is only kept the first
+/// time its canonical path is listed.
+///
+/// # Arguments
+/// * `groups` - Path groups from [`parse_fdupes`]
+/// * `options` - Hashing options
+/// * `on_hashed` - Called after each file is hashed with the running count
+/// * `is_cancelled` - Returns true if the import was cancelled
+///
+/// # Returns
+/// Confirmed duplicate groups sorted by wasted space, the number of files
+/// listed, and errors.
+pub fn verify_groups<F, C>(
+    groups: Vec<Vec<String>>,
+    options: &HashOptions,
+    on_hashed: F,
+    is_cancelled: C,
+) -> (Vec<DuplicateGroup>, u64, Vec<ScanError>)
+where
+    F: Fn(&HashResult, u64) + Send + Sync,
+    C: Fn() -> bool + Send + Sync,
+{
+    let mut errors = Vec::new();
+    let mut files = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+    let mut listed = 0;
+
+    for (index, group) in groups.into_iter().enumerate() {
+        for path in group {
+            listed += 1;
+            // Not following links: a link and its target are the same file
+            match std::fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    errors.push(ScanError::new(path, "Symbolic link"));
+                }
+                Ok(metadata) if metadata.is_file() => {
+                    let canonical = match std::fs::canonicalize(&path) {
+                        Ok(canonical) => canonical.display().to_string(),
+                        Err(e) => {
+                            errors.push(ScanError::new(path, e.to_string()));
+                            continue;
+                        }
+                    };
+                    if group_of.contains_key(&canonical) {
+                        debug!("Skipping {}: already listed", path);
+                        continue;
+                    }
+                    let modified = metadata.modified().ok().and_then(format_system_time);
+                    group_of.insert(canonical.clone(), index);
+                    files.push(FileEntry::new(canonical, metadata.len(), modified));
+                }
+                Ok(_) => errors.push(ScanError::new(path, "Not a regular file")),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let message = ScannerError::FileDisappeared(path.clone()).to_string();
+                    errors.push(ScanError::new(path, message));
+                }
+                Err(e) => errors.push(ScanError::new(path, e.to_string())),
+            }
+        }
+    }
+
+    let mut hashed: HashMap<usize, Vec<(FileEntry, String)>> = HashMap::new();
+    for result in hash_files_parallel_observed(files, options, on_hashed, is_cancelled) {
+        match result.hash {
+            Ok(hash) => hashed
+                .entry(group_of[&result.file.path])
+                .or_default()
+                .push((result.file, hash)),
+            Err(message) => errors.push(ScanError::new(result.file.path, message)),
+        }
+    }
+
+    let mut confirmed: Vec<DuplicateGroup> =
+        hashed.into_values().flat_map(find_duplicates).collect();
+    confirmed.sort_by_key(|g| std::cmp::Reverse(g.wasted_space()));
+
+    debug!(
+        "Imported {} listed files into {} confirmed groups",
+        listed,
+        confirmed.len()
+    );
+    (confirmed, listed, errors)
+}
+
+/// Returns true for `-S` size lines such as `1024 bytes each:`.
+fn is_size_line(line: &str) -> bool {
+    let Some(rest) = line.strip_suffix(" each:") else {
+        return false;
+    };
+    let Some((count, unit)) = rest.split_once(' ') else {
+        return false;
+    };
+    matches!(unit, "byte" | "bytes")
+        && !count.is_empty()
+        && count.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::tests::sample_result;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_fdupes() {
+        let mut out = Vec::new();
+        write_fdupes(&mut out, &sample_result()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "/photos/a.jpg\n/backup/a, copy.jpg\n\n"
+        );
+    }
+
+    #[test]
+    fn test_write_fdupes_skips_retagged_audio() {
+        let mut result = sample_result();
+        let files = vec![
+            FileEntry::new("/music/a.mp3".to_string(), 10, None),
+            FileEntry::new("/music/b.mp3".to_string(), 12, None),
+        ];
+        result
+            .duplicate_groups
+            .push(DuplicateGroup::new("h".to_string(), 10, files).with_metadata_differs(true));
+
+        let mut out = Vec::new();
+        write_fdupes(&mut out, &result).unwrap();
+
+        assert!(!String::from_utf8(out).unwrap().contains("/music/"));
+    }
+
+    #[test]
+    fn test_parse_fdupes_and_jdupes_sizes() {
+        let text = "1024 bytes each:\n/a/x\n/b/x\n\n/a/y\n/b/y\n/c/y\n\n/lonely\n\n";
+
+        let groups = parse_fdupes(text);
+
+        assert_eq!(
+            groups,
+            vec![
+                vec!["/a/x".to_string(), "/b/x".to_string()],
+                vec!["/a/y".to_string(), "/b/y".to_string(), "/c/y".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_without_trailing_blank_line() {
+        assert_eq!(parse_fdupes("/a\r\n/b").len(), 1);
+    }
+
+    #[test]
+    fn test_verify_groups_drops_changed_and_missing_files() {
+        let dir = TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name).display().to_string();
+        fs::write(path("a"), b"same content").unwrap();
+        fs::write(path("b"), b"same content").unwrap();
+        fs::write(path("c"), b"changed since").unwrap();
+
+        let groups = vec![vec![path("a"), path("b"), path("c"), path("gone")]];
+        let (confirmed, listed, errors) =
+            verify_groups(groups, &HashOptions::default(), |_, _| {}, || false);
+
+        assert_eq!(listed, 4);
+        assert_eq!(confirmed.len(), 1);
+        assert_eq!(confirmed[0].files.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, path("gone"));
+    }
+
+    #[test]
+    fn test_verify_groups_ignores_repeated_paths() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("only");
+        fs::write(&file, b"one copy").unwrap();
+        let path = file.display().to_string();
+        let dotted = dir.path().join(".").join("only").display().to_string();
+
+        let groups = vec![vec![path.clone(), path.clone()], vec![path.clone(), dotted]];
+        let (confirmed, listed, errors) =
+            verify_groups(groups, &HashOptions::default(), |_, _| {}, || false);
+
+        assert_eq!(listed, 4);
+        assert!(confirmed.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_groups_refuses_symlinks() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("target");
+        let link = dir.path().join("link");
+        fs::write(&target, b"one copy").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let groups = vec![vec![
+            target.display().to_string(),
+            link.display().to_string(),
+        ]];
+        let (confirmed, _, errors) =
+            verify_groups(groups, &HashOptions::default(), |_, _| {}, || false);
+
+        assert!(confirmed.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, link.display().to_string());
+    }
+}
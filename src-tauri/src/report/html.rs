@@ -0,0 +1,342 @@
+//! Self-contained static HTML report.
+//!
+//! The page has inline styles and no scripts, so it can be mailed or opened
+//! from a file share as is. It summarizes the scan, then lists each
+//! duplicate group with its keeper, followed by duplicate folders, content
+//! shared between roots, and errors.
+
+use crate::types::{FileEntry, OverlapMatrix, ScanResult};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;padding:0 1rem;color:#1f2933}\
+h1{margin-bottom:0}.generated{color:#616e7c;margin-top:.25rem}\
+dl.summary{display:grid;grid-template-columns:max-content auto;gap:.25rem 1.5rem}\
+dt{color:#616e7c}dd{margin:0;font-weight:600}\
+section.group{border:1px solid #d9e2ec;border-radius:6px;margin:1rem 0;padding:.5rem 1rem}\
+h3{font-size:1rem;margin:.5rem 0}.hash{font-family:monospace;color:#616e7c;font-size:.85rem}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.25rem .5rem;border-top:1px solid #e4e7eb}\
+td.path{font-family:monospace;word-break:break-all}\
+.keep{color:#0c6b58;font-weight:600}.reference{color:#2d3a8c}";
+
+/// Writes the report as a single HTML page.
+pub fn write_html<W: Write>(
+    writer: &mut W,
+    result: &ScanResult,
+    generated_at: u64,
+) -> io::Result<()> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html lang=\"en\">")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Duplicate File Report</title>")?;
+    writeln!(writer, "<style>{}</style>", STYLE)?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Duplicate File Report</h1>")?;
+    writeln!(
+        writer,
+        "<p class=\"generated\">Generated {}</p>",
+        format_timestamp(generated_at)
+    )?;
+
+    write_summary(writer, result)?;
+
+    writeln!(writer, "<h2>Duplicate files</h2>")?;
+    if result.duplicate_groups.is_empty() {
+        writeln!(writer, "<p>No duplicate files were found.</p>")?;
+    }
+    for (index, group) in result.duplicate_groups.iter().enumerate() {
+        writeln!(writer, "<section class=\"group\">")?;
+        writeln!(
+            writer,
+            "<h3>Group {}: {} files of {}, {} wasted{}</h3>",
+            index + 1,
+            group.files.len(),
+            format_bytes(group.size),
+            format_bytes(group.wasted_space()),
+            if group.metadata_differs {
+                " (audio matches, tags differ)"
+            } else {
+                ""
+            }
+        )?;
+        writeln!(writer, "<p class=\"hash\">{}</p>", escape(&group.hash))?;
+        writeln!(
+            writer,
+            "<table><tr><th></th><th>Path</th><th>Size</th><th>Modified</th></tr>"
+        )?;
+        for (position, file) in group.files.iter().enumerate() {
+            write_file_row(writer, file, position == 0)?;
+        }
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</section>")?;
+    }
+
+    if !result.duplicate_directories.is_empty() {
+        writeln!(writer, "<h2>Duplicate folders</h2>")?;
+        writeln!(
+            writer,
+            "<table><tr><th>Folders</th><th>Files</th><th>Size</th><th>Wasted</th></tr>"
+        )?;
+        for group in &result.duplicate_directories {
+            let directories: Vec<String> = group.directories.iter().map(|d| escape(d)).collect();
+            writeln!(
+                writer,
+                "<tr><td class=\"path\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                directories.join("<br>"),
+                group.file_count,
+                format_bytes(group.total_size),
+                format_bytes(group.wasted_space())
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+    }
+
+    if let Some(matrix) = &result.overlap_matrix {
+        write_overlap(writer, matrix)?;
+    }
+
+    if !result.errors.is_empty() {
+        writeln!(writer, "<h2>Errors</h2>")?;
+        writeln!(writer, "<table><tr><th>Path</th><th>Error</th></tr>")?;
+        for error in &result.errors {
+            writeln!(
+                writer,
+                "<tr><td class=\"path\">{}</td><td>{}</td></tr>",
+                escape(&error.path),
+                escape(&error.message)
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+    }
+
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")
+}
+
+/// Writes the totals shown at the top of the report.
+fn write_summary<W: Write>(writer: &mut W, result: &ScanResult) -> io::Result<()> {
+    let rows = [
+        ("Files scanned", result.total_files_scanned.to_string()),
+        (
+            "Duplicate groups",
+            result.duplicate_groups.len().to_string(),
+        ),
+        ("Duplicate files", result.total_duplicates_found.to_string()),
+        ("Wasted space", format_bytes(result.total_wasted_space)),
+        (
+            "Duplicate folders",
+            result.duplicate_directories.len().to_string(),
+        ),
+        ("Empty files", result.empty_files.len().to_string()),
+        ("Errors", result.errors.len().to_string()),
+        (
+            "Scan duration",
+            format!("{:.1} s", result.duration_ms as f64 / 1000.0),
+        ),
+    ];
+
+    writeln!(writer, "<dl class=\"summary\">")?;
+    for (label, value) in rows {
+        writeln!(writer, "<dt>{}</dt><dd>{}</dd>", label, value)?;
+    }
+    writeln!(writer, "</dl>")
+}
+
+/// Writes the content shared between each pair of roots, with each side's
+/// share of its own size.
+fn write_overlap<W: Write>(writer: &mut W, matrix: &OverlapMatrix) -> io::Result<()> {
+    let totals: HashMap<&str, u64> = matrix
+        .folders
+        .iter()
+        .map(|f| (f.path.as_str(), f.total_bytes))
+        .collect();
+    let share = |folder: &str, bytes: u64| match totals.get(folder) {
+        Some(&total) if total > 0 => format!("{:.0}%", bytes as f64 * 100.0 / total as f64),
+        _ => String::new(),
+    };
+
+    writeln!(writer, "<h2>Shared content</h2>")?;
+    if matrix.pairs.is_empty() {
+        writeln!(writer, "<p>The scanned folders share no files.</p>")?;
+        return Ok(());
+    }
+    writeln!(
+        writer,
+        "<table><tr><th>Folder</th><th>Folder</th><th>Shared files</th><th>Shared size</th><th>Share of each</th></tr>"
+    )?;
+    for pair in &matrix.pairs {
+        writeln!(
+            writer,
+            "<tr><td class=\"path\">{}</td><td class=\"path\">{}</td><td>{}</td><td>{}</td><td>{} / {}</td></tr>",
+            escape(&pair.folder_a),
+            escape(&pair.folder_b),
+            pair.shared_files,
+            format_bytes(pair.shared_bytes),
+            share(&pair.folder_a, pair.shared_bytes),
+            share(&pair.folder_b, pair.shared_bytes)
+        )?;
+    }
+    writeln!(writer, "</table>")
+}
+
+/// Writes one file of a group as a table row.
+fn write_file_row<W: Write>(writer: &mut W, file: &FileEntry, keeper: bool) -> io::Result<()> {
+    let mut marks = Vec::new();
+    if keeper {
+        marks.push("<span class=\"keep\">Keep</span>");
+    }
+    if file.is_reference {
+        marks.push("<span class=\"reference\">Reference</span>");
+    }
+
+    let modified = file
+        .modified
+        .as_deref()
+        .and_then(|m| m.parse().ok())
+        .map(format_timestamp)
+        .unwrap_or_default();
+
+    writeln!(
+        writer,
+        "<tr><td>{}</td><td class=\"path\">{}</td><td>{}</td><td>{}</td></tr>",
+        marks.join(" "),
+        escape(&file.path),
+        format_bytes(file.size),
+        modified
+    )
+}
+
+/// Escapes text for use in HTML content.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Formats bytes like the UI does (1024-based, up to two decimals).
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["Bytes", "KB", "MB", "GB", "TB"];
+
+    if bytes == 0 {
+        return "0 Bytes".to_string();
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let rounded = format!("{:.2}", value);
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}", trimmed, UNITS[unit])
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::tests::sample_result;
+    use crate::types::{FolderOverlap, OverlapFolder};
+
+    #[test]
+    fn test_html_report() {
+        let mut result = sample_result();
+        result.duplicate_groups[0].files[1].path = "/backup/<script>.jpg".to_string();
+
+        let mut out = Vec::new();
+        write_html(&mut out, &result, 1_700_000_000).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Generated 2023-11-14 22:13 UTC"));
+        assert!(html.contains("<dt>Wasted space</dt><dd>1 KB</dd>"));
+        assert!(html.contains("Group 1: 2 files of 1 KB, 1 KB wasted"));
+        assert!(html.contains("<span class=\"keep\">Keep</span>"));
+        assert!(html.contains("/backup/&lt;script&gt;.jpg"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_html_overlap_section() {
+        let result = sample_result().with_overlap_matrix(Some(OverlapMatrix {
+            folders: vec![
+                OverlapFolder {
+                    path: "/photos".to_string(),
+                    total_files: 1,
+                    total_bytes: 1024,
+                },
+                OverlapFolder {
+                    path: "/backup".to_string(),
+                    total_files: 4,
+                    total_bytes: 4096,
+                },
+            ],
+            pairs: vec![FolderOverlap {
+                folder_a: "/backup".to_string(),
+                folder_b: "/photos".to_string(),
+                shared_files: 1,
+                shared_bytes: 1024,
+            }],
+        }));
+
+        let mut out = Vec::new();
+        write_html(&mut out, &result, 0).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains("<h2>Shared content</h2>"));
+        assert!(html.contains("<td>1</td><td>1 KB</td><td>25% / 100%</td>"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 Bytes");
+        assert_eq!(format_bytes(512), "512 Bytes");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5 GB");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00 UTC");
+    }
+}
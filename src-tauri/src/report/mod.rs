@@ -0,0 +1,163 @@
+//! Scan result reports for sharing outside the app.
+//!
+//! Four formats are written:
+//! - JSON: the full scan result under a versioned envelope, including the
+//!   root overlap matrix. It can be loaded back as a reference manifest.
+//! - CSV: one row per duplicate file, for spreadsheets and scripts.
+//! - HTML: a self-contained static page with group summaries and the content
+//!   shared between roots.
+//! - fdupes: the plain text printed by `fdupes`/`jdupes`, which can also be
+//!   imported.
+//!
+//! In every format the first file of a group is its keeper: the copy the app
+//! keeps when all but one file is selected (reference copies come first).
+
+pub mod csv;
+pub mod fdupes;
+pub mod html;
+
+use crate::atomic::write_atomic;
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::{ReportFormat, ScanResult};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current version of the JSON report format.
+///
+/// Bumped only when a field is removed or changes meaning; new fields are
+/// added with defaults and leave the version unchanged.
+pub const REPORT_VERSION: u32 = 1;
+
+/// A JSON report: the scan result plus format version and creation time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportDocument {
+    /// Format version (REPORT_VERSION when written).
+    pub version: u32,
+
+    /// When the report was written, as seconds since the Unix epoch.
+    pub generated_at: u64,
+
+    /// The scan result, with its fields at the top level of the document.
+    #[serde(flatten)]
+    pub result: ScanResult,
+}
+
+/// Writes a scan result report to `path`.
+///
+/// The report replaces `path` atomically (see [`write_atomic`]).
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `format` - JSON, CSV, HTML or fdupes
+/// * `result` - The scan result to export
+pub fn export_report(path: &Path, format: ReportFormat, result: &ScanResult) -> ScannerResult<()> {
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    write_atomic(path, |mut writer| match format {
+        ReportFormat::Json => write_json(&mut writer, result, generated_at),
+        ReportFormat::Csv => csv::write_csv(&mut writer, result),
+        ReportFormat::Html => html::write_html(&mut writer, result, generated_at),
+        ReportFormat::Fdupes => fdupes::write_fdupes(&mut writer, result),
+    })
+    .map_err(|e| ScannerError::Report(format!("{}: {}", path.display(), e)))
+}
+
+/// Writes the versioned JSON report.
+fn write_json<W: Write>(writer: &mut W, result: &ScanResult, generated_at: u64) -> io::Result<()> {
+    let document = ReportDocument {
+        version: REPORT_VERSION,
+        generated_at,
+        result: result.clone(),
+    };
+
+    serde_json::to_writer_pretty(&mut *writer, &document)?;
+    writeln!(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::load_manifest;
+    use crate::types::{DuplicateGroup, FileEntry, HashAlgorithm};
+    use std::fs;
+    use tempfile::TempDir;
+
+    pub(super) fn sample_result() -> ScanResult {
+        let files = vec![
+            FileEntry::new(
+                "/photos/a.jpg".to_string(),
+                1024,
+                Some("1700000000".to_string()),
+            ),
+            FileEntry::new("/backup/a, copy.jpg".to_string(), 1024, None),
+        ];
+        ScanResult::new(
+            vec![DuplicateGroup::new("ab".repeat(16), 1024, files)],
+            10,
+            Vec::new(),
+            42,
+        )
+        .with_hash_algorithm(HashAlgorithm::Md5)
+    }
+
+    #[test]
+    fn test_json_report_is_versioned() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.json");
+
+        export_report(&path, ReportFormat::Json, &sample_result()).unwrap();
+
+        let document: ReportDocument =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(document.version, REPORT_VERSION);
+        assert!(document.generated_at > 0);
+        assert_eq!(document.result.duplicate_groups.len(), 1);
+        assert_eq!(document.result.total_wasted_space, 1024);
+    }
+
+    #[test]
+    fn test_json_report_loads_as_manifest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.json");
+
+        export_report(&path, ReportFormat::Json, &sample_result()).unwrap();
+
+        let manifest = load_manifest(&path).unwrap();
+        assert_eq!(manifest.algorithm, HashAlgorithm::Md5);
+        assert_eq!(manifest.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_export_leaves_neighbouring_files_alone() {
+        let dir = TempDir::new().unwrap();
+        let neighbour = dir.path().join("notes.tmp");
+        fs::write(&neighbour, b"keep me").unwrap();
+
+        export_report(
+            &dir.path().join("notes.html"),
+            ReportFormat::Html,
+            &sample_result(),
+        )
+        .unwrap();
+
+        assert_eq!(fs::read(&neighbour).unwrap(), b"keep me");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_export_to_missing_directory_fails() {
+        let result = export_report(
+            Path::new("/nonexistent/dir/report.html"),
+            ReportFormat::Html,
+            &sample_result(),
+        );
+
+        assert!(matches!(result, Err(ScannerError::Report(_))));
+    }
+}
@@ -0,0 +1,212 @@
+//! Empty directory detection and removal.
+//!
+//! A directory is empty when nothing below it but other empty directories
+//! remains. Detection works from the scan's directory layout, so it can also
+//! answer which directories *would* become empty once a set of files is
+//! deleted. Removal re-checks the disk and only ever deletes directories.
+
+use super::ScanOutput;
+use log::debug;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+/// Directory layout recorded by a scan.
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryListing {
+    /// Directories below the scan roots.
+    directories: Vec<String>,
+
+    /// Listed files.
+    files: Vec<String>,
+
+    /// Entries that were not listed as files (filtered out or unreadable).
+    unlisted: Vec<String>,
+}
+
+impl DirectoryListing {
+    /// Records the layout of a completed directory walk.
+    pub fn from_scan(output: &ScanOutput) -> Self {
+        Self {
+            directories: output.directories.clone(),
+            files: output.files.iter().map(|f| f.path.clone()).collect(),
+            unlisted: output.unlisted.clone(),
+        }
+    }
+
+    /// Finds directories that are empty once `removed` files are gone.
+    ///
+    /// Only the topmost directory of an empty subtree is reported, since
+    /// removing it removes everything below. Scan roots are never reported.
+    ///
+    /// # Arguments
+    /// * `removed` - Paths of files planned for deletion (may be empty)
+    ///
+    /// # Returns
+    /// Empty directories, sorted by path.
+    pub fn empty_directories(&self, removed: &HashSet<&str>) -> Vec<String> {
+        let mut occupied: HashSet<&Path> = HashSet::new();
+
+        let remaining = self
+            .files
+            .iter()
+            .filter(|path| !removed.contains(path.as_str()));
+        for path in remaining {
+            mark_occupied(&mut occupied, Path::new(path).parent());
+        }
+        // An unreadable directory may well have contents
+        for path in &self.unlisted {
+            mark_occupied(&mut occupied, Some(Path::new(path)));
+        }
+
+        let empty: HashSet<&Path> = self
+            .directories
+            .iter()
+            .map(Path::new)
+            .filter(|dir| !occupied.contains(dir))
+            .collect();
+
+        let mut topmost: Vec<String> = empty
+            .iter()
+            .filter(|dir| dir.parent().is_none_or(|parent| !empty.contains(parent)))
+            .map(|dir| dir.display().to_string())
+            .collect();
+        topmost.sort();
+
+        debug!(
+            "{} empty directories ({} planned deletions)",
+            topmost.len(),
+            removed.len()
+        );
+        topmost
+    }
+}
+
+/// Marks `dir` and all of its ancestors as occupied.
+fn mark_occupied<'a>(occupied: &mut HashSet<&'a Path>, dir: Option<&'a Path>) {
+    for ancestor in dir.into_iter().flat_map(Path::ancestors) {
+        if !occupied.insert(ancestor) {
+            break; // Ancestors above were marked by an earlier path
+        }
+    }
+}
+
+/// Returns true if `path` is a directory holding only empty directories.
+///
+/// Symbolic links count as content and are never followed.
+pub fn is_empty_tree(path: &Path) -> io::Result<bool> {
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() || !is_empty_tree(&entry.path())? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Removes a directory that holds only empty directories.
+///
+/// The tree is checked before anything is removed, and directories are then
+/// removed bottom-up with `remove_dir`, which refuses to delete anything that
+/// gained content in the meantime.
+pub fn remove_empty_tree(path: &Path) -> io::Result<()> {
+    if !is_empty_tree(path)? {
+        return Err(io::Error::other("Directory is not empty"));
+    }
+    remove_bottom_up(path)
+}
+
+fn remove_bottom_up(path: &Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        remove_bottom_up(&entry?.path())?;
+    }
+    std::fs::remove_dir(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::scan_directories;
+    use crate::types::ScanOptions;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn scan(dir: &TempDir, options: ScanOptions) -> DirectoryListing {
+        let options = ScanOptions {
+            root_paths: vec![dir.path().display().to_string()],
+            ..options
+        };
+        DirectoryListing::from_scan(&scan_directories(&options).unwrap())
+    }
+
+    fn path(dir: &TempDir, rel: &str) -> String {
+        dir.path().join(rel).display().to_string()
+    }
+
+    #[test]
+    fn test_reports_topmost_empty_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("empty/nested/deeper")).unwrap();
+        fs::create_dir_all(dir.path().join("full/sub")).unwrap();
+        fs::write(dir.path().join("full/sub/file.txt"), b"content").unwrap();
+
+        let listing = scan(&dir, ScanOptions::default());
+
+        assert_eq!(
+            listing.empty_directories(&HashSet::new()),
+            vec![path(&dir, "empty")]
+        );
+    }
+
+    #[test]
+    fn test_filtered_files_keep_directory_occupied() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("photos")).unwrap();
+        fs::write(dir.path().join("photos/a.jpg"), b"jpeg").unwrap();
+
+        let listing = scan(
+            &dir,
+            ScanOptions {
+                include_extensions: Some(vec!["txt".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(listing.empty_directories(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_planned_deletions_empty_directories() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("old/copies")).unwrap();
+        fs::write(dir.path().join("old/copies/a.txt"), b"a").unwrap();
+        fs::write(dir.path().join("old/copies/b.txt"), b"b").unwrap();
+        fs::write(dir.path().join("keep.txt"), b"k").unwrap();
+
+        let listing = scan(&dir, ScanOptions::default());
+        let a = path(&dir, "old/copies/a.txt");
+        let b = path(&dir, "old/copies/b.txt");
+
+        let partly: HashSet<&str> = [a.as_str()].into();
+        assert!(listing.empty_directories(&partly).is_empty());
+
+        let all: HashSet<&str> = [a.as_str(), b.as_str()].into();
+        assert_eq!(listing.empty_directories(&all), vec![path(&dir, "old")]);
+    }
+
+    #[test]
+    fn test_remove_empty_tree() {
+        let dir = TempDir::new().unwrap();
+        let empty = dir.path().join("empty");
+        fs::create_dir_all(empty.join("a/b")).unwrap();
+        let full = dir.path().join("full");
+        fs::create_dir_all(full.join("a")).unwrap();
+        fs::write(full.join("a/file.txt"), b"x").unwrap();
+
+        remove_empty_tree(&empty).unwrap();
+        assert!(!empty.exists());
+
+        assert!(remove_empty_tree(&full).is_err());
+        assert!(full.join("a/file.txt").exists());
+    }
+}
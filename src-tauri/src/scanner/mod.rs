@@ -1,7 +1,9 @@
 //! Directory scanning module for the Duplicate File Detector.
 //!
-//! Provides efficient recursive directory traversal with filtering support.
+//! Provides efficient recursive directory traversal with filtering support,
+//! and records the directory layout so empty directories can be reported.
 
+pub mod empty;
 pub mod filter;
 
 use crate::error::{ScannerError, ScannerResult};
@@ -21,6 +23,13 @@ pub struct ScanOutput {
 
     /// Non-fatal errors encountered during scanning.
     pub errors: Vec<ScanError>,
+
+    /// Directories found below the scan roots (the roots themselves excluded).
+    pub directories: Vec<String>,
+
+    /// Entries left out of `files`: filtered-out files and paths that could
+    /// not be read. Directories holding them are never reported as empty.
+    pub unlisted: Vec<String>,
 }
 
 impl ScanOutput {
@@ -29,6 +38,8 @@ impl ScanOutput {
         Self {
             files: Vec::new(),
             errors: Vec::new(),
+            directories: Vec::new(),
+            unlisted: Vec::new(),
         }
     }
 
@@ -57,7 +68,7 @@ impl Default for ScanOutput {
 /// # Returns
 /// A ScanOutput containing matching files and any errors encountered.
 pub fn scan_directories(options: &ScanOptions) -> ScannerResult<ScanOutput> {
-    let paths: Vec<PathBuf> = options.root_paths.iter().map(PathBuf::from).collect();
+    let paths: Vec<PathBuf> = options.scan_roots().iter().map(PathBuf::from).collect();
 
     // Validate paths exist
     for path in &paths {
@@ -107,6 +118,9 @@ fn scan_directory(
                     .path()
                     .map(|p| p.display().to_string())
                     .unwrap_or_default();
+                if !path.is_empty() {
+                    output.unlisted.push(path.clone());
+                }
                 let message = e.to_string();
                 output.add_error(ScanError::new(path, message));
             }
@@ -120,21 +134,30 @@ fn process_entry(
     filter: &FileFilter,
     output: &mut ScanOutput,
 ) -> ScannerResult<()> {
-    // Skip directories
+    let path = entry.path();
+
+    // Record directories below the roots, but don't list them as files
     if entry.file_type().is_dir() {
+        if entry.depth() > 0 {
+            output.directories.push(path.display().to_string());
+        }
         return Ok(());
     }
 
     // Get file metadata
-    let metadata = entry
-        .metadata()
-        .map_err(|e| ScannerError::Io(std::io::Error::other(e.to_string())))?;
+    let metadata = match entry.metadata() {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            output.unlisted.push(path.display().to_string());
+            return Err(ScannerError::Io(std::io::Error::other(e.to_string())));
+        }
+    };
 
     let size = metadata.len();
-    let path = entry.path();
 
     // Apply filters
     if !filter.matches(path, size) {
+        output.unlisted.push(path.display().to_string());
         return Ok(());
     }
 
@@ -147,6 +170,57 @@ fn process_entry(
     Ok(())
 }
 
+/// Re-reads metadata for a previously discovered list of files.
+///
+/// Used when resuming an interrupted scan: instead of walking the roots again,
+/// each known path is stat'ed so that size and modification time reflect the
+/// current state of the disk. Files that vanished or no longer pass the
+/// filters are dropped, with an error recorded for the former.
+///
+/// # Arguments
+/// * `files` - Files discovered by an earlier scan
+/// * `options` - Scan configuration used to re-apply filters
+///
+/// # Returns
+/// A ScanOutput containing the refreshed entries and any errors encountered.
+pub fn refresh_files(files: &[FileEntry], options: &ScanOptions) -> ScanOutput {
+    let filter = build_filter(options);
+    let mut output = ScanOutput::new();
+
+    for file in files {
+        let path = Path::new(&file.path);
+
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let message = if e.kind() == std::io::ErrorKind::NotFound {
+                    ScannerError::FileDisappeared(file.path.clone()).to_string()
+                } else {
+                    e.to_string()
+                };
+                output.add_error(ScanError::new(file.path.clone(), message));
+                continue;
+            }
+        };
+
+        if !metadata.is_file() || !filter.matches(path, metadata.len()) {
+            continue;
+        }
+
+        let modified = metadata.modified().ok().and_then(format_system_time);
+        output.add_file(FileEntry::new(file.path.clone(), metadata.len(), modified));
+    }
+
+    debug!(
+        "Refreshed {} of {} files, {} errors",
+        output.files.len(),
+        files.len(),
+        output.errors.len()
+    );
+
+    output
+}
+
 /// Builds a FileFilter from ScanOptions.
 fn build_filter(options: &ScanOptions) -> FileFilter {
     let mut filter = FileFilter::new();
@@ -167,7 +241,7 @@ fn build_filter(options: &ScanOptions) -> FileFilter {
 }
 
 /// Formats a SystemTime as an ISO 8601 string.
-fn format_system_time(time: SystemTime) -> Option<String> {
+pub fn format_system_time(time: SystemTime) -> Option<String> {
     time.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| {
         // Simple ISO 8601 format
         let secs = d.as_secs();
@@ -302,6 +376,29 @@ mod tests {
 
         let result = scan_directories(&options).unwrap();
         assert_eq!(result.files.len(), 2);
+        assert_eq!(result.unlisted.len(), 1);
+        assert!(result.unlisted[0].ends_with("image.jpg"));
+    }
+
+    #[test]
+    fn test_scan_records_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("a").join("b")).unwrap();
+
+        let options = ScanOptions {
+            root_paths: vec![temp_dir.path().display().to_string()],
+            ..Default::default()
+        };
+
+        let mut result = scan_directories(&options).unwrap();
+        result.directories.sort();
+        assert_eq!(
+            result.directories,
+            vec![
+                temp_dir.path().join("a").display().to_string(),
+                temp_dir.path().join("a").join("b").display().to_string(),
+            ]
+        );
     }
 
     #[test]
@@ -402,6 +499,36 @@ mod tests {
         assert_eq!(output.errors[0].path, "/bad/path");
     }
 
+    #[test]
+    fn test_refresh_files_updates_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(temp_dir.path(), "grow.txt", b"short");
+
+        let stale = vec![FileEntry::new(path.display().to_string(), 1, None)];
+        let options = ScanOptions::default();
+
+        let result = refresh_files(&stale, &options);
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.files[0].size, 5);
+        assert!(result.files[0].modified.is_some());
+    }
+
+    #[test]
+    fn test_refresh_files_reports_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(temp_dir.path(), "kept.txt", b"kept");
+
+        let files = vec![
+            FileEntry::new(path.display().to_string(), 4, None),
+            FileEntry::new("/nonexistent/gone.txt".to_string(), 4, None),
+        ];
+
+        let result = refresh_files(&files, &ScanOptions::default());
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "/nonexistent/gone.txt");
+    }
+
     #[test]
     fn test_file_entry_contains_size() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,219 @@
+//! Comparison of the duplicate groups found by two scans.
+//!
+//! Groups are matched by content hash, so a group is followed across scans
+//! even when its files were moved or renamed. Hashes are only comparable when
+//! both scans used the same algorithm.
+
+use crate::error::{ScannerError, ScannerResult};
+use crate::types::{DuplicateGroup, GroupChange, GroupDiff, ScanDiff, ScanResult};
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+/// Compares the duplicate groups of an older and a newer scan.
+///
+/// # Arguments
+/// * `before` - The older scan result
+/// * `after` - The newer scan result
+///
+/// # Returns
+/// New, grown, shrunk, moved and resolved groups, largest change in wasted
+/// space first, along with the totals. Fails if the scans used different
+/// hash algorithms.
+pub fn diff_results(before: &ScanResult, after: &ScanResult) -> ScannerResult<ScanDiff> {
+    let algorithm_before = before.hash_algorithm.unwrap_or_default();
+    let algorithm_after = after.hash_algorithm.unwrap_or_default();
+    if algorithm_before != algorithm_after {
+        return Err(ScannerError::Session(format!(
+            "scans used different hash algorithms ({:?} and {:?})",
+            algorithm_before, algorithm_after
+        )));
+    }
+
+    let mut previous: HashMap<&str, &DuplicateGroup> = before
+        .duplicate_groups
+        .iter()
+        .map(|g| (g.hash.as_str(), g))
+        .collect();
+
+    let mut groups = Vec::new();
+    let mut unchanged_groups = 0;
+
+    for group in &after.duplicate_groups {
+        match previous.remove(group.hash.as_str()) {
+            Some(old) => match compare(old, group) {
+                Some(diff) => groups.push(diff),
+                None => unchanged_groups += 1,
+            },
+            None => groups.push(GroupDiff {
+                hash: group.hash.clone(),
+                size: group.size,
+                change: GroupChange::Added,
+                files: group.files.clone(),
+                added_paths: group.files.iter().map(|f| f.path.clone()).collect(),
+                removed_paths: Vec::new(),
+                wasted_space_before: 0,
+                wasted_space_after: group.wasted_space(),
+            }),
+        }
+    }
+
+    // Whatever wasn't matched no longer has duplicates
+    for old in previous.into_values() {
+        groups.push(GroupDiff {
+            hash: old.hash.clone(),
+            size: old.size,
+            change: GroupChange::Resolved,
+            files: old.files.clone(),
+            added_paths: Vec::new(),
+            removed_paths: old.files.iter().map(|f| f.path.clone()).collect(),
+            wasted_space_before: old.wasted_space(),
+            wasted_space_after: 0,
+        });
+    }
+
+    groups.sort_by(|a, b| {
+        change_size(b)
+            .cmp(&change_size(a))
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+
+    debug!(
+        "Scan diff: {} changed groups, {} unchanged",
+        groups.len(),
+        unchanged_groups
+    );
+
+    Ok(ScanDiff {
+        groups,
+        unchanged_groups,
+        wasted_space_before: before.total_wasted_space,
+        wasted_space_after: after.total_wasted_space,
+        wasted_space_change: after.total_wasted_space as i64 - before.total_wasted_space as i64,
+    })
+}
+
+/// Compares one group across both scans, returning None if it is unchanged.
+fn compare(old: &DuplicateGroup, new: &DuplicateGroup) -> Option<GroupDiff> {
+    let old_paths: HashSet<&str> = old.files.iter().map(|f| f.path.as_str()).collect();
+    let new_paths: HashSet<&str> = new.files.iter().map(|f| f.path.as_str()).collect();
+
+    let added_paths: Vec<String> = new
+        .files
+        .iter()
+        .filter(|f| !old_paths.contains(f.path.as_str()))
+        .map(|f| f.path.clone())
+        .collect();
+    let removed_paths: Vec<String> = old
+        .files
+        .iter()
+        .filter(|f| !new_paths.contains(f.path.as_str()))
+        .map(|f| f.path.clone())
+        .collect();
+
+    let change = match new.files.len().cmp(&old.files.len()) {
+        std::cmp::Ordering::Greater => GroupChange::Grew,
+        std::cmp::Ordering::Less => GroupChange::Shrank,
+        std::cmp::Ordering::Equal if added_paths.is_empty() => return None,
+        std::cmp::Ordering::Equal => GroupChange::Moved,
+    };
+
+    Some(GroupDiff {
+        hash: new.hash.clone(),
+        size: new.size,
+        change,
+        files: new.files.clone(),
+        added_paths,
+        removed_paths,
+        wasted_space_before: old.wasted_space(),
+        wasted_space_after: new.wasted_space(),
+    })
+}
+
+/// Absolute change in wasted space, used to order the diff.
+fn change_size(diff: &GroupDiff) -> u64 {
+    diff.wasted_space_after.abs_diff(diff.wasted_space_before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileEntry, HashAlgorithm};
+
+    fn group(hash: &str, size: u64, paths: &[&str]) -> DuplicateGroup {
+        let files = paths
+            .iter()
+            .map(|p| FileEntry::new(p.to_string(), size, None))
+            .collect();
+        DuplicateGroup::new(hash.to_string(), size, files)
+    }
+
+    fn result(groups: Vec<DuplicateGroup>) -> ScanResult {
+        ScanResult::new(groups, 100, Vec::new(), 1)
+    }
+
+    #[test]
+    fn test_diff_classifies_groups() {
+        let before = result(vec![
+            group("same", 10, &["/a/1", "/b/1"]),
+            group("grows", 100, &["/a/2", "/b/2"]),
+            group("shrinks", 50, &["/a/3", "/b/3", "/c/3"]),
+            group("moves", 20, &["/a/4", "/b/4"]),
+            group("resolved", 1000, &["/a/5", "/b/5"]),
+        ]);
+        let after = result(vec![
+            group("same", 10, &["/a/1", "/b/1"]),
+            group("grows", 100, &["/a/2", "/b/2", "/c/2"]),
+            group("shrinks", 50, &["/a/3", "/b/3"]),
+            group("moves", 20, &["/a/4", "/c/4"]),
+            group("new", 5, &["/a/6", "/b/6"]),
+        ]);
+
+        let diff = diff_results(&before, &after).unwrap();
+
+        assert_eq!(diff.unchanged_groups, 1);
+        let changes: Vec<_> = diff
+            .groups
+            .iter()
+            .map(|g| (g.hash.as_str(), g.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("resolved", GroupChange::Resolved),
+                ("grows", GroupChange::Grew),
+                ("shrinks", GroupChange::Shrank),
+                ("new", GroupChange::Added),
+                ("moves", GroupChange::Moved),
+            ]
+        );
+
+        let moved = diff.groups_with(GroupChange::Moved).next().unwrap();
+        assert_eq!(moved.added_paths, vec!["/c/4"]);
+        assert_eq!(moved.removed_paths, vec!["/b/4"]);
+    }
+
+    #[test]
+    fn test_diff_totals() {
+        let before = result(vec![group("a", 1000, &["/x", "/y"])]);
+        let after = result(vec![group("b", 10, &["/x2", "/y2"])]);
+
+        let diff = diff_results(&before, &after).unwrap();
+
+        assert_eq!(diff.wasted_space_before, 1000);
+        assert_eq!(diff.wasted_space_after, 10);
+        assert_eq!(diff.wasted_space_change, -990);
+        assert_eq!(diff.groups_with(GroupChange::Resolved).count(), 1);
+        assert_eq!(diff.groups_with(GroupChange::Added).count(), 1);
+    }
+
+    #[test]
+    fn test_diff_rejects_mixed_algorithms() {
+        let before = result(Vec::new());
+        let after = result(Vec::new()).with_hash_algorithm(HashAlgorithm::Blake3);
+
+        assert!(matches!(
+            diff_results(&before, &after),
+            Err(ScannerError::Session(_))
+        ));
+    }
+}
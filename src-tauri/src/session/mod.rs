@@ -0,0 +1,415 @@
+//! Saved scan sessions for the Duplicate File Detector.
+//!
+//! A session stores a finished scan result together with the options it was
+//! run with, so a long scan can be reviewed later without scanning again.
+//! Files may have changed in the meantime, so on reopening every listed file
+//! is stat'ed and entries whose size or modification time no longer match are
+//! marked stale and can no longer be deleted from the session. Two saved
+//! sessions can be compared to follow duplicates over time.
+
+pub mod diff;
+
+pub use diff::diff_results;
+
+use crate::atomic::write_atomic;
+use crate::error::{ScannerError, ScannerResult};
+use crate::scanner::format_system_time;
+use crate::types::{FileEntry, ScanOptions, ScanResult, SessionSummary, StaleReason};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current version of the session file format.
+///
+/// Bumped only when a field is removed or changes meaning; new fields are
+/// added with defaults and leave the version unchanged.
+pub const SESSION_VERSION: u32 = 1;
+
+/// File extension used for session files.
+const SESSION_EXTENSION: &str = "json";
+
+/// A scan result persisted to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSession {
+    /// Format version (SESSION_VERSION when written).
+    pub version: u32,
+
+    /// Unique session ID.
+    pub id: String,
+
+    /// Name given by the user, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// When the session was saved, as seconds since the Unix epoch.
+    pub saved_at: u64,
+
+    /// Version of the app that saved the session.
+    pub app_version: String,
+
+    /// Options the scan was run with.
+    pub options: ScanOptions,
+
+    /// The scan result as it was when saved.
+    pub result: ScanResult,
+}
+
+impl SavedSession {
+    /// Creates a session for a finished scan, stamped with the current time
+    /// and app version.
+    pub fn new(
+        id: impl Into<String>,
+        name: Option<String>,
+        options: ScanOptions,
+        result: ScanResult,
+    ) -> Self {
+        Self {
+            version: SESSION_VERSION,
+            id: id.into(),
+            name,
+            saved_at: unix_now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            options,
+            result,
+        }
+    }
+
+    /// Returns a lightweight summary suitable for listing in the UI.
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            root_paths: self.options.root_paths.clone(),
+            saved_at: self.saved_at,
+            app_version: self.app_version.clone(),
+            duplicate_groups: self.result.duplicate_groups.len() as u64,
+            total_wasted_space: self.result.total_wasted_space,
+        }
+    }
+}
+
+/// Directory-backed storage for saved sessions.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    /// Directory holding one JSON file per session.
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    /// Creates a store rooted at the given directory.
+    /// The directory is created lazily on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Writes a session to disk, atomically replacing any previous version.
+    pub fn save(&self, session: &SavedSession) -> ScannerResult<()> {
+        let path = self.path_for(&session.id)?;
+        fs::create_dir_all(&self.dir)?;
+
+        let json = serde_json::to_vec(session).map_err(|e| ScannerError::Session(e.to_string()))?;
+
+        write_atomic(&path, |w| w.write_all(&json))?;
+
+        debug!(
+            "Saved session {} ({} duplicate groups)",
+            session.id,
+            session.result.duplicate_groups.len()
+        );
+        Ok(())
+    }
+
+    /// Loads a session by ID.
+    ///
+    /// Sessions written by a newer format version are rejected rather than
+    /// being misread.
+    pub fn load(&self, id: &str) -> ScannerResult<SavedSession> {
+        let path = self.path_for(id)?;
+        let data = fs::read(&path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                ScannerError::Session(format!("{} not found", id))
+            } else {
+                ScannerError::Io(e)
+            }
+        })?;
+
+        let session: SavedSession =
+            serde_json::from_slice(&data).map_err(|e| ScannerError::Session(e.to_string()))?;
+
+        if session.version > SESSION_VERSION {
+            return Err(ScannerError::Session(format!(
+                "{} was saved by a newer version ({})",
+                id, session.app_version
+            )));
+        }
+
+        Ok(session)
+    }
+
+    /// Lists all saved sessions, most recently saved first.
+    ///
+    /// Unreadable or corrupt session files are skipped with a warning.
+    pub fn list(&self) -> ScannerResult<Vec<SessionSummary>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut sessions = Vec::new();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SESSION_EXTENSION) {
+                continue;
+            }
+
+            let loaded = fs::read(&path).map_err(|e| e.to_string()).and_then(|data| {
+                serde_json::from_slice::<SavedSession>(&data).map_err(|e| e.to_string())
+            });
+
+            match loaded {
+                Ok(session) => sessions.push(session.summary()),
+                Err(e) => warn!("Skipping unreadable session {}: {}", path.display(), e),
+            }
+        }
+
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        Ok(sessions)
+    }
+
+    /// Removes a session. Removing a session that doesn't exist is not an error.
+    pub fn remove(&self, id: &str) -> ScannerResult<()> {
+        let path = self.path_for(id)?;
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the file path for a session, rejecting IDs that could escape the store.
+    fn path_for(&self, id: &str) -> ScannerResult<PathBuf> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        if !valid {
+            return Err(ScannerError::Session(format!("invalid id: {}", id)));
+        }
+
+        Ok(self.dir.join(format!("{}.{}", id, SESSION_EXTENSION)))
+    }
+}
+
+/// Checks every file in a result against the disk and marks those that changed.
+///
+/// A file is stale when it no longer exists or its size or modification time
+/// differs from the recorded one. Each path is stat'ed once, however many
+/// times it appears in the result. Files that still match have their stale
+/// mark cleared.
+///
+/// # Returns
+/// The paths of the stale files.
+pub fn mark_stale(result: &mut ScanResult) -> HashSet<String> {
+    let mut checked: HashMap<String, Option<StaleReason>> = HashMap::new();
+    let mut check = |file: &mut FileEntry| {
+        file.stale = *checked
+            .entry(file.path.clone())
+            .or_insert_with(|| stale_reason(file));
+    };
+
+    for group in &mut result.duplicate_groups {
+        group.files.iter_mut().for_each(&mut check);
+    }
+    for group in result
+        .similar_images
+        .iter_mut()
+        .chain(result.similar_text.iter_mut())
+    {
+        for member in &mut group.members {
+            check(&mut member.file);
+        }
+    }
+    if let Some(analysis) = &mut result.chunk_analysis {
+        for overlap in &mut analysis.overlaps {
+            check(&mut overlap.file_a);
+            check(&mut overlap.file_b);
+        }
+    }
+    for matched in &mut result.manifest_matches {
+        check(&mut matched.file);
+    }
+    result.empty_files.iter_mut().for_each(&mut check);
+    for conflict in &mut result.name_conflicts {
+        for version in &mut conflict.versions {
+            version.files.iter_mut().for_each(&mut check);
+        }
+    }
+
+    let total = checked.len();
+    let stale: HashSet<String> = checked
+        .into_iter()
+        .filter_map(|(path, reason)| reason.map(|_| path))
+        .collect();
+    debug!("{} of {} session files are stale", stale.len(), total);
+    stale
+}
+
+/// Compares a recorded file with the disk.
+fn stale_reason(file: &FileEntry) -> Option<StaleReason> {
+    match fs::metadata(&file.path) {
+        Ok(metadata) if metadata.is_file() => {
+            let modified = metadata.modified().ok().and_then(format_system_time);
+            (metadata.len() != file.size || modified != file.modified)
+                .then_some(StaleReason::Changed)
+        }
+        Ok(_) => Some(StaleReason::Changed),
+        Err(_) => Some(StaleReason::Missing),
+    }
+}
+
+/// Returns the current time as seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DuplicateGroup;
+    use tempfile::TempDir;
+
+    fn entry_for(path: &std::path::Path) -> FileEntry {
+        let metadata = fs::metadata(path).unwrap();
+        FileEntry::new(
+            path.display().to_string(),
+            metadata.len(),
+            metadata.modified().ok().and_then(format_system_time),
+        )
+    }
+
+    fn sample_session(id: &str) -> SavedSession {
+        let options = ScanOptions {
+            root_paths: vec!["/photos".to_string()],
+            ..Default::default()
+        };
+        let files = vec![
+            FileEntry::new("/photos/a.jpg".to_string(), 100, None),
+            FileEntry::new("/photos/b.jpg".to_string(), 100, None),
+        ];
+        let result = ScanResult::new(
+            vec![DuplicateGroup::new("abc".to_string(), 100, files)],
+            2,
+            Vec::new(),
+            5,
+        );
+        SavedSession::new(id, Some("Photos".to_string()), options, result)
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = SessionStore::new(dir.path().join("sessions"));
+
+        store.save(&sample_session("session_1")).unwrap();
+        let loaded = store.load("session_1").unwrap();
+
+        assert_eq!(loaded.version, SESSION_VERSION);
+        assert_eq!(loaded.app_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(loaded.options.root_paths, vec!["/photos"]);
+        assert_eq!(loaded.result.duplicate_groups.len(), 1);
+        assert_eq!(loaded.result.total_wasted_space, 100);
+    }
+
+    #[test]
+    fn test_list_summaries_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let store = SessionStore::new(dir.path());
+
+        let mut older = sample_session("older");
+        older.saved_at = 100;
+        let mut newer = sample_session("newer");
+        newer.saved_at = 200;
+        store.save(&older).unwrap();
+        store.save(&newer).unwrap();
+        fs::write(dir.path().join("corrupt.json"), b"not json").unwrap();
+
+        let sessions = store.list().unwrap();
+
+        let ids: Vec<_> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["newer", "older"]);
+        assert_eq!(sessions[0].name.as_deref(), Some("Photos"));
+        assert_eq!(sessions[0].duplicate_groups, 1);
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version_and_bad_ids() {
+        let dir = TempDir::new().unwrap();
+        let store = SessionStore::new(dir.path());
+
+        let mut session = sample_session("future");
+        session.version = SESSION_VERSION + 1;
+        store.save(&session).unwrap();
+
+        assert!(matches!(
+            store.load("future"),
+            Err(ScannerError::Session(_))
+        ));
+        assert!(matches!(
+            store.load("../escape"),
+            Err(ScannerError::Session(_))
+        ));
+        assert!(matches!(
+            store.load("missing"),
+            Err(ScannerError::Session(_))
+        ));
+        assert!(store.remove("missing").is_ok());
+    }
+
+    #[test]
+    fn test_mark_stale() {
+        let dir = TempDir::new().unwrap();
+        let same = dir.path().join("same");
+        let changed = dir.path().join("changed");
+        let gone = dir.path().join("gone");
+        for path in [&same, &changed, &gone] {
+            fs::write(path, b"content").unwrap();
+        }
+
+        let files = vec![entry_for(&same), entry_for(&changed), entry_for(&gone)];
+        let mut result = ScanResult::new(
+            vec![DuplicateGroup::new("abc".to_string(), 7, files.clone())],
+            3,
+            Vec::new(),
+            1,
+        )
+        .with_empty(files, Vec::new());
+
+        fs::write(&changed, b"longer content").unwrap();
+        fs::remove_file(&gone).unwrap();
+
+        let stale_paths = mark_stale(&mut result);
+        assert_eq!(stale_paths.len(), 2);
+        assert!(stale_paths.contains(&changed.display().to_string()));
+
+        let stale: Vec<_> = result.duplicate_groups[0]
+            .files
+            .iter()
+            .map(|f| f.stale)
+            .collect();
+        assert_eq!(
+            stale,
+            vec![None, Some(StaleReason::Changed), Some(StaleReason::Missing)]
+        );
+        assert_eq!(result.empty_files[2].stale, Some(StaleReason::Missing));
+    }
+}
@@ -0,0 +1,441 @@
+//! Content-defined chunking for partial-overlap analysis.
+//!
+//! Splits files into variable-size chunks with FastCDC (gear rolling hash,
+//! normalized chunking), so inserting or removing bytes only changes the
+//! chunks around the edit. Comparing chunk hashes then reveals files that
+//! share most of their bytes without being identical, such as successive
+//! backups or VM images, and how much block-level dedup would reclaim.
+
+use crate::hasher::{pool, read, HashOptions};
+use crate::types::{ChunkAnalysis, ChunkOverlap, FileEntry, ScanError};
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Minimum chunk size (16 KiB).
+pub const MIN_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Target average chunk size (64 KiB).
+pub const AVG_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Maximum chunk size (256 KiB).
+pub const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Files smaller than this are not chunked; they would be a single chunk.
+pub const MIN_CHUNKED_FILE_SIZE: u64 = AVG_CHUNK_SIZE as u64;
+
+/// Default minimum share of the smaller file that must be shared for a pair
+/// to be reported.
+pub const DEFAULT_OVERLAP_THRESHOLD: f64 = 0.5;
+
+/// Chunks present in more files than this (zero-filled blocks, common
+/// headers) still count towards the dedup estimate but are ignored when
+/// pairing files, which would otherwise be quadratic in their file count.
+const MAX_FILES_PER_PAIRED_CHUNK: usize = 64;
+
+/// Cut-point mask used before the average size: more bits, so cuts are rarer.
+const MASK_SMALL: u64 = mask(17);
+
+/// Cut-point mask used after the average size: fewer bits, so cuts are likelier.
+const MASK_LARGE: u64 = mask(15);
+
+/// A mask with the `bits` most significant bits set. FastCDC's gear hash
+/// shifts left, so the high bits depend on the most bytes.
+const fn mask(bits: u32) -> u64 {
+    !0u64 << (64 - bits)
+}
+
+/// A chunk's content hash (first 128 bits of BLAKE3) and length.
+type Chunk = (u128, u32);
+
+/// Analyzes how much content candidate files share at the chunk level.
+///
+/// # Arguments
+/// * `files` - Candidate files; files below MIN_CHUNKED_FILE_SIZE are ignored
+/// * `options` - Read strategy and resource limits for reading files
+/// * `min_ratio` - Minimum shared fraction of the smaller file to report a pair
+/// * `exact_hashes` - Content hashes from the scan; identical pairs are not reported
+/// * `is_cancelled` - Returns true if the scan was cancelled; remaining files are skipped
+///
+/// # Returns
+/// The analysis and any files that could not be read.
+pub fn analyze_chunk_overlap<C>(
+    files: &[FileEntry],
+    options: &HashOptions,
+    min_ratio: f64,
+    exact_hashes: &[(FileEntry, String)],
+    is_cancelled: C,
+) -> (ChunkAnalysis, Vec<ScanError>)
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let candidates: Vec<&FileEntry> = files
+        .iter()
+        .filter(|f| f.size >= MIN_CHUNKED_FILE_SIZE)
+        .collect();
+
+    debug!("Chunking {} files", candidates.len());
+
+    let chunk_all = || -> Vec<(&FileEntry, Result<Vec<Chunk>, String>)> {
+        candidates
+            .into_par_iter()
+            .filter_map(|file| {
+                if is_cancelled() {
+                    return None;
+                }
+                let chunks = chunk_file(Path::new(&file.path), options).map_err(|e| {
+                    warn!("Failed to chunk {}: {}", file.path, e);
+                    format!("Could not read file for chunking: {}", e)
+                });
+                Some((file, chunks))
+            })
+            .collect()
+    };
+
+    let chunked = match pool::build_hash_pool(options.max_threads, options.low_priority) {
+        Ok(hash_pool) => hash_pool.install(chunk_all),
+        Err(e) => {
+            warn!("Failed to build hash pool, using global pool: {}", e);
+            chunk_all()
+        }
+    };
+
+    let mut analyzed: Vec<(&FileEntry, Vec<Chunk>)> = Vec::with_capacity(chunked.len());
+    let mut errors = Vec::new();
+    for (file, chunks) in chunked {
+        match chunks {
+            Ok(chunks) => analyzed.push((file, chunks)),
+            Err(message) => errors.push(ScanError::new(file.path.clone(), message)),
+        }
+    }
+
+    (summarize(&analyzed, min_ratio, exact_hashes), errors)
+}
+
+/// Computes dedup totals and overlapping pairs from per-file chunk lists.
+fn summarize(
+    analyzed: &[(&FileEntry, Vec<Chunk>)],
+    min_ratio: f64,
+    exact_hashes: &[(FileEntry, String)],
+) -> ChunkAnalysis {
+    let mut total_bytes = 0u64;
+    let mut chunk_sizes: HashMap<u128, u32> = HashMap::new();
+    let mut files_by_chunk: HashMap<u128, Vec<usize>> = HashMap::new();
+
+    for (index, (_, chunks)) in analyzed.iter().enumerate() {
+        let mut seen = HashSet::new();
+        for &(hash, len) in chunks {
+            total_bytes += u64::from(len);
+            chunk_sizes.insert(hash, len);
+            if seen.insert(hash) {
+                files_by_chunk.entry(hash).or_default().push(index);
+            }
+        }
+    }
+
+    let unique_bytes: u64 = chunk_sizes.values().map(|&len| u64::from(len)).sum();
+
+    // Bytes of distinct chunks shared by each pair of files
+    let mut shared: HashMap<(usize, usize), u64> = HashMap::new();
+    for (hash, holders) in &files_by_chunk {
+        if holders.len() < 2 || holders.len() > MAX_FILES_PER_PAIRED_CHUNK {
+            continue;
+        }
+        let len = u64::from(chunk_sizes[hash]);
+        for (i, &a) in holders.iter().enumerate() {
+            for &b in &holders[i + 1..] {
+                *shared.entry((a, b)).or_default() += len;
+            }
+        }
+    }
+
+    let exact: HashMap<&str, &str> = exact_hashes
+        .iter()
+        .map(|(file, hash)| (file.path.as_str(), hash.as_str()))
+        .collect();
+
+    let mut overlaps: Vec<ChunkOverlap> = shared
+        .into_iter()
+        .filter_map(|((a, b), shared_bytes)| {
+            let (file_a, file_b) = (analyzed[a].0, analyzed[b].0);
+
+            let exact_a = exact.get(file_a.path.as_str());
+            if exact_a.is_some() && exact_a == exact.get(file_b.path.as_str()) {
+                return None; // Already reported as an exact duplicate
+            }
+
+            let smaller = file_a.size.min(file_b.size).max(1);
+            let ratio = (shared_bytes as f64 / smaller as f64).min(1.0);
+            (ratio >= min_ratio)
+                .then(|| ChunkOverlap::new(file_a.clone(), file_b.clone(), shared_bytes, ratio))
+        })
+        .collect();
+
+    overlaps.sort_by(|x, y| {
+        y.shared_bytes
+            .cmp(&x.shared_bytes)
+            .then_with(|| x.file_a.path.cmp(&y.file_a.path))
+            .then_with(|| x.file_b.path.cmp(&y.file_b.path))
+    });
+
+    debug!(
+        "Chunk analysis: {} files, {} bytes, {} unique, {} overlapping pairs",
+        analyzed.len(),
+        total_bytes,
+        unique_bytes,
+        overlaps.len()
+    );
+
+    ChunkAnalysis {
+        overlaps,
+        files_analyzed: analyzed.len() as u64,
+        total_bytes,
+        unique_bytes,
+        reclaimable_bytes: total_bytes - unique_bytes,
+    }
+}
+
+/// Splits a file into content-defined chunks.
+fn chunk_file(path: &Path, options: &HashOptions) -> std::io::Result<Vec<Chunk>> {
+    let file = File::open(path)?;
+    let mut chunks = Vec::new();
+    let mut pending: Vec<u8> = Vec::with_capacity(MAX_CHUNK_SIZE * 2);
+
+    let mut emit = |data: &[u8]| {
+        let hash = blake3::hash(data);
+        let mut prefix = [0u8; 16];
+        prefix.copy_from_slice(&hash.as_bytes()[..16]);
+        chunks.push((u128::from_le_bytes(prefix), data.len() as u32));
+    };
+
+    read::read_file(
+        &file,
+        options.read_strategy,
+        options.throttle.as_deref(),
+        |data| {
+            pending.extend_from_slice(data);
+            // A cut never looks past MAX_CHUNK_SIZE bytes, so cutting once that
+            // much is buffered gives the same chunks as cutting the whole file.
+            while pending.len() >= MAX_CHUNK_SIZE {
+                let cut = find_cut(&pending);
+                emit(&pending[..cut]);
+                pending.drain(..cut);
+            }
+        },
+    )?;
+
+    while !pending.is_empty() {
+        let cut = find_cut(&pending);
+        emit(&pending[..cut]);
+        pending.drain(..cut);
+    }
+
+    Ok(chunks)
+}
+
+/// Returns the length of the first chunk of `data` (FastCDC with normalized chunking).
+pub fn find_cut(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return data.len();
+    }
+
+    let gear = gear_table();
+    let normal = AVG_CHUNK_SIZE.min(data.len());
+    let end = MAX_CHUNK_SIZE.min(data.len());
+    let mut hash = 0u64;
+
+    for (i, &byte) in data.iter().enumerate().take(end).skip(MIN_CHUNK_SIZE) {
+        hash = (hash << 1).wrapping_add(gear[usize::from(byte)]);
+        let mask = if i < normal { MASK_SMALL } else { MASK_LARGE };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+
+    end
+}
+
+/// Pseudo-random gear values for the rolling hash, fixed so chunk
+/// boundaries are stable across runs.
+fn gear_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        // SplitMix64
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut table = [0u64; 256];
+        for value in table.iter_mut() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *value = z ^ (z >> 31);
+        }
+        table
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Deterministic incompressible test data.
+    fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn cut_all(data: &[u8]) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut rest = data;
+        while !rest.is_empty() {
+            let cut = find_cut(rest);
+            lengths.push(cut);
+            rest = &rest[cut..];
+        }
+        lengths
+    }
+
+    fn write(dir: &TempDir, name: &str, data: &[u8]) -> FileEntry {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        FileEntry::new(path.display().to_string(), data.len() as u64, None)
+    }
+
+    #[test]
+    fn test_chunk_sizes_within_bounds() {
+        let data = random_bytes(4 * 1024 * 1024, 1);
+        let lengths = cut_all(&data);
+
+        assert_eq!(lengths.iter().sum::<usize>(), data.len());
+        for &len in &lengths[..lengths.len() - 1] {
+            assert!((MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&len));
+        }
+        // Normalized chunking keeps the average near the target
+        let average = data.len() / lengths.len();
+        assert!(average > AVG_CHUNK_SIZE / 2 && average < AVG_CHUNK_SIZE * 2);
+    }
+
+    #[test]
+    fn test_boundaries_resynchronize_after_insert() {
+        let original = random_bytes(2 * 1024 * 1024, 7);
+        let mut edited = original[..100_000].to_vec();
+        edited.extend_from_slice(b"a few inserted bytes");
+        edited.extend_from_slice(&original[100_000..]);
+
+        let dir = TempDir::new().unwrap();
+        let a = write(&dir, "a.img", &original);
+        let b = write(&dir, "b.img", &edited);
+        let options = HashOptions::default();
+
+        let chunks_a: HashSet<Chunk> = chunk_file(Path::new(&a.path), &options)
+            .unwrap()
+            .into_iter()
+            .collect();
+        let chunks_b = chunk_file(Path::new(&b.path), &options).unwrap();
+        let common = chunks_b.iter().filter(|c| chunks_a.contains(c)).count();
+
+        // Only the chunks around the insertion differ
+        assert!(common + 3 >= chunks_b.len());
+    }
+
+    #[test]
+    fn test_streaming_matches_whole_buffer() {
+        let data = random_bytes(1024 * 1024 + 12345, 3);
+        let dir = TempDir::new().unwrap();
+        let file = write(&dir, "data.bin", &data);
+
+        let streamed: Vec<usize> = chunk_file(Path::new(&file.path), &HashOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|(_, len)| len as usize)
+            .collect();
+
+        assert_eq!(streamed, cut_all(&data));
+    }
+
+    #[test]
+    fn test_analyze_chunk_overlap() {
+        let dir = TempDir::new().unwrap();
+        let base = random_bytes(1024 * 1024, 11);
+        let mut backup = base.clone();
+        backup.extend(random_bytes(256 * 1024, 12));
+
+        let a = write(&dir, "backup-monday.tar", &base);
+        let b = write(&dir, "backup-tuesday.tar", &backup);
+        let c = write(&dir, "unrelated.bin", &random_bytes(512 * 1024, 13));
+        let tiny = write(&dir, "tiny.txt", b"too small to chunk");
+
+        let (analysis, errors) = analyze_chunk_overlap(
+            &[a.clone(), b.clone(), c, tiny],
+            &HashOptions::default(),
+            DEFAULT_OVERLAP_THRESHOLD,
+            &[],
+            || false,
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(analysis.files_analyzed, 3);
+        assert_eq!(analysis.overlaps.len(), 1);
+        let overlap = &analysis.overlaps[0];
+        let pair = [&overlap.file_a.path, &overlap.file_b.path];
+        assert!(pair.contains(&&a.path) && pair.contains(&&b.path));
+        assert!(overlap.shared_ratio > 0.9);
+        // Most of the shared base could be reclaimed by block-level dedup
+        assert!(analysis.reclaimable_bytes > 900 * 1024);
+        assert_eq!(
+            analysis.total_bytes - analysis.unique_bytes,
+            analysis.reclaimable_bytes
+        );
+    }
+
+    #[test]
+    fn test_exact_duplicates_not_reported_as_overlap() {
+        let dir = TempDir::new().unwrap();
+        let data = random_bytes(256 * 1024, 5);
+        let a = write(&dir, "a.bin", &data);
+        let b = write(&dir, "b.bin", &data);
+        let hashes = vec![
+            (a.clone(), "same".to_string()),
+            (b.clone(), "same".to_string()),
+        ];
+
+        let (analysis, _) = analyze_chunk_overlap(
+            &[a, b],
+            &HashOptions::default(),
+            DEFAULT_OVERLAP_THRESHOLD,
+            &hashes,
+            || false,
+        );
+
+        assert!(analysis.overlaps.is_empty());
+        // Identical files still count towards the dedup estimate
+        assert_eq!(analysis.reclaimable_bytes, 256 * 1024);
+    }
+
+    #[test]
+    fn test_unreadable_file_reported() {
+        let file = FileEntry::new("/nonexistent/big.img".to_string(), 1 << 20, None);
+
+        let (analysis, errors) = analyze_chunk_overlap(
+            &[file],
+            &HashOptions::default(),
+            DEFAULT_OVERLAP_THRESHOLD,
+            &[],
+            || false,
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(analysis.files_analyzed, 0);
+    }
+}
@@ -0,0 +1,457 @@
+//! Perceptual hashing for near-duplicate image detection.
+//!
+//! Images are decoded, reduced to a small grayscale thumbnail and turned
+//! into a 64-bit fingerprint that barely changes when an image is resized,
+//! re-encoded or stripped of metadata. Similar fingerprints are then
+//! clustered by Hamming distance.
+
+use super::{build_groups, cluster_by_distance};
+use crate::hasher::{pool, read, HashOptions};
+use crate::types::{FileEntry, ImageHashAlgorithm, ScanError, SimilarityGroup};
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::fs::File;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// File extensions treated as images (lowercase).
+pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff"];
+
+/// Default maximum Hamming distance between similar images.
+pub const DEFAULT_IMAGE_DISTANCE: u32 = 10;
+
+/// Images are first reduced to at most this many pixels per side, which
+/// keeps decoding memory bounded and makes the final resize cheap.
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Side length of the grayscale grid the DCT hash is computed from.
+const DCT_SIZE: usize = 32;
+
+/// Result of similar image detection.
+#[derive(Debug, Default)]
+pub struct SimilarImages {
+    /// Groups of similar images, largest group first.
+    pub groups: Vec<SimilarityGroup>,
+
+    /// Images that could not be decoded.
+    pub errors: Vec<ScanError>,
+}
+
+/// Returns true if the path has an image file extension.
+pub fn is_image(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Finds groups of visually similar images.
+///
+/// Within each group the largest file is the reference; every other member
+/// is within `max_distance` bits of it and scored by its similarity to it.
+/// Groups whose members are all byte-identical are left out, since they are
+/// already reported as exact duplicates.
+///
+/// # Arguments
+/// * `files` - Candidate files; non-images are ignored
+/// * `options` - Thread, throughput and priority limits for reading images
+/// * `algorithm` - Perceptual hash to compute
+/// * `max_distance` - Maximum Hamming distance from the group's reference
+/// * `exact_hashes` - Content hashes from the scan, used to skip exact duplicates
+/// * `is_cancelled` - Returns true if the scan was cancelled; remaining images are skipped
+pub fn find_similar_images<C>(
+    files: &[FileEntry],
+    options: &HashOptions,
+    algorithm: ImageHashAlgorithm,
+    max_distance: u32,
+    exact_hashes: &[(FileEntry, String)],
+    is_cancelled: C,
+) -> SimilarImages
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let mut images: Vec<&FileEntry> = files.iter().filter(|f| is_image(&f.path)).collect();
+    images.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let fingerprint_all = || -> Vec<(&FileEntry, Result<u64, String>)> {
+        images
+            .into_par_iter()
+            .filter_map(|file| {
+                if is_cancelled() {
+                    return None;
+                }
+                let fingerprint = image_fingerprint(Path::new(&file.path), algorithm, options);
+                Some((file, fingerprint))
+            })
+            .collect()
+    };
+
+    let fingerprints = match pool::build_hash_pool(options.max_threads, options.low_priority) {
+        Ok(hash_pool) => hash_pool.install(fingerprint_all),
+        Err(e) => {
+            warn!("Failed to build hash pool, using global pool: {}", e);
+            fingerprint_all()
+        }
+    };
+
+    let mut hashed: Vec<&FileEntry> = Vec::with_capacity(fingerprints.len());
+    let mut values: Vec<u64> = Vec::with_capacity(fingerprints.len());
+    let mut errors = Vec::new();
+    for (file, fingerprint) in fingerprints {
+        match fingerprint {
+            Ok(value) => {
+                hashed.push(file);
+                values.push(value);
+            }
+            Err(message) => errors.push(ScanError::new(file.path.clone(), message)),
+        }
+    }
+
+    let clusters = cluster_by_distance(&values, max_distance);
+    let groups = build_groups(clusters, &hashed, exact_hashes);
+
+    debug!(
+        "Compared {} images: {} similar groups, {} unreadable",
+        values.len(),
+        groups.len(),
+        errors.len()
+    );
+
+    SimilarImages { groups, errors }
+}
+
+/// Decodes an image and computes its perceptual fingerprint.
+///
+/// The file is read with the scan's read strategy and throttle before decoding.
+pub fn image_fingerprint(
+    path: &Path,
+    algorithm: ImageHashAlgorithm,
+    options: &HashOptions,
+) -> Result<u64, String> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|file| {
+            data.reserve(file.metadata()?.len() as usize);
+            read::read_file(
+                &file,
+                options.read_strategy,
+                options.throttle.as_deref(),
+                |chunk| data.extend_from_slice(chunk),
+            )
+        })
+        .map_err(|e| format!("Could not read image: {}", e))?;
+
+    let image =
+        image::load_from_memory(&data).map_err(|e| format!("Could not decode image: {}", e))?;
+    let gray = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_luma8();
+
+    Ok(fingerprint_luma(
+        gray.as_raw(),
+        gray.width() as usize,
+        gray.height() as usize,
+        algorithm,
+    ))
+}
+
+/// Computes a perceptual fingerprint from 8-bit grayscale pixels (row-major).
+pub fn fingerprint_luma(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    algorithm: ImageHashAlgorithm,
+) -> u64 {
+    match algorithm {
+        ImageHashAlgorithm::Average => average_hash(&resize(pixels, width, height, 8, 8)),
+        ImageHashAlgorithm::Difference => difference_hash(&resize(pixels, width, height, 9, 8)),
+        ImageHashAlgorithm::Perceptual => {
+            dct_hash(&resize(pixels, width, height, DCT_SIZE, DCT_SIZE))
+        }
+    }
+}
+
+/// aHash: one bit per pixel of an 8x8 grid, set if brighter than the mean.
+fn average_hash(grid: &[f32]) -> u64 {
+    let mean = grid.iter().sum::<f32>() / grid.len() as f32;
+    bits_from(grid.iter().map(|&v| v > mean))
+}
+
+/// dHash: one bit per horizontal neighbour pair of a 9x8 grid, set if the
+/// left pixel is brighter than the right.
+fn difference_hash(grid: &[f32]) -> u64 {
+    bits_from(
+        grid.chunks(9)
+            .flat_map(|row| row.windows(2).map(|pair| pair[0] > pair[1])),
+    )
+}
+
+/// pHash: 2D DCT of a 32x32 grid; one bit per coefficient of the 8x8
+/// lowest-frequency block, set if above the median of that block (DC excluded).
+fn dct_hash(grid: &[f32]) -> u64 {
+    let coefficients = dct_low_frequencies(grid);
+
+    let mut ac: Vec<f32> = coefficients[1..].to_vec();
+    ac.sort_by(|a, b| a.total_cmp(b));
+    let median = (ac[ac.len() / 2 - 1] + ac[ac.len() / 2]) / 2.0;
+
+    bits_from(coefficients.iter().map(|&c| c > median))
+}
+
+/// Returns the top-left 8x8 DCT-II coefficients of a DCT_SIZE x DCT_SIZE grid.
+fn dct_low_frequencies(grid: &[f32]) -> Vec<f32> {
+    let table = dct_table();
+
+    // Rows first: DCT_SIZE rows x 8 frequencies
+    let mut rows = vec![0f32; DCT_SIZE * 8];
+    for y in 0..DCT_SIZE {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..DCT_SIZE)
+                .map(|x| grid[y * DCT_SIZE + x] * table[u * DCT_SIZE + x])
+                .sum();
+        }
+    }
+
+    // Then columns: 8 x 8
+    let mut out = vec![0f32; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            out[v * 8 + u] = (0..DCT_SIZE)
+                .map(|y| rows[y * 8 + u] * table[v * DCT_SIZE + y])
+                .sum();
+        }
+    }
+    out
+}
+
+/// Cosine basis for the first 8 DCT-II frequencies over DCT_SIZE samples.
+fn dct_table() -> &'static [f32] {
+    static TABLE: OnceLock<Vec<f32>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let n = DCT_SIZE as f32;
+        (0..8)
+            .flat_map(|u| {
+                (0..DCT_SIZE)
+                    .map(move |x| ((std::f32::consts::PI / n) * (x as f32 + 0.5) * u as f32).cos())
+            })
+            .collect()
+    })
+}
+
+/// Packs up to 64 booleans into a fingerprint, first bit most significant.
+fn bits_from(bits: impl Iterator<Item = bool>) -> u64 {
+    bits.take(64)
+        .fold(0u64, |acc, bit| (acc << 1) | u64::from(bit))
+}
+
+/// Resizes grayscale pixels to `out_width` x `out_height` by averaging the
+/// source pixels that fall into each output cell.
+fn resize(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    out_width: usize,
+    out_height: usize,
+) -> Vec<f32> {
+    if width == 0 || height == 0 {
+        return vec![0.0; out_width * out_height];
+    }
+
+    let span = |i: usize, from: usize, to: usize| {
+        let start = i * from / to;
+        let end = ((i + 1) * from / to).max(start + 1).min(from);
+        start.min(from - 1)..end
+    };
+
+    let mut out = Vec::with_capacity(out_width * out_height);
+    for oy in 0..out_height {
+        let rows = span(oy, height, out_height);
+        for ox in 0..out_width {
+            let cols = span(ox, width, out_width);
+            let mut sum = 0u64;
+            for y in rows.clone() {
+                let row = &pixels[y * width..(y + 1) * width];
+                sum += row[cols.clone()].iter().map(|&p| u64::from(p)).sum::<u64>();
+            }
+            let count = (rows.len() * cols.len()) as f32;
+            out.push(sum as f32 / count);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ALGORITHMS: [ImageHashAlgorithm; 3] = [
+        ImageHashAlgorithm::Average,
+        ImageHashAlgorithm::Difference,
+        ImageHashAlgorithm::Perceptual,
+    ];
+
+    /// A smooth, textured test image of `size` x `size` pixels that looks the
+    /// same at every resolution.
+    fn test_pattern(size: usize) -> Vec<u8> {
+        (0..size * size)
+            .map(|i| {
+                let x = (i % size) as f32 / size as f32;
+                let y = (i / size) as f32 / size as f32;
+                (127.0 + 60.0 * (x * 7.0).sin() + 60.0 * (y * 5.0 + x * 3.0).cos()) as u8
+            })
+            .collect()
+    }
+
+    fn save_gray(dir: &TempDir, name: &str, pixels: Vec<u8>, size: u32) -> FileEntry {
+        let path = dir.path().join(name);
+        image::GrayImage::from_raw(size, size, pixels)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+        let len = std::fs::metadata(&path).unwrap().len();
+        FileEntry::new(path.display().to_string(), len, None)
+    }
+
+    #[test]
+    fn test_is_image() {
+        assert!(is_image("/photos/a.JPG"));
+        assert!(is_image("/photos/b.webp"));
+        assert!(!is_image("/docs/a.txt"));
+        assert!(!is_image("/photos/noext"));
+    }
+
+    #[test]
+    fn test_resize_averages() {
+        let pixels = [0, 100, 200, 50];
+        assert_eq!(resize(&pixels, 2, 2, 1, 1), vec![87.5]);
+        // Upscaling repeats source pixels
+        assert_eq!(resize(&[10], 1, 1, 2, 2), vec![10.0; 4]);
+    }
+
+    #[test]
+    fn test_resized_image_has_close_fingerprint() {
+        let large = test_pattern(256);
+        let small = test_pattern(64);
+
+        for algorithm in ALGORITHMS {
+            let a = fingerprint_luma(&large, 256, 256, algorithm);
+            let b = fingerprint_luma(&small, 64, 64, algorithm);
+            assert!(
+                (a ^ b).count_ones() <= 4,
+                "{:?}: distance {}",
+                algorithm,
+                (a ^ b).count_ones()
+            );
+        }
+    }
+
+    #[test]
+    fn test_different_images_are_far_apart() {
+        let pattern = test_pattern(64);
+        let inverted: Vec<u8> = pattern.iter().map(|&p| 255 - p).collect();
+
+        for algorithm in ALGORITHMS {
+            let a = fingerprint_luma(&pattern, 64, 64, algorithm);
+            let b = fingerprint_luma(&inverted, 64, 64, algorithm);
+            assert!(
+                (a ^ b).count_ones() > DEFAULT_IMAGE_DISTANCE,
+                "{:?}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_similar_images() {
+        let dir = TempDir::new().unwrap();
+        let original = save_gray(&dir, "original.png", test_pattern(128), 128);
+        let resized = save_gray(&dir, "resized.png", test_pattern(64), 64);
+        let other = save_gray(
+            &dir,
+            "other.png",
+            test_pattern(64).iter().map(|&p| 255 - p).collect(),
+            64,
+        );
+        let text = FileEntry::new(dir.path().join("notes.txt").display().to_string(), 5, None);
+
+        let result = find_similar_images(
+            &[resized.clone(), other, original.clone(), text],
+            &HashOptions::default(),
+            ImageHashAlgorithm::Difference,
+            DEFAULT_IMAGE_DISTANCE,
+            &[],
+            || false,
+        );
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.groups.len(), 1);
+        let members = &result.groups[0].members;
+        assert_eq!(members.len(), 2);
+        // The larger file is the reference
+        assert_eq!(members[0].file, original);
+        assert_eq!(members[0].similarity, 1.0);
+        assert_eq!(members[1].file, resized);
+        assert!(members[1].similarity > 0.8);
+    }
+
+    #[test]
+    fn test_exact_duplicates_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let a = save_gray(&dir, "a.png", test_pattern(64), 64);
+        let b = save_gray(&dir, "b.png", test_pattern(64), 64);
+        let hashes = vec![
+            (a.clone(), "same".to_string()),
+            (b.clone(), "same".to_string()),
+        ];
+
+        let result = find_similar_images(
+            &[a, b],
+            &HashOptions::default(),
+            ImageHashAlgorithm::Average,
+            DEFAULT_IMAGE_DISTANCE,
+            &hashes,
+            || false,
+        );
+
+        assert!(result.groups.is_empty());
+    }
+
+    #[test]
+    fn test_undecodable_image_reported() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("broken.jpg");
+        std::fs::write(&path, b"not an image").unwrap();
+        let file = FileEntry::new(path.display().to_string(), 12, None);
+
+        let result = find_similar_images(
+            &[file],
+            &HashOptions::default(),
+            ImageHashAlgorithm::Difference,
+            DEFAULT_IMAGE_DISTANCE,
+            &[],
+            || false,
+        );
+
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].message.contains("decode"));
+    }
+
+    #[test]
+    fn test_cancelled_skips_work() {
+        let dir = TempDir::new().unwrap();
+        let a = save_gray(&dir, "a.png", test_pattern(64), 64);
+        let b = save_gray(&dir, "b.png", test_pattern(64), 64);
+
+        let result = find_similar_images(
+            &[a, b],
+            &HashOptions::default(),
+            ImageHashAlgorithm::Difference,
+            DEFAULT_IMAGE_DISTANCE,
+            &[],
+            || true,
+        );
+
+        assert!(result.groups.is_empty());
+        assert!(result.errors.is_empty());
+    }
+}
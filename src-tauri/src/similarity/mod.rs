@@ -0,0 +1,285 @@
+//! Near-duplicate detection module.
+//!
+//! Finds files that are similar rather than byte-identical by comparing
+//! 64-bit fingerprints by Hamming distance. Fingerprints are indexed in a
+//! BK-tree so each lookup only visits nearby candidates. Large files that
+//! share long runs of bytes are compared by content-defined chunks instead.
+
+pub mod chunks;
+pub mod images;
+pub mod text;
+
+pub use chunks::analyze_chunk_overlap;
+pub use images::find_similar_images;
+pub use text::find_similar_text;
+
+use crate::types::{FileEntry, SimilarFile, SimilarityGroup};
+use std::collections::HashMap;
+
+/// A cluster of similar fingerprints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// (index into the input, Hamming distance to the first member).
+    /// The first member is the cluster's reference, at distance 0.
+    pub members: Vec<(usize, u32)>,
+}
+
+/// Groups fingerprints that lie within `max_distance` of a reference.
+///
+/// Fingerprints are taken as references in input order, so callers should
+/// order the input by preference (e.g. largest file first). Each fingerprint
+/// joins at most one cluster, and members are always compared against the
+/// reference rather than each other, so chains of slightly different items
+/// don't merge into one sprawling group.
+///
+/// # Arguments
+/// * `fingerprints` - 64-bit fingerprints, in order of reference preference
+/// * `max_distance` - Maximum Hamming distance from the reference
+///
+/// # Returns
+/// Clusters with at least 2 members, in the order their references appear.
+pub fn cluster_by_distance(fingerprints: &[u64], max_distance: u32) -> Vec<Cluster> {
+    let mut tree = BkTree::default();
+    for (index, &fingerprint) in fingerprints.iter().enumerate() {
+        tree.insert(fingerprint, index);
+    }
+
+    let mut assigned = vec![false; fingerprints.len()];
+    let mut clusters = Vec::new();
+
+    for (index, &fingerprint) in fingerprints.iter().enumerate() {
+        if assigned[index] {
+            continue;
+        }
+
+        let mut members: Vec<(usize, u32)> = tree
+            .find(fingerprint, max_distance)
+            .into_iter()
+            .filter(|&(other, _)| other != index && !assigned[other])
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        members.sort_unstable_by_key(|&(other, distance)| (distance, other));
+        members.insert(0, (index, 0));
+        for &(member, _) in &members {
+            assigned[member] = true;
+        }
+
+        clusters.push(Cluster { members });
+    }
+
+    clusters
+}
+
+/// Turns clusters into similarity groups, largest group first.
+///
+/// Groups whose members are all byte-identical are left out, since they are
+/// already reported as exact duplicates.
+///
+/// # Arguments
+/// * `clusters` - Clusters from `cluster_by_distance`
+/// * `files` - The files the clustered fingerprints were computed from
+/// * `exact_hashes` - Content hashes from the scan
+pub fn build_groups(
+    clusters: Vec<Cluster>,
+    files: &[&FileEntry],
+    exact_hashes: &[(FileEntry, String)],
+) -> Vec<SimilarityGroup> {
+    let exact: HashMap<&str, &str> = exact_hashes
+        .iter()
+        .map(|(file, hash)| (file.path.as_str(), hash.as_str()))
+        .collect();
+    let exact_hash = |index: usize| exact.get(files[index].path.as_str());
+
+    let mut groups: Vec<SimilarityGroup> = clusters
+        .into_iter()
+        .filter(|cluster| {
+            let first = exact_hash(cluster.members[0].0);
+            first.is_none() || cluster.members.iter().any(|&(i, _)| exact_hash(i) != first)
+        })
+        .map(|cluster| {
+            SimilarityGroup::new(
+                cluster
+                    .members
+                    .into_iter()
+                    .map(|(i, distance)| {
+                        SimilarFile::new(files[i].clone(), similarity_score(distance))
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.members.len()));
+    groups
+}
+
+/// Converts a Hamming distance between 64-bit fingerprints to a 0.0-1.0 similarity.
+pub fn similarity_score(distance: u32) -> f64 {
+    1.0 - f64::from(distance.min(64)) / 64.0
+}
+
+/// Hamming distance between two fingerprints.
+fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// A BK-tree over 64-bit fingerprints using Hamming distance.
+#[derive(Debug, Default)]
+struct BkTree {
+    nodes: Vec<BkNode>,
+}
+
+#[derive(Debug)]
+struct BkNode {
+    fingerprint: u64,
+    index: usize,
+    /// (distance to this node, child node position)
+    children: Vec<(u32, usize)>,
+}
+
+impl BkTree {
+    /// Adds a fingerprint with the caller's index.
+    fn insert(&mut self, fingerprint: u64, index: usize) {
+        let new_node = BkNode {
+            fingerprint,
+            index,
+            children: Vec::new(),
+        };
+
+        if self.nodes.is_empty() {
+            self.nodes.push(new_node);
+            return;
+        }
+
+        let mut current = 0;
+        loop {
+            let distance = hamming(self.nodes[current].fingerprint, fingerprint);
+            let next = self.nodes[current]
+                .children
+                .iter()
+                .find(|&&(d, _)| d == distance)
+                .map(|&(_, child)| child);
+
+            match next {
+                Some(child) => current = child,
+                None => {
+                    let position = self.nodes.len();
+                    self.nodes.push(new_node);
+                    self.nodes[current].children.push((distance, position));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns (index, distance) for every fingerprint within `max_distance`.
+    fn find(&self, fingerprint: u64, max_distance: u32) -> Vec<(usize, u32)> {
+        let mut found = Vec::new();
+        if self.nodes.is_empty() {
+            return found;
+        }
+
+        let mut stack = vec![0];
+        while let Some(current) = stack.pop() {
+            let node = &self.nodes[current];
+            let distance = hamming(node.fingerprint, fingerprint);
+            if distance <= max_distance {
+                found.push((node.index, distance));
+            }
+
+            // Triangle inequality: only subtrees in this band can match
+            let low = distance.saturating_sub(max_distance);
+            let high = distance + max_distance;
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|&&(d, _)| d >= low && d <= high)
+                    .map(|&(_, child)| child),
+            );
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bk_tree_matches_linear_search() {
+        // Deterministic pseudo-random fingerprints
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let fingerprints: Vec<u64> = (0..500)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            })
+            .collect();
+
+        let mut tree = BkTree::default();
+        for (i, &f) in fingerprints.iter().enumerate() {
+            tree.insert(f, i);
+        }
+
+        let query = fingerprints[42] ^ 0b1011;
+        for max_distance in [0, 3, 20, 30] {
+            let mut found = tree.find(query, max_distance);
+            found.sort_unstable();
+
+            let expected: Vec<(usize, u32)> = fingerprints
+                .iter()
+                .enumerate()
+                .map(|(i, &f)| (i, hamming(f, query)))
+                .filter(|&(_, d)| d <= max_distance)
+                .collect();
+
+            assert_eq!(found, expected, "max_distance {}", max_distance);
+        }
+    }
+
+    #[test]
+    fn test_cluster_by_distance() {
+        let fingerprints = [
+            0x0000_0000_0000_0000, // reference for the first cluster
+            0x0000_0000_0000_0003, // 2 bits from [0]
+            0xffff_ffff_ffff_ffff, // unrelated
+            0x0000_0000_0000_0001, // 1 bit from [0]
+        ];
+
+        let clusters = cluster_by_distance(&fingerprints, 2);
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, vec![(0, 0), (3, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_cluster_does_not_chain() {
+        // a-b and b-c are within 2 bits, but a-c is 4 bits apart
+        let fingerprints = [0b0000, 0b0011, 0b1111];
+
+        let clusters = cluster_by_distance(&fingerprints, 2);
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn test_identical_fingerprints_cluster() {
+        let clusters = cluster_by_distance(&[7, 7, 7], 0);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members.len(), 3);
+    }
+
+    #[test]
+    fn test_similarity_score() {
+        assert_eq!(similarity_score(0), 1.0);
+        assert_eq!(similarity_score(16), 0.75);
+        assert_eq!(similarity_score(64), 0.0);
+    }
+}
@@ -0,0 +1,422 @@
+//! SimHash fingerprints for near-duplicate text detection.
+//!
+//! Text is split into lowercase words, overlapping word shingles are hashed,
+//! and the shingle hashes are combined into a 64-bit SimHash. Documents that
+//! share most of their shingles (a changed date, an extra paragraph) end up
+//! only a few bits apart.
+//!
+//! Only plain-text formats are understood; binary documents such as PDF or
+//! DOCX are not text-like and are skipped without being opened.
+
+use super::{build_groups, cluster_by_distance};
+use crate::hasher::audio::AUDIO_EXTENSIONS;
+use crate::hasher::{pool, read, HashOptions};
+use crate::similarity::images::IMAGE_EXTENSIONS;
+use crate::types::{FileEntry, ScanError, SimilarityGroup};
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// File extensions always treated as text (lowercase).
+pub const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "tex", "csv", "tsv", "json", "xml", "html", "htm", "rtf",
+    "log", "yaml", "yml", "toml", "ini", "cfg", "conf", "srt", "vtt",
+];
+
+/// File extensions of binary formats that are never sniffed (lowercase),
+/// in addition to image and audio extensions.
+pub const BINARY_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "zip", "gz", "bz2",
+    "xz", "zst", "7z", "rar", "tar", "iso", "img", "dmg", "exe", "dll", "so", "dylib", "bin",
+    "mp4", "mkv", "mov", "avi", "wmv", "webm", "m4v", "wav", "heic", "raw", "cr2", "nef", "psd",
+    "sqlite", "db",
+];
+
+/// Default minimum similarity (0.0-1.0) for text files to be grouped.
+pub const DEFAULT_TEXT_SIMILARITY: f64 = 0.9;
+
+/// Files larger than this are not compared (16 MiB).
+pub const MAX_TEXT_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Number of leading bytes inspected when sniffing files without a known
+/// text extension.
+const SNIFF_SIZE: usize = 8 * 1024;
+
+/// Number of consecutive words per shingle.
+const SHINGLE_WORDS: usize = 3;
+
+/// Result of similar text detection.
+#[derive(Debug, Default)]
+pub struct SimilarText {
+    /// Groups of similar text files, largest group first.
+    pub groups: Vec<SimilarityGroup>,
+
+    /// Text files that could not be read.
+    pub errors: Vec<ScanError>,
+}
+
+/// Returns true if the path has a known text extension.
+pub fn has_text_extension(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| TEXT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Returns true if the path has an extension of a known binary format.
+pub fn has_binary_extension(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| {
+            let e = e.to_ascii_lowercase();
+            [BINARY_EXTENSIONS, IMAGE_EXTENSIONS, AUDIO_EXTENSIONS]
+                .iter()
+                .any(|list| list.contains(&e.as_str()))
+        })
+        .unwrap_or(false)
+}
+
+/// Returns true if `bytes` look like text: no NUL bytes and valid UTF-8,
+/// allowing a multi-byte character to be cut off at the end.
+pub fn looks_like_text(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        // error_len() is None when the input merely ends mid-character
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Finds groups of text files with nearly identical content.
+///
+/// Files with a known text extension are always compared and files with a
+/// known binary extension never are; only files with an unknown extension
+/// are opened to check whether their first bytes look like text. Within each group the
+/// largest file is the reference the others are scored against. Groups of
+/// byte-identical files are left out, since they are already reported as
+/// exact duplicates.
+///
+/// # Arguments
+/// * `files` - Candidate files; binary and oversized files are ignored
+/// * `options` - Thread, throughput and priority limits for reading files
+/// * `min_similarity` - Minimum similarity (0.0-1.0) to the group's reference
+/// * `exact_hashes` - Content hashes from the scan, used to skip exact duplicates
+/// * `is_cancelled` - Returns true if the scan was cancelled; remaining files are skipped
+pub fn find_similar_text<C>(
+    files: &[FileEntry],
+    options: &HashOptions,
+    min_similarity: f64,
+    exact_hashes: &[(FileEntry, String)],
+    is_cancelled: C,
+) -> SimilarText
+where
+    C: Fn() -> bool + Send + Sync,
+{
+    let mut candidates: Vec<&FileEntry> = files
+        .iter()
+        .filter(|f| f.size > 0 && f.size <= MAX_TEXT_FILE_SIZE)
+        .filter(|f| !has_binary_extension(&f.path))
+        .collect();
+    candidates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let fingerprint_all = || -> Vec<(&FileEntry, io::Result<Option<u64>>)> {
+        candidates
+            .into_par_iter()
+            .filter_map(|file| {
+                if is_cancelled() {
+                    return None;
+                }
+                Some((file, text_fingerprint(Path::new(&file.path), options)))
+            })
+            .collect()
+    };
+
+    let fingerprints = match pool::build_hash_pool(options.max_threads, options.low_priority) {
+        Ok(hash_pool) => hash_pool.install(fingerprint_all),
+        Err(e) => {
+            warn!("Failed to build hash pool, using global pool: {}", e);
+            fingerprint_all()
+        }
+    };
+
+    let mut hashed: Vec<&FileEntry> = Vec::new();
+    let mut values: Vec<u64> = Vec::new();
+    let mut errors = Vec::new();
+    for (file, fingerprint) in fingerprints {
+        match fingerprint {
+            Ok(Some(value)) => {
+                hashed.push(file);
+                values.push(value);
+            }
+            Ok(None) => {}
+            Err(e) => errors.push(ScanError::new(
+                file.path.clone(),
+                format!("Could not read text: {}", e),
+            )),
+        }
+    }
+
+    let clusters = cluster_by_distance(&values, max_distance_for(min_similarity));
+    let groups = build_groups(clusters, &hashed, exact_hashes);
+
+    debug!(
+        "Compared {} text files: {} similar groups, {} unreadable",
+        values.len(),
+        groups.len(),
+        errors.len()
+    );
+
+    SimilarText { groups, errors }
+}
+
+/// Converts a 0.0-1.0 similarity threshold into a maximum SimHash distance.
+pub fn max_distance_for(min_similarity: f64) -> u32 {
+    ((1.0 - min_similarity.clamp(0.0, 1.0)) * 64.0).floor() as u32
+}
+
+/// Reads a file through the read throttle and computes its SimHash.
+///
+/// # Returns
+/// `Ok(None)` if the file is not text-like or contains no words.
+pub fn text_fingerprint(path: &Path, options: &HashOptions) -> io::Result<Option<u64>> {
+    let file = File::open(path)?;
+    let throttle = options.throttle.as_deref();
+    let mut bytes = Vec::new();
+
+    if !has_text_extension(&path.to_string_lossy()) {
+        read::read_chunks(
+            (&file).take(SNIFF_SIZE as u64),
+            SNIFF_SIZE,
+            throttle,
+            |chunk| bytes.extend_from_slice(chunk),
+        )?;
+        if !looks_like_text(&bytes) {
+            return Ok(None);
+        }
+    }
+
+    // Continues after the sniffed bytes, if any
+    read::read_chunks(&file, SNIFF_SIZE, throttle, |chunk| {
+        bytes.extend_from_slice(chunk)
+    })?;
+    Ok(simhash(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Computes the SimHash of a text's word shingles.
+///
+/// Texts shorter than one shingle are hashed word by word.
+///
+/// # Returns
+/// None if the text contains no words.
+pub fn simhash(text: &str) -> Option<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.is_empty() {
+        return None;
+    }
+
+    let shingle_len = SHINGLE_WORDS.min(words.len());
+    let mut weights = [0i64; 64];
+
+    for shingle in words.windows(shingle_len) {
+        let hash = shingle_hash(shingle);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+
+    Some(
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, &w)| w > 0)
+            .fold(0u64, |acc, (bit, _)| acc | (1 << bit)),
+    )
+}
+
+/// Stable 64-bit FNV-1a hash of a shingle's words.
+fn shingle_hash(words: &[String]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET;
+    for word in words {
+        for byte in word.bytes().chain(std::iter::once(b' ')) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+
+    // FNV's low bits are weak for short inputs; finish with a 64-bit mixer
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn article(extra: &str) -> String {
+        let mut text = String::new();
+        for i in 0..60 {
+            text.push_str(&format!(
+                "Paragraph {} of the quarterly report discusses revenue growth and outlook. ",
+                i
+            ));
+        }
+        text.push_str(extra);
+        text
+    }
+
+    fn write(dir: &TempDir, name: &str, content: &[u8]) -> FileEntry {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        FileEntry::new(path.display().to_string(), content.len() as u64, None)
+    }
+
+    #[test]
+    fn test_looks_like_text() {
+        assert!(looks_like_text(b"plain ascii"));
+        assert!(looks_like_text("héllo".as_bytes()));
+        // Truncated in the middle of a two-byte character
+        assert!(looks_like_text(&"é".as_bytes()[..1]));
+        assert!(!looks_like_text(b"bin\0ary"));
+        assert!(!looks_like_text(&[0xff, 0xfe, 0x41]));
+    }
+
+    #[test]
+    fn test_simhash_ignores_case_and_punctuation() {
+        assert_eq!(simhash("Hello, World! Foo"), simhash("hello world foo"));
+        assert_eq!(simhash("   ...  "), None);
+    }
+
+    #[test]
+    fn test_simhash_small_edit_is_close() {
+        let a = simhash(&article("")).unwrap();
+        let b = simhash(&article("An extra closing paragraph was added here.")).unwrap();
+        let c = simhash("Completely unrelated notes about gardening and tomatoes.").unwrap();
+
+        assert!((a ^ b).count_ones() <= max_distance_for(DEFAULT_TEXT_SIMILARITY));
+        assert!((a ^ c).count_ones() > max_distance_for(DEFAULT_TEXT_SIMILARITY));
+    }
+
+    #[test]
+    fn test_max_distance_for() {
+        assert_eq!(max_distance_for(1.0), 0);
+        assert_eq!(max_distance_for(0.9), 6);
+        assert_eq!(max_distance_for(-1.0), 64);
+    }
+
+    #[test]
+    fn test_find_similar_text() {
+        let dir = TempDir::new().unwrap();
+        let original = write(&dir, "report.txt", article("").as_bytes());
+        let edited = write(
+            &dir,
+            "report-v2.md",
+            article("An extra closing paragraph was added here.").as_bytes(),
+        );
+        // No extension, detected by content sniffing
+        let sniffed = write(&dir, "REPORT", article("Signed.").as_bytes());
+        let other = write(
+            &dir,
+            "notes.txt",
+            b"Completely unrelated notes about gardening.",
+        );
+        let binary = write(&dir, "blob.bin", &[0u8, 1, 2, 3, 0, 5]);
+
+        let result = find_similar_text(
+            &[original, edited.clone(), sniffed.clone(), other, binary],
+            &HashOptions::default(),
+            DEFAULT_TEXT_SIMILARITY,
+            &[],
+            || false,
+        );
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.groups.len(), 1);
+        let members = &result.groups[0].members;
+        assert_eq!(members.len(), 3);
+        // The largest file is the reference
+        assert_eq!(members[0].file, edited);
+        assert_eq!(members[0].similarity, 1.0);
+        assert!(members.iter().any(|m| m.file == sniffed));
+        assert!(members
+            .iter()
+            .all(|m| m.similarity >= DEFAULT_TEXT_SIMILARITY));
+    }
+
+    #[test]
+    fn test_exact_duplicates_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let a = write(&dir, "a.txt", article("").as_bytes());
+        let b = write(&dir, "b.txt", article("").as_bytes());
+        let hashes = vec![
+            (a.clone(), "same".to_string()),
+            (b.clone(), "same".to_string()),
+        ];
+
+        let result = find_similar_text(
+            &[a, b],
+            &HashOptions::default(),
+            DEFAULT_TEXT_SIMILARITY,
+            &hashes,
+            || false,
+        );
+
+        assert!(result.groups.is_empty());
+    }
+
+    #[test]
+    fn test_missing_file_reported() {
+        let file = FileEntry::new("/nonexistent/file.txt".to_string(), 10, None);
+
+        let result = find_similar_text(
+            &[file],
+            &HashOptions::default(),
+            DEFAULT_TEXT_SIMILARITY,
+            &[],
+            || false,
+        );
+
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_binary_extensions_are_not_opened() {
+        let dir = TempDir::new().unwrap();
+        // Text content, but a binary extension: skipped without sniffing
+        let movie = write(&dir, "clip.MP4", article("").as_bytes());
+        let missing = FileEntry::new("/nonexistent/photo.jpg".to_string(), 10, None);
+
+        let result = find_similar_text(
+            &[movie.clone(), movie, missing],
+            &HashOptions::default(),
+            DEFAULT_TEXT_SIMILARITY,
+            &[],
+            || false,
+        );
+
+        assert!(result.errors.is_empty());
+        assert!(result.groups.is_empty());
+        assert!(has_binary_extension("/a/song.flac"));
+        assert!(!has_binary_extension("/a/README"));
+    }
+}
@@ -1,9 +1,15 @@
 //! Application state management for the Duplicate File Detector.
 //!
-//! Provides thread-safe state for tracking scan status and cancellation.
+//! Provides thread-safe state for tracking scan status and cancellation, and
+//! keeps what the last scan left behind for the commands that follow it.
 
+use crate::duplicates::ReferenceRoots;
+use crate::scanner::empty::DirectoryListing;
+use crate::types::{FileEntry, HashAlgorithm, ScanResult};
 use parking_lot::RwLock;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 /// Thread-safe application state.
 #[derive(Debug, Default)]
@@ -16,6 +22,31 @@ pub struct AppState {
 
     /// Current scan ID for matching events.
     current_scan_id: RwLock<Option<String>>,
+
+    /// Reference roots of the most recent scan; files under them are never deleted.
+    reference_roots: RwLock<ReferenceRoots>,
+
+    /// Content hashes computed by the most recent completed scan.
+    last_hashes: RwLock<Option<Arc<ComputedHashes>>>,
+
+    /// Directory layout seen by the most recent completed scan.
+    directory_listing: RwLock<Option<Arc<DirectoryListing>>>,
+
+    /// Result of the most recent completed scan, import or reopened session.
+    last_result: RwLock<Option<Arc<ScanResult>>>,
+
+    /// Files of a reopened session that changed on disk; never deleted.
+    stale_paths: RwLock<HashSet<String>>,
+}
+
+/// Content hashes computed by a scan.
+#[derive(Debug, Clone)]
+pub struct ComputedHashes {
+    /// Algorithm the hashes were computed with.
+    pub algorithm: HashAlgorithm,
+
+    /// (file, hash) pairs.
+    pub hashes: Vec<(FileEntry, String)>,
 }
 
 impl AppState {
@@ -25,6 +56,11 @@ impl AppState {
             is_scanning: AtomicBool::new(false),
             cancel_requested: AtomicBool::new(false),
             current_scan_id: RwLock::new(None),
+            reference_roots: RwLock::new(ReferenceRoots::default()),
+            last_hashes: RwLock::new(None),
+            directory_listing: RwLock::new(None),
+            last_result: RwLock::new(None),
+            stale_paths: RwLock::new(HashSet::new()),
         }
     }
 
@@ -82,12 +118,79 @@ impl AppState {
         self.current_scan_id.read().clone()
     }
 
+    /// Records the reference roots of the scan being run.
+    pub fn set_reference_roots(&self, roots: ReferenceRoots) {
+        *self.reference_roots.write() = roots;
+    }
+
+    /// Returns whether `path` lies under a reference root of the last scan.
+    pub fn is_reference(&self, path: &str) -> bool {
+        self.reference_roots.read().contains(path)
+    }
+
+    /// Replaces the hashes kept from the last completed scan.
+    pub fn set_last_hashes(&self, hashes: Option<ComputedHashes>) {
+        *self.last_hashes.write() = hashes.map(Arc::new);
+    }
+
+    /// Returns the hashes of the last completed scan, if any.
+    pub fn last_hashes(&self) -> Option<Arc<ComputedHashes>> {
+        self.last_hashes.read().clone()
+    }
+
+    /// Replaces the directory layout kept from the last completed scan.
+    pub fn set_directory_listing(&self, listing: Option<DirectoryListing>) {
+        *self.directory_listing.write() = listing.map(Arc::new);
+    }
+
+    /// Returns the directory layout of the last completed scan, if any.
+    pub fn directory_listing(&self) -> Option<Arc<DirectoryListing>> {
+        self.directory_listing.read().clone()
+    }
+
+    /// Replaces the result kept for querying.
+    ///
+    /// Stale marks belong to the previous result and are cleared.
+    pub fn set_last_result(&self, result: Option<ScanResult>) {
+        *self.last_result.write() = result.map(Arc::new);
+        self.stale_paths.write().clear();
+    }
+
+    /// Records the files of the last result that changed on disk.
+    pub fn set_stale_paths(&self, paths: HashSet<String>) {
+        *self.stale_paths.write() = paths;
+    }
+
+    /// Returns whether `path` changed on disk since the last result was made.
+    pub fn is_stale(&self, path: &str) -> bool {
+        self.stale_paths.read().contains(path)
+    }
+
+    /// Returns the last result, if any.
+    pub fn last_result(&self) -> Option<Arc<ScanResult>> {
+        self.last_result.read().clone()
+    }
+
+    /// Drops deleted files from the last result so later queries don't
+    /// return them.
+    pub fn remove_deleted_files(&self, paths: &[String]) {
+        if let Some(result) = self.last_result.write().as_mut() {
+            let paths: HashSet<&str> = paths.iter().map(String::as_str).collect();
+            Arc::make_mut(result).remove_files(&paths);
+        }
+    }
+
     /// Resets the state to initial values.
     /// Used primarily for testing.
     pub fn reset(&self) {
         self.is_scanning.store(false, Ordering::SeqCst);
         self.cancel_requested.store(false, Ordering::SeqCst);
         *self.current_scan_id.write() = None;
+        *self.reference_roots.write() = ReferenceRoots::default();
+        *self.last_hashes.write() = None;
+        *self.directory_listing.write() = None;
+        *self.last_result.write() = None;
+        self.stale_paths.write().clear();
     }
 }
 
@@ -118,6 +221,87 @@ mod tests {
         assert!(state.current_scan_id().is_none());
     }
 
+    #[test]
+    fn test_reference_roots() {
+        let state = AppState::new();
+        assert!(!state.is_reference("/archive/a.jpg"));
+
+        state.set_reference_roots(ReferenceRoots::new(&["/archive".to_string()]));
+        assert!(state.is_reference("/archive/a.jpg"));
+        assert!(!state.is_reference("/downloads/a.jpg"));
+
+        state.reset();
+        assert!(!state.is_reference("/archive/a.jpg"));
+    }
+
+    #[test]
+    fn test_last_hashes() {
+        let state = AppState::new();
+        assert!(state.last_hashes().is_none());
+
+        state.set_last_hashes(Some(ComputedHashes {
+            algorithm: HashAlgorithm::Md5,
+            hashes: vec![(FileEntry::new("/a".to_string(), 1, None), "h".to_string())],
+        }));
+        assert_eq!(state.last_hashes().unwrap().hashes.len(), 1);
+
+        state.reset();
+        assert!(state.last_hashes().is_none());
+    }
+
+    #[test]
+    fn test_directory_listing() {
+        let state = AppState::new();
+        assert!(state.directory_listing().is_none());
+
+        state.set_directory_listing(Some(DirectoryListing::default()));
+        assert!(state.directory_listing().is_some());
+
+        state.reset();
+        assert!(state.directory_listing().is_none());
+    }
+
+    #[test]
+    fn test_last_result_forgets_deleted_files() {
+        use crate::types::DuplicateGroup;
+
+        let state = AppState::new();
+        assert!(state.last_result().is_none());
+
+        let files = vec![
+            FileEntry::new("/a".to_string(), 10, None),
+            FileEntry::new("/b".to_string(), 10, None),
+            FileEntry::new("/c".to_string(), 10, None),
+        ];
+        let group = DuplicateGroup::new("h".to_string(), 10, files);
+        state.set_last_result(Some(ScanResult::new(vec![group], 3, Vec::new(), 1)));
+
+        state.remove_deleted_files(&["/c".to_string()]);
+        let result = state.last_result().unwrap();
+        assert_eq!(result.duplicate_groups[0].files.len(), 2);
+        assert_eq!(result.total_wasted_space, 10);
+
+        state.remove_deleted_files(&["/b".to_string()]);
+        let result = state.last_result().unwrap();
+        assert!(result.duplicate_groups.is_empty());
+        assert_eq!(result.total_wasted_space, 0);
+
+        state.reset();
+        assert!(state.last_result().is_none());
+    }
+
+    #[test]
+    fn test_stale_paths_cleared_with_result() {
+        let state = AppState::new();
+        state.set_last_result(Some(ScanResult::new(Vec::new(), 0, Vec::new(), 1)));
+        state.set_stale_paths(HashSet::from(["/gone".to_string()]));
+        assert!(state.is_stale("/gone"));
+        assert!(!state.is_stale("/kept"));
+
+        state.set_last_result(Some(ScanResult::new(Vec::new(), 0, Vec::new(), 1)));
+        assert!(!state.is_stale("/gone"));
+    }
+
     #[test]
     fn test_start_scan() {
         let state = AppState::new();
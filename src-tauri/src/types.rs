@@ -4,6 +4,8 @@
 //! serialized as JSON with camelCase naming for JavaScript compatibility.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
 
 /// Options for configuring a duplicate scan operation.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -30,6 +32,217 @@ pub struct ScanOptions {
     /// Default is false to avoid infinite loops.
     #[serde(default)]
     pub follow_symlinks: bool,
+
+    /// Algorithm used to hash file contents.
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+
+    /// Maximum number of threads used for hashing.
+    /// If None, one thread per CPU is used.
+    #[serde(default)]
+    pub max_hash_threads: Option<usize>,
+
+    /// Maximum number of concurrent hashing threads per rotational disk.
+    /// If None, spinning disks are read by a single thread at a time.
+    #[serde(default)]
+    pub rotational_hash_threads: Option<usize>,
+
+    /// Cap on combined read throughput while hashing, in bytes per second.
+    /// If None, reads are not throttled.
+    #[serde(default)]
+    pub max_bytes_per_second: Option<u64>,
+
+    /// Run hashing threads at reduced CPU and I/O priority (Linux only).
+    #[serde(default)]
+    pub low_priority: bool,
+
+    /// How file contents are read while hashing.
+    #[serde(default)]
+    pub read_strategy: ReadStrategy,
+
+    /// Hint the OS to drop hashed files from the page cache (Linux only),
+    /// so a scan doesn't evict data other applications are using.
+    #[serde(default)]
+    pub avoid_page_cache: bool,
+
+    /// Also look for visually similar images (resized, re-encoded, stripped EXIF).
+    #[serde(default)]
+    pub find_similar_images: bool,
+
+    /// Perceptual hash used to compare images.
+    #[serde(default)]
+    pub image_hash_algorithm: ImageHashAlgorithm,
+
+    /// Maximum Hamming distance (out of 64 bits) between similar images.
+    /// If None, a default of 10 is used.
+    #[serde(default)]
+    pub image_similarity_distance: Option<u32>,
+
+    /// Also look for nearly identical text files (by extension or content sniffing).
+    #[serde(default)]
+    pub find_similar_text: bool,
+
+    /// Minimum similarity (0.0-1.0) for text files to be grouped.
+    /// If None, a default of 0.9 is used.
+    #[serde(default)]
+    pub text_similarity_threshold: Option<f64>,
+
+    /// Compare MP3/FLAC/Ogg/M4A files by their audio payload only, so copies
+    /// with different tags are reported as duplicates.
+    #[serde(default)]
+    pub match_audio_content: bool,
+
+    /// Also split large files into content-defined chunks to find files that
+    /// share most of their bytes, and estimate block-level dedup savings.
+    #[serde(default)]
+    pub analyze_chunk_overlap: bool,
+
+    /// Minimum shared fraction (0.0-1.0) of the smaller file for a pair to be
+    /// reported. If None, a default of 0.5 is used.
+    #[serde(default)]
+    pub chunk_overlap_threshold: Option<f64>,
+
+    /// Roots holding reference copies (e.g. an archive). They are scanned
+    /// along with `root_paths`; when set, only groups containing both a
+    /// reference and a non-reference file are reported, and reference files
+    /// are never deleted.
+    #[serde(default)]
+    pub reference_roots: Vec<String>,
+
+    /// Checksum manifests (`md5sum`/`sha256sum`/`b3sum` output) or exported
+    /// JSON scan reports whose hashes form a virtual reference set.
+    #[serde(default)]
+    pub reference_manifests: Vec<String>,
+
+    /// Hash every scanned file, not just files sharing a size with another,
+    /// so a complete manifest can be exported afterwards.
+    #[serde(default)]
+    pub hash_all_files: bool,
+
+    /// Also report files that share a name but differ in content, to help
+    /// resolve folder merges.
+    #[serde(default)]
+    pub find_name_conflicts: bool,
+
+    /// What counts as the same name when looking for conflicts.
+    #[serde(default)]
+    pub name_conflict_key: NameConflictKey,
+
+    /// Leave duplicate groups out of the returned result; the full result
+    /// is kept in the backend and fetched a page at a time with `query_groups`.
+    #[serde(default)]
+    pub page_results: bool,
+}
+
+impl ScanOptions {
+    /// Returns the directories to walk: root and reference paths, skipping
+    /// exact repeats and paths nested inside another listed path, so no
+    /// file is scanned twice.
+    pub fn scan_roots(&self) -> Vec<String> {
+        let all: Vec<&String> = self
+            .root_paths
+            .iter()
+            .chain(&self.reference_roots)
+            .collect();
+
+        let mut roots: Vec<String> = Vec::new();
+        for (i, path) in all.iter().enumerate() {
+            let nested = all.iter().enumerate().any(|(j, other)| {
+                j != i && other != path && Path::new(path.as_str()).starts_with(other.as_str())
+            });
+            if !nested && !roots.contains(path) {
+                roots.push(path.to_string());
+            }
+        }
+        roots
+    }
+}
+
+/// Content hash algorithms.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    /// MD5 (128-bit).
+    #[default]
+    Md5,
+
+    /// SHA-256 (256-bit). Slower than BLAKE3; useful to match `sha256sum` manifests.
+    Sha256,
+
+    /// BLAKE3 (256-bit). Faster, and very large files are hashed on multiple threads.
+    Blake3,
+}
+
+/// How files are matched up when looking for name conflicts.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NameConflictKey {
+    /// Same file name, anywhere in the scanned roots.
+    #[default]
+    FileName,
+
+    /// Same path relative to the scan root, in different roots.
+    RelativePath,
+}
+
+/// Output formats for exported hash manifests.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestFormat {
+    /// `<hex>  <path>` lines, as written by `md5sum`/`sha256sum`/`b3sum`.
+    #[default]
+    Checksums,
+
+    /// Versioned JSON with size and modification time for each file.
+    Json,
+}
+
+/// Output formats for exported scan reports.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    /// Versioned JSON holding the full scan result.
+    #[default]
+    Json,
+
+    /// One row per duplicate file.
+    Csv,
+
+    /// Self-contained static HTML page.
+    Html,
+
+    /// Plain path lists as printed by `fdupes`/`jdupes`.
+    Fdupes,
+}
+
+/// Strategies for reading file contents while hashing.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadStrategy {
+    /// 64 KB reads through a buffered reader.
+    Buffered,
+
+    /// Large (1 MiB) reads straight into a single buffer.
+    #[default]
+    Direct,
+
+    /// Memory-map large files; smaller files use direct reads.
+    Mmap,
+}
+
+/// Perceptual hash algorithms for comparing images.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageHashAlgorithm {
+    /// Average hash: each pixel compared to the mean brightness. Fastest, least robust.
+    Average,
+
+    /// Difference hash: each pixel compared to its right neighbour.
+    #[default]
+    Difference,
+
+    /// DCT-based hash: compares low-frequency components. Most robust to re-encoding.
+    Perceptual,
 }
 
 /// Represents a single file entry with its metadata.
@@ -45,6 +258,14 @@ pub struct FileEntry {
     /// Last modification time as ISO 8601 string, if available.
     #[serde(default)]
     pub modified: Option<String>,
+
+    /// True if the file lies under a reference root and must not be deleted.
+    #[serde(default)]
+    pub is_reference: bool,
+
+    /// Set when a reopened session finds the file no longer matches the disk.
+    #[serde(default)]
+    pub stale: Option<StaleReason>,
 }
 
 impl FileEntry {
@@ -54,28 +275,58 @@ impl FileEntry {
             path,
             size,
             modified,
+            is_reference: false,
+            stale: None,
         }
     }
 }
 
+/// Why a file from a saved session no longer matches the disk.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StaleReason {
+    /// The file no longer exists or can't be accessed.
+    Missing,
+
+    /// The file's size or modification time changed since the scan.
+    Changed,
+}
+
 /// A group of duplicate files sharing the same content hash.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct DuplicateGroup {
-    /// MD5 hash of the file content (lowercase hex string).
+    /// Hash of the file content (lowercase hex string).
     pub hash: String,
 
-    /// Size of each file in bytes.
+    /// Size of each file in bytes. For groups whose metadata differs, this
+    /// is the size of the smallest file.
     pub size: u64,
 
     /// List of files with this hash (at least 2 entries).
     pub files: Vec<FileEntry>,
+
+    /// True if the files share the same audio content but have different
+    /// tags. The hash then covers only the audio payload.
+    #[serde(default)]
+    pub metadata_differs: bool,
 }
 
 impl DuplicateGroup {
     /// Creates a new DuplicateGroup.
     pub fn new(hash: String, size: u64, files: Vec<FileEntry>) -> Self {
-        Self { hash, size, files }
+        Self {
+            hash,
+            size,
+            files,
+            metadata_differs: false,
+        }
+    }
+
+    /// Marks the group as a content match with differing metadata.
+    pub fn with_metadata_differs(mut self, metadata_differs: bool) -> Self {
+        self.metadata_differs = metadata_differs;
+        self
     }
 
     /// Returns the number of duplicate files in this group.
@@ -91,6 +342,430 @@ impl DuplicateGroup {
         }
         ((self.files.len() - 1) as u64) * self.size
     }
+
+    /// Returns the group's totals without its file list.
+    pub fn summary(&self) -> GroupSummary {
+        GroupSummary {
+            hash: self.hash.clone(),
+            size: self.size,
+            file_count: self.files.len() as u64,
+            wasted_space: self.wasted_space(),
+            metadata_differs: self.metadata_differs,
+        }
+    }
+}
+
+/// A duplicate group without its files, sent while a paged scan is running.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSummary {
+    /// Hash of the file content (lowercase hex string).
+    pub hash: String,
+
+    /// Size of each file in bytes.
+    pub size: u64,
+
+    /// Number of files in the group.
+    pub file_count: u64,
+
+    /// Space that could be recovered by keeping only one copy.
+    pub wasted_space: u64,
+
+    /// True if the files share the same audio content but have different tags.
+    pub metadata_differs: bool,
+}
+
+/// A root or top-level folder compared in the overlap matrix.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlapFolder {
+    /// Absolute path of the folder.
+    pub path: String,
+
+    /// Number of scanned files under the folder.
+    pub total_files: u64,
+
+    /// Total size of the scanned files under the folder in bytes.
+    pub total_bytes: u64,
+}
+
+/// Content held by both folders of a pair.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderOverlap {
+    /// First folder of the pair.
+    pub folder_a: String,
+
+    /// Second folder of the pair.
+    pub folder_b: String,
+
+    /// Number of distinct files (by content) present in both folders.
+    pub shared_files: u64,
+
+    /// Total size of those files in bytes, counted once per file.
+    pub shared_bytes: u64,
+}
+
+/// How much content each pair of roots (or top-level folders) shares.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlapMatrix {
+    /// Folders compared, sorted by path.
+    pub folders: Vec<OverlapFolder>,
+
+    /// Pairs sharing at least one file, most shared bytes first. Pairs
+    /// that share nothing are left out.
+    pub pairs: Vec<FolderOverlap>,
+}
+
+/// Duplicate files under one folder, including its subfolders.
+///
+/// A group's first file is its keeper: it counts toward the duplicate files
+/// and bytes of its folder, but not toward the wasted space.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSummary {
+    /// Absolute path of the folder.
+    pub path: String,
+
+    /// Folder name; the full path for scan roots.
+    pub name: String,
+
+    /// Number of files under the folder that belong to a duplicate group.
+    pub duplicate_files: u64,
+
+    /// Total size of those files in bytes.
+    pub duplicate_bytes: u64,
+
+    /// Bytes that deleting all but the keepers would free.
+    pub wasted_space: u64,
+
+    /// Subfolders that hold duplicates, most wasted space first.
+    pub children: Vec<FolderSummary>,
+}
+
+/// What duplicate groups are sorted by when queried.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupSortKey {
+    /// Bytes freed by keeping one copy.
+    #[default]
+    WastedSpace,
+
+    /// Size of each file.
+    Size,
+
+    /// Number of copies.
+    Count,
+
+    /// Alphabetically first path in the group.
+    Path,
+
+    /// Most recent modification time in the group.
+    Modified,
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    /// Smallest (or alphabetically first) first.
+    Ascending,
+
+    /// Largest (or most recent) first.
+    Descending,
+}
+
+/// A page request over the duplicate groups of the last result.
+///
+/// File filters (path and extension) match a group when any one of its files
+/// matches all of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupQuery {
+    /// Number of matching groups to skip.
+    #[serde(default)]
+    pub offset: u64,
+
+    /// Maximum number of groups to return (None = default page size).
+    #[serde(default)]
+    pub limit: Option<u64>,
+
+    /// Sort key.
+    #[serde(default)]
+    pub sort_by: GroupSortKey,
+
+    /// Sort direction (None = ascending for paths, descending otherwise).
+    #[serde(default)]
+    pub order: Option<SortOrder>,
+
+    /// Case-insensitive substring a file path must contain.
+    #[serde(default)]
+    pub path_contains: Option<String>,
+
+    /// Wildcard pattern a full file path must match (`*` matches any run of
+    /// characters, including `/`; `?` matches one character).
+    #[serde(default)]
+    pub path_glob: Option<String>,
+
+    /// File extensions to include (empty = all).
+    #[serde(default)]
+    pub extensions: Vec<String>,
+
+    /// Minimum file size in bytes.
+    #[serde(default)]
+    pub min_size: Option<u64>,
+
+    /// Maximum file size in bytes.
+    #[serde(default)]
+    pub max_size: Option<u64>,
+
+    /// Minimum number of copies.
+    #[serde(default)]
+    pub min_copies: Option<u64>,
+}
+
+/// One page of queried duplicate groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPage {
+    /// Groups on this page.
+    pub groups: Vec<DuplicateGroup>,
+
+    /// Offset of the first group on this page.
+    pub offset: u64,
+
+    /// Number of groups matching the filters, across all pages.
+    pub total_groups: u64,
+
+    /// Wasted space of all matching groups.
+    pub total_wasted_space: u64,
+}
+
+/// A group of directories with identical contents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateDirectoryGroup {
+    /// Merkle hash of the directory contents (lowercase hex string).
+    pub hash: String,
+
+    /// Total size of the files in each directory, in bytes.
+    pub total_size: u64,
+
+    /// Number of files in each directory, including subdirectories.
+    pub file_count: u64,
+
+    /// Paths of the identical directories (at least 2 entries).
+    pub directories: Vec<String>,
+}
+
+impl DuplicateDirectoryGroup {
+    /// Creates a new DuplicateDirectoryGroup.
+    pub fn new(hash: String, total_size: u64, file_count: u64, directories: Vec<String>) -> Self {
+        Self {
+            hash,
+            total_size,
+            file_count,
+            directories,
+        }
+    }
+
+    /// Calculates the space recoverable by keeping only one directory.
+    pub fn wasted_space(&self) -> u64 {
+        (self.directories.len().saturating_sub(1) as u64) * self.total_size
+    }
+}
+
+/// A directory whose contents are all present in another directory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySubset {
+    /// Directory whose contents are contained in `superset`.
+    pub subset: String,
+
+    /// Directory containing a copy of every file in `subset`.
+    pub superset: String,
+
+    /// Total size of the files in `subset`, in bytes.
+    pub total_size: u64,
+
+    /// Number of files in `subset`, including subdirectories.
+    pub file_count: u64,
+}
+
+impl DirectorySubset {
+    /// Creates a new DirectorySubset.
+    pub fn new(subset: String, superset: String, total_size: u64, file_count: u64) -> Self {
+        Self {
+            subset,
+            superset,
+            total_size,
+            file_count,
+        }
+    }
+}
+
+/// A group of files that are similar but not necessarily identical.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityGroup {
+    /// Members of the group. The first member is the reference the others
+    /// were compared against.
+    pub members: Vec<SimilarFile>,
+}
+
+impl SimilarityGroup {
+    /// Creates a new SimilarityGroup.
+    pub fn new(members: Vec<SimilarFile>) -> Self {
+        Self { members }
+    }
+}
+
+/// A file in a similarity group.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarFile {
+    /// The file.
+    pub file: FileEntry,
+
+    /// Similarity to the group's reference file, from 0.0 to 1.0 (identical).
+    pub similarity: f64,
+}
+
+impl SimilarFile {
+    /// Creates a new SimilarFile.
+    pub fn new(file: FileEntry, similarity: f64) -> Self {
+        Self { file, similarity }
+    }
+}
+
+/// A scanned file whose content is listed in a reference manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMatch {
+    /// The scanned file.
+    pub file: FileEntry,
+
+    /// Hash of the file (lowercase hex string).
+    pub hash: String,
+
+    /// Algorithm the hash was computed with.
+    pub algorithm: HashAlgorithm,
+
+    /// Path of the manifest listing the content.
+    pub manifest: String,
+
+    /// Paths the manifest lists for this content.
+    pub known_paths: Vec<String>,
+}
+
+impl ManifestMatch {
+    /// Creates a new ManifestMatch.
+    pub fn new(
+        file: FileEntry,
+        hash: String,
+        algorithm: HashAlgorithm,
+        manifest: String,
+        known_paths: Vec<String>,
+    ) -> Self {
+        Self {
+            file,
+            hash,
+            algorithm,
+            manifest,
+            known_paths,
+        }
+    }
+}
+
+/// A pair of files that share content-defined chunks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkOverlap {
+    /// First file of the pair.
+    pub file_a: FileEntry,
+
+    /// Second file of the pair.
+    pub file_b: FileEntry,
+
+    /// Bytes of distinct chunks present in both files.
+    pub shared_bytes: u64,
+
+    /// Shared bytes as a fraction (0.0-1.0) of the smaller file.
+    pub shared_ratio: f64,
+}
+
+impl ChunkOverlap {
+    /// Creates a new ChunkOverlap.
+    pub fn new(file_a: FileEntry, file_b: FileEntry, shared_bytes: u64, shared_ratio: f64) -> Self {
+        Self {
+            file_a,
+            file_b,
+            shared_bytes,
+            shared_ratio,
+        }
+    }
+}
+
+/// One version of a conflicting file: copies with identical content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictVersion {
+    /// Content hash, or None if the size alone tells this version apart.
+    #[serde(default)]
+    pub hash: Option<String>,
+
+    /// File size in bytes.
+    pub size: u64,
+
+    /// Files with this content, most recently modified first.
+    pub files: Vec<FileEntry>,
+}
+
+impl ConflictVersion {
+    /// Creates a new ConflictVersion.
+    pub fn new(hash: Option<String>, size: u64, files: Vec<FileEntry>) -> Self {
+        Self { hash, size, files }
+    }
+}
+
+/// Files sharing a name (or relative path) but not their content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameConflict {
+    /// The shared file name or relative path.
+    pub name: String,
+
+    /// Distinct versions, the most recently modified first.
+    pub versions: Vec<ConflictVersion>,
+}
+
+impl NameConflict {
+    /// Creates a new NameConflict.
+    pub fn new(name: String, versions: Vec<ConflictVersion>) -> Self {
+        Self { name, versions }
+    }
+}
+
+/// Result of content-defined chunk analysis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkAnalysis {
+    /// Pairs of files sharing at least the configured fraction of chunks,
+    /// most shared bytes first.
+    pub overlaps: Vec<ChunkOverlap>,
+
+    /// Number of files that were chunked.
+    pub files_analyzed: u64,
+
+    /// Total size of the chunked files in bytes.
+    pub total_bytes: u64,
+
+    /// Bytes remaining if every distinct chunk were stored once.
+    pub unique_bytes: u64,
+
+    /// Bytes block-level dedup would reclaim (total minus unique).
+    pub reclaimable_bytes: u64,
 }
 
 /// Progress information for an ongoing scan.
@@ -140,7 +815,7 @@ pub enum ScanPhase {
     /// Grouping files by size.
     Grouping,
 
-    /// Computing MD5 hashes for candidate files.
+    /// Computing content hashes for candidate files.
     Hashing,
 
     /// Final grouping by hash.
@@ -174,6 +849,52 @@ pub struct ScanResult {
 
     /// Duration of the scan in milliseconds.
     pub duration_ms: u64,
+
+    /// Groups of directories with identical contents.
+    #[serde(default)]
+    pub duplicate_directories: Vec<DuplicateDirectoryGroup>,
+
+    /// Directories whose contents all exist in another directory.
+    #[serde(default)]
+    pub directory_subsets: Vec<DirectorySubset>,
+
+    /// Groups of visually similar images (only when `find_similar_images` is set).
+    #[serde(default)]
+    pub similar_images: Vec<SimilarityGroup>,
+
+    /// Groups of nearly identical text files (only when `find_similar_text` is set).
+    #[serde(default)]
+    pub similar_text: Vec<SimilarityGroup>,
+
+    /// Chunk-level overlap analysis (only when `analyze_chunk_overlap` is set).
+    #[serde(default)]
+    pub chunk_analysis: Option<ChunkAnalysis>,
+
+    /// Scanned files whose content is listed in a reference manifest.
+    #[serde(default)]
+    pub manifest_matches: Vec<ManifestMatch>,
+
+    /// Algorithm used for `duplicate_groups` hashes (None in older results).
+    #[serde(default)]
+    pub hash_algorithm: Option<HashAlgorithm>,
+
+    /// Zero-byte files. They are never hashed or grouped as duplicates.
+    #[serde(default)]
+    pub empty_files: Vec<FileEntry>,
+
+    /// Directories holding nothing but empty directories (topmost only).
+    #[serde(default)]
+    pub empty_directories: Vec<String>,
+
+    /// Files sharing a name but not their content (only when
+    /// `find_name_conflicts` is set).
+    #[serde(default)]
+    pub name_conflicts: Vec<NameConflict>,
+
+    /// Content shared between each pair of roots (or top-level folders when
+    /// a single root was scanned).
+    #[serde(default)]
+    pub overlap_matrix: Option<OverlapMatrix>,
 }
 
 impl ScanResult {
@@ -196,8 +917,103 @@ impl ScanResult {
             total_wasted_space,
             errors,
             duration_ms,
+            duplicate_directories: Vec::new(),
+            directory_subsets: Vec::new(),
+            similar_images: Vec::new(),
+            similar_text: Vec::new(),
+            chunk_analysis: None,
+            manifest_matches: Vec::new(),
+            hash_algorithm: None,
+            empty_files: Vec::new(),
+            empty_directories: Vec::new(),
+            name_conflicts: Vec::new(),
+            overlap_matrix: None,
         }
     }
+
+    /// Sets the duplicate directory analysis results.
+    pub fn with_directories(
+        mut self,
+        duplicate_directories: Vec<DuplicateDirectoryGroup>,
+        directory_subsets: Vec<DirectorySubset>,
+    ) -> Self {
+        self.duplicate_directories = duplicate_directories;
+        self.directory_subsets = directory_subsets;
+        self
+    }
+
+    /// Sets the similar image groups.
+    pub fn with_similar_images(mut self, similar_images: Vec<SimilarityGroup>) -> Self {
+        self.similar_images = similar_images;
+        self
+    }
+
+    /// Sets the similar text groups.
+    pub fn with_similar_text(mut self, similar_text: Vec<SimilarityGroup>) -> Self {
+        self.similar_text = similar_text;
+        self
+    }
+
+    /// Sets the chunk-level overlap analysis.
+    pub fn with_chunk_analysis(mut self, chunk_analysis: Option<ChunkAnalysis>) -> Self {
+        self.chunk_analysis = chunk_analysis;
+        self
+    }
+
+    /// Sets the files matched against reference manifests.
+    pub fn with_manifest_matches(mut self, manifest_matches: Vec<ManifestMatch>) -> Self {
+        self.manifest_matches = manifest_matches;
+        self
+    }
+
+    /// Records the algorithm used for content hashes.
+    pub fn with_hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = Some(hash_algorithm);
+        self
+    }
+
+    /// Sets the empty files and empty directories found by the scan.
+    pub fn with_empty(
+        mut self,
+        empty_files: Vec<FileEntry>,
+        empty_directories: Vec<String>,
+    ) -> Self {
+        self.empty_files = empty_files;
+        self.empty_directories = empty_directories;
+        self
+    }
+
+    /// Sets the name conflicts.
+    pub fn with_name_conflicts(mut self, name_conflicts: Vec<NameConflict>) -> Self {
+        self.name_conflicts = name_conflicts;
+        self
+    }
+
+    /// Drops deleted files from the duplicate groups and empty files.
+    ///
+    /// Groups left with fewer than two files are removed and the totals are
+    /// recomputed.
+    pub fn remove_files(&mut self, paths: &HashSet<&str>) {
+        for group in &mut self.duplicate_groups {
+            group.files.retain(|f| !paths.contains(f.path.as_str()));
+        }
+        self.duplicate_groups.retain(|g| g.files.len() > 1);
+        self.empty_files
+            .retain(|f| !paths.contains(f.path.as_str()));
+
+        self.total_duplicates_found = self
+            .duplicate_groups
+            .iter()
+            .map(|g| g.files.len() as u64)
+            .sum();
+        self.total_wasted_space = self.duplicate_groups.iter().map(|g| g.wasted_space()).sum();
+    }
+
+    /// Sets the matrix of content shared between roots.
+    pub fn with_overlap_matrix(mut self, overlap_matrix: Option<OverlapMatrix>) -> Self {
+        self.overlap_matrix = overlap_matrix;
+        self
+    }
 }
 
 /// A non-fatal error that occurred during scanning.
@@ -221,6 +1037,296 @@ impl ScanError {
     }
 }
 
+/// Summary of a scan that was interrupted and can be resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedScan {
+    /// Checkpoint ID to pass when resuming.
+    pub id: String,
+
+    /// Root directories the scan was started with.
+    pub root_paths: Vec<String>,
+
+    /// When the scan was started (seconds since the Unix epoch).
+    pub created_at: u64,
+
+    /// When the checkpoint was last written (seconds since the Unix epoch).
+    pub updated_at: u64,
+
+    /// Number of files discovered by the directory scan.
+    pub files_discovered: u64,
+
+    /// Number of files already hashed.
+    pub hashes_completed: u64,
+}
+
+/// Wasted space attributed to one scan root.
+///
+/// A group's first file is its keeper; every other copy counts toward the
+/// root it lies under.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RootWaste {
+    /// Root directory of the scan.
+    pub root: String,
+
+    /// Bytes taken up by duplicate copies under the root.
+    pub wasted_space: u64,
+}
+
+/// A completed scan recorded in the scan history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanHistoryEntry {
+    /// ID of the scan.
+    pub id: String,
+
+    /// When the scan finished (seconds since the Unix epoch).
+    pub finished_at: u64,
+
+    /// Options the scan was run with.
+    pub options: ScanOptions,
+
+    /// Scan duration in milliseconds.
+    pub duration_ms: u64,
+
+    /// Total number of files scanned.
+    pub files_scanned: u64,
+
+    /// Number of duplicate groups found.
+    pub duplicate_groups: u64,
+
+    /// Number of duplicate files (excluding one keeper per group).
+    pub duplicates_found: u64,
+
+    /// Total wasted space in bytes.
+    pub wasted_space: u64,
+
+    /// Number of files or directories that could not be processed.
+    pub error_count: u64,
+
+    /// Wasted space per scanned root.
+    #[serde(default)]
+    pub wasted_by_root: Vec<RootWaste>,
+}
+
+/// One point of a wasted space trend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// ID of the scan the point comes from.
+    pub scan_id: String,
+
+    /// When the scan finished (seconds since the Unix epoch).
+    pub finished_at: u64,
+
+    /// Wasted space under the root at that time.
+    pub wasted_space: u64,
+}
+
+/// Wasted space over time for one scan root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RootTrend {
+    /// Root directory.
+    pub root: String,
+
+    /// Points from scans that included the root, oldest first.
+    pub points: Vec<TrendPoint>,
+}
+
+/// Summary of a saved scan session, for listing in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    /// Session ID to pass when reopening.
+    pub id: String,
+
+    /// Name given when saving, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Root directories of the scan.
+    pub root_paths: Vec<String>,
+
+    /// When the session was saved (seconds since the Unix epoch).
+    pub saved_at: u64,
+
+    /// Version of the app that saved the session.
+    pub app_version: String,
+
+    /// Number of duplicate groups in the result.
+    pub duplicate_groups: u64,
+
+    /// Total wasted space in bytes.
+    pub total_wasted_space: u64,
+}
+
+/// A reopened scan session, checked against the disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedSession {
+    /// The session's summary.
+    pub summary: SessionSummary,
+
+    /// Options the scan was run with.
+    pub options: ScanOptions,
+
+    /// The saved result, with files that changed since marked `stale`.
+    pub result: ScanResult,
+
+    /// Number of distinct files marked stale.
+    pub stale_files: u64,
+}
+
+/// How a duplicate group changed between two scans.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupChange {
+    /// The group only exists in the newer scan.
+    Added,
+
+    /// The group has more copies than before.
+    Grew,
+
+    /// The group has fewer copies than before, but still at least two.
+    Shrank,
+
+    /// The same number of copies, at different paths.
+    Moved,
+
+    /// The group no longer has duplicates in the newer scan.
+    Resolved,
+}
+
+/// A duplicate group that differs between two scans, matched by hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupDiff {
+    /// Content hash shared by the group.
+    pub hash: String,
+
+    /// Size of each file in bytes.
+    pub size: u64,
+
+    /// How the group changed.
+    pub change: GroupChange,
+
+    /// Files in the newer scan, or in the older one for resolved groups.
+    pub files: Vec<FileEntry>,
+
+    /// Paths that are only in the newer scan's group.
+    pub added_paths: Vec<String>,
+
+    /// Paths that are only in the older scan's group.
+    pub removed_paths: Vec<String>,
+
+    /// Wasted space of the group in the older scan.
+    pub wasted_space_before: u64,
+
+    /// Wasted space of the group in the newer scan.
+    pub wasted_space_after: u64,
+}
+
+/// Differences between the duplicate groups of two scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanDiff {
+    /// Groups that were added, changed or resolved, largest change in wasted
+    /// space first.
+    pub groups: Vec<GroupDiff>,
+
+    /// Number of groups with exactly the same files in both scans.
+    pub unchanged_groups: u64,
+
+    /// Total wasted space in the older scan.
+    pub wasted_space_before: u64,
+
+    /// Total wasted space in the newer scan.
+    pub wasted_space_after: u64,
+
+    /// Change in total wasted space; negative when space was freed.
+    pub wasted_space_change: i64,
+}
+
+impl ScanDiff {
+    /// Returns the groups with the given kind of change.
+    pub fn groups_with(&self, change: GroupChange) -> impl Iterator<Item = &GroupDiff> {
+        self.groups.iter().filter(move |g| g.change == change)
+    }
+}
+
+/// A file whose content no longer matches its baseline hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMismatch {
+    /// The file as it is now.
+    pub file: FileEntry,
+
+    /// Hash recorded in the baseline.
+    pub expected_hash: String,
+
+    /// Hash of the current content.
+    pub actual_hash: String,
+
+    /// Size recorded in the baseline, if any.
+    #[serde(default)]
+    pub expected_size: Option<u64>,
+
+    /// Modification time recorded in the baseline, if any.
+    #[serde(default)]
+    pub expected_modified: Option<String>,
+}
+
+impl VerifyMismatch {
+    /// Creates a new VerifyMismatch.
+    pub fn new(
+        file: FileEntry,
+        expected_hash: String,
+        actual_hash: String,
+        expected_size: Option<u64>,
+        expected_modified: Option<String>,
+    ) -> Self {
+        Self {
+            file,
+            expected_hash,
+            actual_hash,
+            expected_size,
+            expected_modified,
+        }
+    }
+}
+
+/// Result of verifying files against a baseline manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResult {
+    /// Number of baseline files that were re-hashed.
+    pub files_verified: u64,
+
+    /// Number of files whose content matches the baseline.
+    pub unchanged: u64,
+
+    /// Files whose content changed while size and modification time did
+    /// not (likely silent corruption).
+    pub corrupted: Vec<VerifyMismatch>,
+
+    /// Files whose content changed along with their size or modification time.
+    pub modified: Vec<VerifyMismatch>,
+
+    /// Baseline files that no longer exist.
+    pub missing: Vec<String>,
+
+    /// Files under the verified roots that the baseline doesn't list.
+    pub new_files: Vec<FileEntry>,
+
+    /// Files that could not be read.
+    pub errors: Vec<ScanError>,
+
+    /// Duration of the verification in milliseconds.
+    pub duration_ms: u64,
+}
+
 /// Result of a file deletion operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -277,6 +1383,45 @@ mod tests {
         assert!(opts.include_extensions.is_none());
         assert!(opts.exclude_extensions.is_none());
         assert!(!opts.follow_symlinks);
+        assert_eq!(opts.hash_algorithm, HashAlgorithm::Md5);
+        assert!(opts.max_hash_threads.is_none());
+        assert!(opts.rotational_hash_threads.is_none());
+        assert!(opts.max_bytes_per_second.is_none());
+        assert!(!opts.low_priority);
+        assert_eq!(opts.read_strategy, ReadStrategy::Direct);
+        assert!(!opts.avoid_page_cache);
+        assert!(!opts.find_similar_images);
+        assert_eq!(opts.image_hash_algorithm, ImageHashAlgorithm::Difference);
+        assert!(opts.image_similarity_distance.is_none());
+        assert!(!opts.find_similar_text);
+        assert!(opts.text_similarity_threshold.is_none());
+        assert!(!opts.match_audio_content);
+        assert!(!opts.analyze_chunk_overlap);
+        assert!(opts.chunk_overlap_threshold.is_none());
+        assert!(opts.reference_roots.is_empty());
+        assert!(opts.reference_manifests.is_empty());
+        assert!(!opts.hash_all_files);
+        assert!(!opts.find_name_conflicts);
+        assert_eq!(opts.name_conflict_key, NameConflictKey::FileName);
+        assert!(!opts.page_results);
+    }
+
+    #[test]
+    fn test_scan_roots_skips_nested_and_repeated_paths() {
+        let opts = ScanOptions {
+            root_paths: vec![
+                "/home/user/Downloads".to_string(),
+                "/data".to_string(),
+                "/data".to_string(),
+            ],
+            reference_roots: vec!["/home/user".to_string(), "/data/archive".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            opts.scan_roots(),
+            vec!["/data".to_string(), "/home/user".to_string()]
+        );
     }
 
     #[test]
@@ -287,6 +1432,27 @@ mod tests {
             include_extensions: Some(vec!["jpg".to_string(), "png".to_string()]),
             exclude_extensions: None,
             follow_symlinks: true,
+            hash_algorithm: HashAlgorithm::Blake3,
+            max_hash_threads: Some(2),
+            rotational_hash_threads: Some(1),
+            max_bytes_per_second: Some(50 * 1024 * 1024),
+            low_priority: true,
+            read_strategy: ReadStrategy::Mmap,
+            avoid_page_cache: true,
+            find_similar_images: true,
+            image_hash_algorithm: ImageHashAlgorithm::Perceptual,
+            image_similarity_distance: Some(6),
+            find_similar_text: true,
+            text_similarity_threshold: Some(0.8),
+            match_audio_content: true,
+            analyze_chunk_overlap: true,
+            chunk_overlap_threshold: Some(0.75),
+            reference_roots: vec!["/archive".to_string()],
+            reference_manifests: vec!["/archive/SHA256SUMS".to_string()],
+            hash_all_files: true,
+            find_name_conflicts: true,
+            name_conflict_key: NameConflictKey::RelativePath,
+            page_results: true,
         };
 
         let json = serde_json::to_string(&opts).unwrap();
@@ -294,11 +1460,57 @@ mod tests {
         assert!(json.contains("minFileSize"));
         assert!(json.contains("includeExtensions"));
         assert!(json.contains("followSymlinks"));
+        assert!(json.contains("hashAlgorithm"));
+        assert!(json.contains("maxHashThreads"));
+        assert!(json.contains("rotationalHashThreads"));
+        assert!(json.contains("maxBytesPerSecond"));
+        assert!(json.contains("lowPriority"));
 
         let deserialized: ScanOptions = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.root_paths, opts.root_paths);
         assert_eq!(deserialized.min_file_size, opts.min_file_size);
         assert_eq!(deserialized.follow_symlinks, opts.follow_symlinks);
+        assert_eq!(deserialized.hash_algorithm, HashAlgorithm::Blake3);
+        assert_eq!(deserialized.max_hash_threads, opts.max_hash_threads);
+        assert_eq!(deserialized.max_bytes_per_second, opts.max_bytes_per_second);
+        assert_eq!(deserialized.low_priority, opts.low_priority);
+        assert_eq!(deserialized.read_strategy, ReadStrategy::Mmap);
+        assert!(deserialized.avoid_page_cache);
+        assert!(deserialized.find_similar_images);
+        assert_eq!(
+            deserialized.image_hash_algorithm,
+            ImageHashAlgorithm::Perceptual
+        );
+        assert_eq!(deserialized.image_similarity_distance, Some(6));
+        assert!(deserialized.find_similar_text);
+        assert_eq!(deserialized.text_similarity_threshold, Some(0.8));
+        assert!(deserialized.match_audio_content);
+        assert!(deserialized.analyze_chunk_overlap);
+        assert_eq!(deserialized.chunk_overlap_threshold, Some(0.75));
+        assert_eq!(deserialized.reference_roots, vec!["/archive".to_string()]);
+        assert_eq!(
+            deserialized.reference_manifests,
+            vec!["/archive/SHA256SUMS".to_string()]
+        );
+        assert!(deserialized.hash_all_files);
+        assert!(deserialized.find_name_conflicts);
+        assert_eq!(
+            deserialized.name_conflict_key,
+            NameConflictKey::RelativePath
+        );
+        assert!(deserialized.page_results);
+    }
+
+    #[test]
+    fn test_read_strategy_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ReadStrategy::Direct).unwrap(),
+            "\"direct\""
+        );
+        assert_eq!(
+            serde_json::from_str::<ReadStrategy>("\"mmap\"").unwrap(),
+            ReadStrategy::Mmap
+        );
     }
 
     #[test]
@@ -368,6 +1580,26 @@ mod tests {
         assert_eq!(group.wasted_space(), 0);
     }
 
+    #[test]
+    fn test_duplicate_group_summary() {
+        let group = DuplicateGroup::new(
+            "abc123".to_string(),
+            1000,
+            vec![
+                FileEntry::new("/file1.txt".to_string(), 1000, None),
+                FileEntry::new("/file2.txt".to_string(), 1000, None),
+            ],
+        );
+
+        let summary = group.summary();
+        assert_eq!(summary.hash, "abc123");
+        assert_eq!(summary.file_count, 2);
+        assert_eq!(summary.wasted_space, 1000);
+        assert!(serde_json::to_string(&summary)
+            .unwrap()
+            .contains("\"fileCount\":2"));
+    }
+
     #[test]
     fn test_duplicate_group_empty_no_waste() {
         let group = DuplicateGroup::new("abc123".to_string(), 1000, vec![]);
@@ -440,6 +1672,31 @@ mod tests {
         assert_eq!(result.duration_ms, 1000);
     }
 
+    #[test]
+    fn test_duplicate_directory_group_wasted_space() {
+        let group = DuplicateDirectoryGroup::new(
+            "merkle".to_string(),
+            300,
+            2,
+            vec!["/a".to_string(), "/b".to_string(), "/c".to_string()],
+        );
+        assert_eq!(group.wasted_space(), 600);
+
+        let result = ScanResult::new(vec![], 6, vec![], 10).with_directories(
+            vec![group],
+            vec![DirectorySubset::new(
+                "/d".to_string(),
+                "/a".to_string(),
+                100,
+                1,
+            )],
+        );
+        assert_eq!(result.duplicate_directories.len(), 1);
+        assert_eq!(result.directory_subsets[0].superset, "/a");
+        // Directory duplicates don't change the file-level totals
+        assert_eq!(result.total_wasted_space, 0);
+    }
+
     #[test]
     fn test_scan_error_creation() {
         let error = ScanError::new("/path/to/file", "Permission denied");
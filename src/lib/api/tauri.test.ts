@@ -3,11 +3,32 @@ import {
   startScan,
   cancelScan,
   deleteFiles,
+  findEmptyDirectories,
+  deleteEmptyDirectories,
   selectFolders,
+  listInterruptedScans,
+  resumeScan,
+  discardInterruptedScan,
+  saveSession,
+  listSessions,
+  loadSession,
+  diffSessions,
+  deleteSession,
+  listScanHistory,
+  scanHistoryTrends,
+  clearScanHistory,
+  exportManifest,
+  exportReport,
+  folderBreakdown,
+  queryGroups,
+  importFdupes,
+  verifyBaseline,
   onScanProgress,
   onScanFinished,
   onScanError,
   onScanCancelled,
+  onDuplicateGroupFound,
+  onDuplicateGroupSummary,
   setupScanListeners,
 } from './tauri';
 
@@ -65,6 +86,32 @@ describe('Tauri API (mock mode)', () => {
     });
   });
 
+  describe('findEmptyDirectories', () => {
+    it('should return empty array in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await findEmptyDirectories(['/test/file.txt']);
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] findEmptyDirectories called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('deleteEmptyDirectories', () => {
+    it('should return empty result in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await deleteEmptyDirectories(['/test/empty'], true);
+
+      expect(result).toEqual({ deleted: [], failed: [] });
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] deleteEmptyDirectories called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
   describe('selectFolders', () => {
     it('should return mock folder in mock mode', async () => {
       const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
@@ -78,6 +125,262 @@ describe('Tauri API (mock mode)', () => {
     });
   });
 
+  describe('listInterruptedScans', () => {
+    it('should return empty list in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await listInterruptedScans();
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] listInterruptedScans called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('resumeScan', () => {
+    it('should return empty result in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await resumeScan('scan_1');
+
+      expect(result.duplicateGroups).toEqual([]);
+      expect(result.totalFilesScanned).toBe(0);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] resumeScan called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('discardInterruptedScan', () => {
+    it('should return undefined in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await discardInterruptedScan('scan_1');
+
+      expect(result).toBeUndefined();
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] discardInterruptedScan called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('saveSession', () => {
+    it('should return a summary in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await saveSession(
+        {
+          duplicateGroups: [],
+          totalFilesScanned: 10,
+          totalDuplicatesFound: 0,
+          totalWastedSpace: 0,
+          errors: [],
+          durationMs: 5,
+        },
+        { rootPaths: ['/photos'], followSymlinks: false },
+        'Photos'
+      );
+
+      expect(result.name).toBe('Photos');
+      expect(result.rootPaths).toEqual(['/photos']);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] saveSession called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('listSessions', () => {
+    it('should return empty list in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await listSessions();
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] listSessions called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('loadSession', () => {
+    it('should return an empty session in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await loadSession('session_1');
+
+      expect(result.summary.id).toBe('session_1');
+      expect(result.result.duplicateGroups).toEqual([]);
+      expect(result.staleFiles).toBe(0);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] loadSession called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('diffSessions', () => {
+    it('should return an empty diff in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await diffSessions('session_1', 'session_2');
+
+      expect(result.groups).toEqual([]);
+      expect(result.wastedSpaceChange).toBe(0);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] diffSessions called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('deleteSession', () => {
+    it('should return undefined in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await deleteSession('session_1');
+
+      expect(result).toBeUndefined();
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] deleteSession called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('listScanHistory', () => {
+    it('should return empty list in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await listScanHistory();
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] listScanHistory called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('scanHistoryTrends', () => {
+    it('should return empty list in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await scanHistoryTrends();
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] scanHistoryTrends called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('clearScanHistory', () => {
+    it('should return undefined in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await clearScanHistory();
+
+      expect(result).toBeUndefined();
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] clearScanHistory called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('exportManifest', () => {
+    it('should return zero files in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await exportManifest('/tmp/SHA256SUMS', 'checksums');
+
+      expect(result).toBe(0);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] exportManifest called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('queryGroups', () => {
+    it('should return an empty page in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await queryGroups({ offset: 200, limit: 100, sortBy: 'size' });
+
+      expect(result.groups).toEqual([]);
+      expect(result.offset).toBe(200);
+      expect(result.totalGroups).toBe(0);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] queryGroups called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('folderBreakdown', () => {
+    it('should return empty list in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await folderBreakdown(
+        {
+          duplicateGroups: [],
+          totalFilesScanned: 0,
+          totalDuplicatesFound: 0,
+          totalWastedSpace: 0,
+          errors: [],
+          durationMs: 0,
+        },
+        ['/data']
+      );
+
+      expect(result).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] folderBreakdown called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('exportReport', () => {
+    it('should do nothing in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      await exportReport('/tmp/report.html', 'html', {
+        duplicateGroups: [],
+        totalFilesScanned: 0,
+        totalDuplicatesFound: 0,
+        totalWastedSpace: 0,
+        errors: [],
+        durationMs: 0,
+      });
+
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] exportReport called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('importFdupes', () => {
+    it('should return empty result in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await importFdupes('/tmp/fdupes.txt', { rootPaths: [] });
+
+      expect(result.duplicateGroups).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] importFdupes called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
+  describe('verifyBaseline', () => {
+    it('should return empty result in mock mode', async () => {
+      const consoleSpy = vi.spyOn(console, 'log').mockImplementation(() => {});
+
+      const result = await verifyBaseline('/tmp/baseline.json', { rootPaths: ['/data'] });
+
+      expect(result.filesVerified).toBe(0);
+      expect(result.corrupted).toEqual([]);
+      expect(consoleSpy).toHaveBeenCalledWith('[Mock] verifyBaseline called');
+
+      consoleSpy.mockRestore();
+    });
+  });
+
   describe('onScanProgress', () => {
     it('should return noop unlisten function in mock mode', async () => {
       const callback = vi.fn();
@@ -124,6 +427,28 @@ describe('Tauri API (mock mode)', () => {
     });
   });
 
+  describe('onDuplicateGroupFound', () => {
+    it('should return noop unlisten function in mock mode', async () => {
+      const callback = vi.fn();
+      const unlisten = await onDuplicateGroupFound(callback);
+
+      expect(typeof unlisten).toBe('function');
+      unlisten();
+      expect(callback).not.toHaveBeenCalled();
+    });
+  });
+
+  describe('onDuplicateGroupSummary', () => {
+    it('should return noop unlisten function in mock mode', async () => {
+      const callback = vi.fn();
+      const unlisten = await onDuplicateGroupSummary(callback);
+
+      expect(typeof unlisten).toBe('function');
+      unlisten();
+      expect(callback).not.toHaveBeenCalled();
+    });
+  });
+
   describe('setupScanListeners', () => {
     it('should return combined unlisten function', async () => {
       const handlers = {
@@ -131,6 +456,8 @@ describe('Tauri API (mock mode)', () => {
         onFinished: vi.fn(),
         onError: vi.fn(),
         onCancelled: vi.fn(),
+        onGroupFound: vi.fn(),
+        onGroupSummary: vi.fn(),
       };
 
       const unlisten = await setupScanListeners(handlers);
@@ -8,6 +8,20 @@ import type {
   ScanResult,
   ScanProgress,
   DeleteResult,
+  DuplicateGroup,
+  FolderSummary,
+  GroupPage,
+  GroupQuery,
+  GroupSummary,
+  InterruptedScan,
+  LoadedSession,
+  ManifestFormat,
+  ReportFormat,
+  RootTrend,
+  ScanDiff,
+  ScanHistoryEntry,
+  SessionSummary,
+  VerifyResult,
 } from '../types';
 
 type UnlistenFn = () => void;
@@ -18,6 +32,8 @@ const EVENTS = {
   SCAN_FINISHED: 'scan_finished',
   SCAN_ERROR: 'scan_error',
   SCAN_CANCELLED: 'scan_cancelled',
+  DUPLICATE_GROUP_FOUND: 'duplicate_group_found',
+  DUPLICATE_GROUP_SUMMARY: 'duplicate_group_summary',
 } as const;
 
 // Check if we're running in Tauri v2 context
@@ -69,6 +85,192 @@ export async function cancelScan(): Promise<void> {
   return invoke('cancel_scan');
 }
 
+/**
+ * Lists scans that were interrupted before completion and can be resumed.
+ */
+export async function listInterruptedScans(): Promise<InterruptedScan[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] listInterruptedScans called');
+    return [];
+  }
+  return invoke<InterruptedScan[]>('list_interrupted_scans');
+}
+
+/**
+ * Resumes an interrupted scan from its checkpoint.
+ * @param checkpointId - ID of the interrupted scan to resume
+ */
+export async function resumeScan(checkpointId: string): Promise<ScanResult> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] resumeScan called');
+    return {
+      duplicateGroups: [],
+      totalFilesScanned: 0,
+      totalDuplicatesFound: 0,
+      totalWastedSpace: 0,
+      errors: [],
+      durationMs: 0,
+    };
+  }
+  return invoke<ScanResult>('resume_scan', { checkpointId });
+}
+
+/**
+ * Deletes the checkpoint of an interrupted scan without resuming it.
+ * @param checkpointId - ID of the interrupted scan to discard
+ */
+export async function discardInterruptedScan(checkpointId: string): Promise<void> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] discardInterruptedScan called');
+    return;
+  }
+  return invoke('discard_interrupted_scan', { checkpointId });
+}
+
+/**
+ * Saves a finished scan so it can be reopened without rescanning.
+ * @param result - The scan result to save, or null for the last result kept in the backend
+ * @param options - Options the scan was run with
+ * @param name - Optional name shown in the session list
+ */
+export async function saveSession(
+  result: ScanResult | null,
+  options: ScanOptions,
+  name?: string
+): Promise<SessionSummary> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] saveSession called');
+    return {
+      id: 'session_mock',
+      name: name ?? null,
+      rootPaths: options.rootPaths,
+      savedAt: Math.floor(Date.now() / 1000),
+      appVersion: '0.0.0',
+      duplicateGroups: result?.duplicateGroups.length ?? 0,
+      totalWastedSpace: result?.totalWastedSpace ?? 0,
+    };
+  }
+  return invoke<SessionSummary>('save_session', { result, options, name: name ?? null });
+}
+
+/**
+ * Lists saved scan sessions, most recently saved first.
+ */
+export async function listSessions(): Promise<SessionSummary[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] listSessions called');
+    return [];
+  }
+  return invoke<SessionSummary[]>('list_sessions');
+}
+
+/**
+ * Reopens a saved session. Files that are missing or changed since the scan
+ * are marked stale.
+ * @param sessionId - ID of the session to reopen
+ */
+export async function loadSession(sessionId: string): Promise<LoadedSession> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] loadSession called');
+    return {
+      summary: {
+        id: sessionId,
+        rootPaths: [],
+        savedAt: 0,
+        appVersion: '0.0.0',
+        duplicateGroups: 0,
+        totalWastedSpace: 0,
+      },
+      options: { rootPaths: [], followSymlinks: false },
+      result: {
+        duplicateGroups: [],
+        totalFilesScanned: 0,
+        totalDuplicatesFound: 0,
+        totalWastedSpace: 0,
+        errors: [],
+        durationMs: 0,
+      },
+      staleFiles: 0,
+    };
+  }
+  return invoke<LoadedSession>('load_session', { sessionId });
+}
+
+/**
+ * Compares the duplicate groups of two saved sessions.
+ * @param baseSessionId - The older session
+ * @param sessionId - The newer session
+ */
+export async function diffSessions(baseSessionId: string, sessionId: string): Promise<ScanDiff> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] diffSessions called');
+    return {
+      groups: [],
+      unchangedGroups: 0,
+      wastedSpaceBefore: 0,
+      wastedSpaceAfter: 0,
+      wastedSpaceChange: 0,
+    };
+  }
+  return invoke<ScanDiff>('diff_sessions', { baseSessionId, sessionId });
+}
+
+/**
+ * Lists completed scans from the scan history, most recent first.
+ */
+export async function listScanHistory(): Promise<ScanHistoryEntry[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] listScanHistory called');
+    return [];
+  }
+  return invoke<ScanHistoryEntry[]>('list_scan_history');
+}
+
+/**
+ * Returns the wasted space over time of every root in the scan history.
+ */
+export async function scanHistoryTrends(): Promise<RootTrend[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] scanHistoryTrends called');
+    return [];
+  }
+  return invoke<RootTrend[]>('scan_history_trends');
+}
+
+/**
+ * Removes all scans from the scan history.
+ */
+export async function clearScanHistory(): Promise<void> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] clearScanHistory called');
+    return;
+  }
+  return invoke('clear_scan_history');
+}
+
+/**
+ * Deletes a saved session.
+ * @param sessionId - ID of the session to delete
+ */
+export async function deleteSession(sessionId: string): Promise<void> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] deleteSession called');
+    return;
+  }
+  return invoke('delete_session', { sessionId });
+}
+
 /**
  * Deletes the specified files.
  * @param filePaths - List of file paths to delete
@@ -86,6 +288,154 @@ export async function deleteFiles(
   return invoke<DeleteResult>('delete_files', { filePaths, useTrash });
 }
 
+/**
+ * Finds directories that would be left empty by deleting the given files,
+ * based on the last completed scan.
+ * @param plannedDeletions - Files about to be (or just) deleted
+ * @returns The topmost empty directories
+ */
+export async function findEmptyDirectories(plannedDeletions: string[]): Promise<string[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] findEmptyDirectories called');
+    return [];
+  }
+  return invoke<string[]>('find_empty_directories', { plannedDeletions });
+}
+
+/**
+ * Removes directories that hold nothing but empty directories.
+ * @param dirPaths - Directories to remove
+ * @param useTrash - If true, move to trash; otherwise remove permanently
+ */
+export async function deleteEmptyDirectories(
+  dirPaths: string[],
+  useTrash: boolean
+): Promise<DeleteResult> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] deleteEmptyDirectories called');
+    return { deleted: [], failed: [] };
+  }
+  return invoke<DeleteResult>('delete_empty_directories', { dirPaths, useTrash });
+}
+
+/**
+ * Exports the hashes computed by the last completed scan as a manifest.
+ * @param path - Destination file
+ * @param format - Checksum list (md5sum/sha256sum-compatible) or JSON
+ * @returns The number of files written
+ */
+export async function exportManifest(path: string, format: ManifestFormat): Promise<number> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] exportManifest called');
+    return 0;
+  }
+  return invoke<number>('export_manifest', { path, format });
+}
+
+/**
+ * Returns one page of the duplicate groups of the last scan, import or
+ * reopened session, filtered and sorted in the backend.
+ * @param query - Filters, sort key and order, and page bounds
+ */
+export async function queryGroups(query: GroupQuery): Promise<GroupPage> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] queryGroups called');
+    return { groups: [], offset: query.offset ?? 0, totalGroups: 0, totalWastedSpace: 0 };
+  }
+  return invoke<GroupPage>('query_groups', { query });
+}
+
+/**
+ * Adds up the duplicate files of a result per folder, at every level below
+ * each scan root.
+ * @param result - The scan result to summarize, or null for the last result kept in the backend
+ * @param rootPaths - Scan roots; each gets its own tree
+ */
+export async function folderBreakdown(
+  result: ScanResult | null,
+  rootPaths: string[]
+): Promise<FolderSummary[]> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] folderBreakdown called');
+    return [];
+  }
+  return invoke<FolderSummary[]>('folder_breakdown', { result, rootPaths });
+}
+
+/**
+ * Writes a scan result as a report to share with others or other tools.
+ * @param path - Destination file
+ * @param format - Versioned JSON, flat CSV (one row per file), static HTML or fdupes text
+ * @param result - The scan result to export, or null for the last result kept in the backend
+ */
+export async function exportReport(
+  path: string,
+  format: ReportFormat,
+  result: ScanResult | null
+): Promise<void> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] exportReport called');
+    return;
+  }
+  return invoke<void>('export_report', { path, format, result });
+}
+
+/**
+ * Imports duplicate groups from fdupes/jdupes output. Listed files are
+ * re-hashed and only files that still match are kept; the result is also
+ * delivered through the scan finished event.
+ * @param path - File holding the fdupes/jdupes output
+ * @param options - Hash algorithm and hashing limits
+ */
+export async function importFdupes(path: string, options: ScanOptions): Promise<ScanResult> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] importFdupes called');
+    return {
+      duplicateGroups: [],
+      totalFilesScanned: 0,
+      totalDuplicatesFound: 0,
+      totalWastedSpace: 0,
+      errors: [],
+      durationMs: 0,
+    };
+  }
+  return invoke<ScanResult>('import_fdupes', { path, options });
+}
+
+/**
+ * Verifies files against a baseline manifest, reporting corrupted,
+ * modified, missing and new files.
+ * @param baselinePath - JSON manifest or checksum list to verify against
+ * @param options - Roots searched for new files, plus filters and hashing limits
+ */
+export async function verifyBaseline(
+  baselinePath: string,
+  options: ScanOptions
+): Promise<VerifyResult> {
+  const invoke = await getTauriInvoke();
+  if (!invoke) {
+    console.log('[Mock] verifyBaseline called');
+    return {
+      filesVerified: 0,
+      unchanged: 0,
+      corrupted: [],
+      modified: [],
+      missing: [],
+      newFiles: [],
+      errors: [],
+      durationMs: 0,
+    };
+  }
+  return invoke<VerifyResult>('verify_baseline', { baselinePath, options });
+}
+
 /**
  * Opens a folder selection dialog and returns the selected paths.
  */
@@ -162,6 +512,43 @@ export async function onScanCancelled(
   });
 }
 
+/**
+ * Listens for duplicate groups confirmed while the scan is still running.
+ * Each group arrives as soon as all files of its size are hashed; the final
+ * result from onScanFinished supersedes them. Scans that page their results
+ * send onDuplicateGroupSummary events instead.
+ * @returns A function to unsubscribe from the event
+ */
+export async function onDuplicateGroupFound(
+  callback: (group: DuplicateGroup) => void
+): Promise<UnlistenFn> {
+  const listen = await getTauriListen();
+  if (!listen) {
+    return () => {};
+  }
+  return listen<DuplicateGroup>(EVENTS.DUPLICATE_GROUP_FOUND, (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listens for summaries of duplicate groups confirmed while a scan with
+ * pageResults is still running. The groups themselves are fetched with
+ * queryGroups once the scan has finished.
+ * @returns A function to unsubscribe from the event
+ */
+export async function onDuplicateGroupSummary(
+  callback: (summary: GroupSummary) => void
+): Promise<UnlistenFn> {
+  const listen = await getTauriListen();
+  if (!listen) {
+    return () => {};
+  }
+  return listen<GroupSummary>(EVENTS.DUPLICATE_GROUP_SUMMARY, (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Sets up all scan event listeners at once.
  * @returns A function to unsubscribe from all events
@@ -171,6 +558,8 @@ export async function setupScanListeners(handlers: {
   onFinished?: (result: ScanResult) => void;
   onError?: (error: string) => void;
   onCancelled?: () => void;
+  onGroupFound?: (group: DuplicateGroup) => void;
+  onGroupSummary?: (summary: GroupSummary) => void;
 }): Promise<UnlistenFn> {
   const unlisteners: UnlistenFn[] = [];
 
@@ -186,6 +575,12 @@ export async function setupScanListeners(handlers: {
   if (handlers.onCancelled) {
     unlisteners.push(await onScanCancelled(handlers.onCancelled));
   }
+  if (handlers.onGroupFound) {
+    unlisteners.push(await onDuplicateGroupFound(handlers.onGroupFound));
+  }
+  if (handlers.onGroupSummary) {
+    unlisteners.push(await onDuplicateGroupSummary(handlers.onGroupSummary));
+  }
 
   // Return a combined unlisten function
   return () => {
@@ -147,6 +147,7 @@ describe('ScanControls', () => {
       onFinished: expect.any(Function),
       onError: expect.any(Function),
       onCancelled: expect.any(Function),
+      onGroupFound: expect.any(Function),
     });
   });
 
@@ -10,6 +10,7 @@ import {
   totalDuplicateFiles,
   isScanning,
   hasResults,
+  hasStreamedGroups,
   hasErrors,
 } from './scanStore';
 import type { ScanResult, DuplicateGroup } from '../types';
@@ -118,6 +119,37 @@ describe('scanStore', () => {
     });
   });
 
+  describe('addGroup', () => {
+    const group = (hash: string): DuplicateGroup => ({
+      hash,
+      size: 100,
+      files: [
+        { path: `/${hash}/a.txt`, size: 100 },
+        { path: `/${hash}/b.txt`, size: 100 },
+      ],
+    });
+
+    it('appends streamed groups while scanning', () => {
+      scanStore.startScan();
+      scanStore.addGroup(group('abc'));
+      scanStore.addGroup(group('def'));
+      scanStore.addGroup(group('ghi'));
+
+      const hashes = get(scanStore).duplicateGroups.map((g) => g.hash);
+      expect(hashes).toEqual(['abc', 'def', 'ghi']);
+      expect(get(hasStreamedGroups)).toBe(true);
+    });
+
+    it('ignores groups that arrive after the scan ended', () => {
+      scanStore.startScan();
+      scanStore.cancelScan();
+      scanStore.addGroup(group('abc'));
+
+      expect(get(scanStore).duplicateGroups).toEqual([]);
+      expect(get(hasStreamedGroups)).toBe(false);
+    });
+  });
+
   describe('cancelScan', () => {
     it('sets status to cancelled', () => {
       scanStore.startScan();
@@ -184,6 +216,45 @@ describe('scanStore', () => {
       expect(selected.has('/c.txt')).toBe(true);
     });
 
+    it('never selects reference files', () => {
+      const group: DuplicateGroup = {
+        hash: 'abc',
+        size: 100,
+        files: [
+          { path: '/archive/a.txt', size: 100, isReference: true },
+          { path: '/archive/b.txt', size: 100, isReference: true },
+          { path: '/downloads/a.txt', size: 100 },
+        ],
+      };
+
+      scanStore.selectAllButOne(group);
+
+      const selected = get(scanStore).selectedForDeletion;
+      expect(selected.has('/archive/a.txt')).toBe(false);
+      expect(selected.has('/archive/b.txt')).toBe(false);
+      expect(selected.has('/downloads/a.txt')).toBe(true);
+    });
+
+    it('never selects stale files', () => {
+      const group: DuplicateGroup = {
+        hash: 'abc',
+        size: 100,
+        files: [
+          { path: '/a.txt', size: 100 },
+          { path: '/b.txt', size: 100, stale: 'changed' },
+          { path: '/c.txt', size: 100, stale: 'missing' },
+          { path: '/d.txt', size: 100, stale: null },
+        ],
+      };
+
+      scanStore.selectAllButOne(group);
+
+      const selected = get(scanStore).selectedForDeletion;
+      expect(selected.has('/b.txt')).toBe(false);
+      expect(selected.has('/c.txt')).toBe(false);
+      expect(selected.has('/d.txt')).toBe(true);
+    });
+
     it('clears group selection', () => {
       const group: DuplicateGroup = {
         hash: 'abc',
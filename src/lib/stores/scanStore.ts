@@ -99,6 +99,17 @@ function createScanStore() {
         errorMessage: null,
       })),
 
+    /**
+     * Appends a group confirmed while the scan is still running. Groups that
+     * arrive after the scan ended are ignored; the final result replaces them.
+     */
+    addGroup: (group: DuplicateGroup) =>
+      update((state) =>
+        state.status === 'scanning'
+          ? { ...state, duplicateGroups: [...state.duplicateGroups, group] }
+          : state
+      ),
+
     /** Sets the scan as finished with results. */
     finishScan: (result: ScanResult) =>
       update((state) => ({
@@ -146,12 +157,15 @@ function createScanStore() {
         return { ...state, selectedForDeletion: newSet };
       }),
 
-    /** Selects all files in a group except one. */
+    /** Selects all files in a group except one, never selecting reference or stale files. */
     selectAllButOne: (group: DuplicateGroup) =>
       update((state) => {
         const newSet = new Set(state.selectedForDeletion);
         // Add all files except the first one
-        group.files.slice(1).forEach((file) => newSet.add(file.path));
+        group.files
+          .slice(1)
+          .filter((file) => !file.isReference && !file.stale)
+          .forEach((file) => newSet.add(file.path));
         return { ...state, selectedForDeletion: newSet };
       }),
 
@@ -176,7 +190,10 @@ function createScanStore() {
         const newSet = new Set(state.selectedForDeletion);
         // For each group, select all files except the first one
         state.duplicateGroups.forEach((group) => {
-          group.files.slice(1).forEach((file) => newSet.add(file.path));
+          group.files
+            .slice(1)
+            .filter((file) => !file.isReference && !file.stale)
+            .forEach((file) => newSet.add(file.path));
         });
         return { ...state, selectedForDeletion: newSet };
       }),
@@ -281,6 +298,12 @@ export const hasResults = derived(
   ($store) => $store.status === 'finished' && $store.duplicateGroups.length > 0
 );
 
+/** Whether groups found so far can be shown while the scan is still running. */
+export const hasStreamedGroups = derived(
+  scanStore,
+  ($store) => $store.status === 'scanning' && $store.duplicateGroups.length > 0
+);
+
 /** Whether there are errors to display. */
 export const hasErrors = derived(
   scanStore,
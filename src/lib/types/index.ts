@@ -19,8 +19,92 @@ export interface ScanOptions {
 
   /** Whether to follow symbolic links. */
   followSymlinks: boolean;
+
+  /** Algorithm used to hash file contents. */
+  hashAlgorithm?: HashAlgorithm;
+
+  /** Maximum number of threads used for hashing. */
+  maxHashThreads?: number;
+
+  /** Maximum number of concurrent hashing threads per rotational disk. */
+  rotationalHashThreads?: number;
+
+  /** Cap on combined read throughput while hashing, in bytes per second. */
+  maxBytesPerSecond?: number;
+
+  /** Run hashing threads at reduced CPU and I/O priority (Linux only). */
+  lowPriority?: boolean;
+
+  /** How file contents are read while hashing. */
+  readStrategy?: ReadStrategy;
+
+  /** Hint the OS to drop hashed files from the page cache (Linux only). */
+  avoidPageCache?: boolean;
+
+  /** Also look for visually similar images (resized, re-encoded, stripped EXIF). */
+  findSimilarImages?: boolean;
+
+  /** Perceptual hash used to compare images. */
+  imageHashAlgorithm?: ImageHashAlgorithm;
+
+  /** Maximum Hamming distance (out of 64 bits) between similar images. Defaults to 10. */
+  imageSimilarityDistance?: number | null;
+
+  /** Also look for nearly identical text files (by extension or content sniffing). */
+  findSimilarText?: boolean;
+
+  /** Minimum similarity (0-1) for text files to be grouped. Defaults to 0.9. */
+  textSimilarityThreshold?: number | null;
+
+  /** Compare MP3/FLAC/Ogg/M4A files by audio payload only, ignoring tags. */
+  matchAudioContent?: boolean;
+
+  /** Split large files into content-defined chunks to find partial overlap. */
+  analyzeChunkOverlap?: boolean;
+
+  /** Minimum shared fraction (0.0-1.0) of the smaller file (default 0.5). */
+  chunkOverlapThreshold?: number | null;
+
+  /** Roots holding reference copies; only groups mixing them with other files are reported. */
+  referenceRoots?: string[];
+
+  /** md5sum/sha256sum/b3sum manifests or exported JSON reports used as a virtual reference set. */
+  referenceManifests?: string[];
+
+  /** Hash every file, not just size collisions, so a complete manifest can be exported. */
+  hashAllFiles?: boolean;
+
+  /** Also report files that share a name but differ in content. */
+  findNameConflicts?: boolean;
+
+  /** What counts as the same name when looking for conflicts (default: 'fileName'). */
+  nameConflictKey?: NameConflictKey;
+
+  /**
+   * Leave duplicate groups out of the returned result; the full result is
+   * kept in the backend and fetched a page at a time with queryGroups.
+   */
+  pageResults?: boolean;
 }
 
+/** Content hash algorithms. */
+export type HashAlgorithm = 'md5' | 'sha256' | 'blake3';
+
+/** How files are matched up when looking for name conflicts. */
+export type NameConflictKey = 'fileName' | 'relativePath';
+
+/** Output formats for exported hash manifests. */
+export type ManifestFormat = 'checksums' | 'json';
+
+/** Output formats for exported scan reports. */
+export type ReportFormat = 'json' | 'csv' | 'html' | 'fdupes';
+
+/** Strategies for reading file contents while hashing. */
+export type ReadStrategy = 'buffered' | 'direct' | 'mmap';
+
+/** Perceptual hash algorithms for comparing images. */
+export type ImageHashAlgorithm = 'average' | 'difference' | 'perceptual';
+
 /** Represents a single file entry with its metadata. */
 export interface FileEntry {
   /** Absolute path to the file. */
@@ -31,11 +115,24 @@ export interface FileEntry {
 
   /** Last modification time as ISO 8601 string. */
   modified?: string;
+
+  /** True if the file lies under a reference root and must not be deleted. */
+  isReference?: boolean;
+
+  /** Set when a reopened session finds the file no longer matches the disk. */
+  stale?: StaleReason | null;
 }
 
+/**
+ * Why a file from a saved session no longer matches the disk:
+ * - missing: the file no longer exists or can't be accessed
+ * - changed: its size or modification time changed since the scan
+ */
+export type StaleReason = 'missing' | 'changed';
+
 /** A group of duplicate files sharing the same content hash. */
 export interface DuplicateGroup {
-  /** MD5 hash of the file content. */
+  /** Hash of the file content (algorithm per ScanOptions.hashAlgorithm). */
   hash: string;
 
   /** Size of each file in bytes. */
@@ -43,6 +140,200 @@ export interface DuplicateGroup {
 
   /** List of files with this hash. */
   files: FileEntry[];
+
+  /** True if the files share the same audio content but have different tags. */
+  metadataDiffers?: boolean;
+}
+
+/** A duplicate group without its files, sent while a paged scan is running. */
+export interface GroupSummary {
+  /** Hash of the file content. */
+  hash: string;
+
+  /** Size of each file in bytes. */
+  size: number;
+
+  /** Number of files in the group. */
+  fileCount: number;
+
+  /** Space that could be recovered by keeping only one copy. */
+  wastedSpace: number;
+
+  /** True if the files share the same audio content but have different tags. */
+  metadataDiffers: boolean;
+}
+
+/**
+ * Duplicate files under one folder, including its subfolders. A group's
+ * first file is its keeper: it counts toward the duplicate files and bytes
+ * of its folder, but not toward the wasted space.
+ */
+export interface FolderSummary {
+  /** Absolute path of the folder. */
+  path: string;
+
+  /** Folder name; the full path for scan roots. */
+  name: string;
+
+  /** Number of files under the folder that belong to a duplicate group. */
+  duplicateFiles: number;
+
+  /** Total size of those files in bytes. */
+  duplicateBytes: number;
+
+  /** Bytes that deleting all but the keepers would free. */
+  wastedSpace: number;
+
+  /** Subfolders that hold duplicates, most wasted space first. */
+  children: FolderSummary[];
+}
+
+/** What duplicate groups are sorted by when queried. */
+export type GroupSortKey = 'wastedSpace' | 'size' | 'count' | 'path' | 'modified';
+
+/** Sort direction. */
+export type SortOrder = 'ascending' | 'descending';
+
+/**
+ * A page request over the duplicate groups of the last result. File filters
+ * (path and extension) match a group when any one of its files matches all
+ * of them.
+ */
+export interface GroupQuery {
+  /** Number of matching groups to skip. */
+  offset?: number;
+
+  /** Maximum number of groups to return (default: 100). */
+  limit?: number | null;
+
+  /** Sort key (default: 'wastedSpace'). */
+  sortBy?: GroupSortKey;
+
+  /** Sort direction (default: ascending for paths, descending otherwise). */
+  order?: SortOrder | null;
+
+  /** Case-insensitive substring a file path must contain. */
+  pathContains?: string | null;
+
+  /** Wildcard pattern a full path must match (`*` any run of characters, `?` one character). */
+  pathGlob?: string | null;
+
+  /** File extensions to include (empty = all). */
+  extensions?: string[];
+
+  /** Minimum file size in bytes. */
+  minSize?: number | null;
+
+  /** Maximum file size in bytes. */
+  maxSize?: number | null;
+
+  /** Minimum number of copies. */
+  minCopies?: number | null;
+}
+
+/** One page of queried duplicate groups. */
+export interface GroupPage {
+  /** Groups on this page. */
+  groups: DuplicateGroup[];
+
+  /** Offset of the first group on this page. */
+  offset: number;
+
+  /** Number of groups matching the filters, across all pages. */
+  totalGroups: number;
+
+  /** Wasted space of all matching groups. */
+  totalWastedSpace: number;
+}
+
+/** A group of directories with identical contents. */
+export interface DuplicateDirectoryGroup {
+  /** Merkle hash of the directory contents. */
+  hash: string;
+
+  /** Total size of the files in each directory, in bytes. */
+  totalSize: number;
+
+  /** Number of files in each directory, including subdirectories. */
+  fileCount: number;
+
+  /** Paths of the identical directories. */
+  directories: string[];
+}
+
+/** A directory whose contents are all present in another directory. */
+export interface DirectorySubset {
+  /** Directory whose contents are contained in the superset. */
+  subset: string;
+
+  /** Directory containing a copy of every file in the subset. */
+  superset: string;
+
+  /** Total size of the files in the subset, in bytes. */
+  totalSize: number;
+
+  /** Number of files in the subset, including subdirectories. */
+  fileCount: number;
+}
+
+/** A group of files that are similar but not necessarily identical. */
+export interface SimilarityGroup {
+  /** Members of the group; the first is the reference the others were compared against. */
+  members: SimilarFile[];
+}
+
+/** A file in a similarity group. */
+export interface SimilarFile {
+  /** The file. */
+  file: FileEntry;
+
+  /** Similarity to the group's reference file, from 0 to 1 (identical). */
+  similarity: number;
+}
+
+/** A file whose content no longer matches its baseline hash. */
+export interface VerifyMismatch {
+  /** The file as it is now. */
+  file: FileEntry;
+
+  /** Hash recorded in the baseline. */
+  expectedHash: string;
+
+  /** Hash of the current content. */
+  actualHash: string;
+
+  /** Size recorded in the baseline, if any. */
+  expectedSize?: number | null;
+
+  /** Modification time recorded in the baseline, if any. */
+  expectedModified?: string | null;
+}
+
+/** Result of verifying files against a baseline manifest. */
+export interface VerifyResult {
+  /** Number of baseline files that were re-hashed. */
+  filesVerified: number;
+
+  /** Number of files whose content matches the baseline. */
+  unchanged: number;
+
+  /** Content changed while size and modification time did not (likely bit rot). */
+  corrupted: VerifyMismatch[];
+
+  /** Content changed along with size or modification time. */
+  modified: VerifyMismatch[];
+
+  /** Baseline files that no longer exist. */
+  missing: string[];
+
+  /** Files under the verified roots that the baseline doesn't list. */
+  newFiles: FileEntry[];
+
+  /** Files that could not be read. */
+  errors: ScanError[];
+
+  /** Duration of the verification in milliseconds. */
+  durationMs: number;
 }
 
 /** Phases of the duplicate scanning process. */
@@ -88,6 +379,150 @@ export interface ScanResult {
 
   /** Duration of the scan in milliseconds. */
   durationMs: number;
+
+  /** Groups of directories with identical contents. */
+  duplicateDirectories?: DuplicateDirectoryGroup[];
+
+  /** Directories whose contents all exist in another directory. */
+  directorySubsets?: DirectorySubset[];
+
+  /** Groups of visually similar images (only when findSimilarImages is set). */
+  similarImages?: SimilarityGroup[];
+
+  /** Groups of nearly identical text files (only when findSimilarText is set). */
+  similarText?: SimilarityGroup[];
+
+  /** Chunk-level overlap analysis (only when analyzeChunkOverlap is set). */
+  chunkAnalysis?: ChunkAnalysis | null;
+
+  /** Scanned files whose content is listed in a reference manifest. */
+  manifestMatches?: ManifestMatch[];
+
+  /** Algorithm used for duplicate group hashes (absent in older results). */
+  hashAlgorithm?: HashAlgorithm | null;
+
+  /** Zero-byte files. They are never hashed or grouped as duplicates. */
+  emptyFiles?: FileEntry[];
+
+  /** Directories holding nothing but empty directories (topmost only). */
+  emptyDirectories?: string[];
+
+  /** Files sharing a name but not their content (only when findNameConflicts is set). */
+  nameConflicts?: NameConflict[];
+
+  /**
+   * Content shared between each pair of roots (or top-level folders when a
+   * single root was scanned).
+   */
+  overlapMatrix?: OverlapMatrix | null;
+}
+
+/** A root or top-level folder compared in the overlap matrix. */
+export interface OverlapFolder {
+  /** Absolute path of the folder. */
+  path: string;
+
+  /** Number of scanned files under the folder. */
+  totalFiles: number;
+
+  /** Total size of the scanned files under the folder in bytes. */
+  totalBytes: number;
+}
+
+/** Content held by both folders of a pair. */
+export interface FolderOverlap {
+  /** First folder of the pair. */
+  folderA: string;
+
+  /** Second folder of the pair. */
+  folderB: string;
+
+  /** Number of distinct files (by content) present in both folders. */
+  sharedFiles: number;
+
+  /** Total size of those files in bytes, counted once per file. */
+  sharedBytes: number;
+}
+
+/** How much content each pair of roots (or top-level folders) shares. */
+export interface OverlapMatrix {
+  /** Folders compared, sorted by path. */
+  folders: OverlapFolder[];
+
+  /** Pairs sharing at least one file, most shared bytes first. */
+  pairs: FolderOverlap[];
+}
+
+/** A scanned file whose content is listed in a reference manifest. */
+export interface ManifestMatch {
+  /** The scanned file. */
+  file: FileEntry;
+
+  /** Hash of the file (lowercase hex). */
+  hash: string;
+
+  /** Algorithm the hash was computed with. */
+  algorithm: HashAlgorithm;
+
+  /** Path of the manifest listing the content. */
+  manifest: string;
+
+  /** Paths the manifest lists for this content. */
+  knownPaths: string[];
+}
+
+/** A pair of files that share content-defined chunks. */
+export interface ChunkOverlap {
+  /** First file of the pair. */
+  fileA: FileEntry;
+
+  /** Second file of the pair. */
+  fileB: FileEntry;
+
+  /** Bytes of distinct chunks present in both files. */
+  sharedBytes: number;
+
+  /** Shared bytes as a fraction (0.0-1.0) of the smaller file. */
+  sharedRatio: number;
+}
+
+/** One version of a conflicting file: copies with identical content. */
+export interface ConflictVersion {
+  /** Content hash, or null if the size alone tells this version apart. */
+  hash?: string | null;
+
+  /** File size in bytes. */
+  size: number;
+
+  /** Files with this content, most recently modified first. */
+  files: FileEntry[];
+}
+
+/** Files sharing a name (or relative path) but not their content. */
+export interface NameConflict {
+  /** The shared file name or relative path. */
+  name: string;
+
+  /** Distinct versions, the most recently modified first. */
+  versions: ConflictVersion[];
+}
+
+/** Result of content-defined chunk analysis. */
+export interface ChunkAnalysis {
+  /** Pairs of files sharing at least the configured fraction, most shared bytes first. */
+  overlaps: ChunkOverlap[];
+
+  /** Number of files that were chunked. */
+  filesAnalyzed: number;
+
+  /** Total size of the chunked files in bytes. */
+  totalBytes: number;
+
+  /** Bytes remaining if every distinct chunk were stored once. */
+  uniqueBytes: number;
+
+  /** Bytes block-level dedup would reclaim (total minus unique). */
+  reclaimableBytes: number;
 }
 
 /** A non-fatal error that occurred during scanning. */
@@ -99,6 +534,187 @@ export interface ScanError {
   message: string;
 }
 
+/** Summary of a scan that was interrupted and can be resumed. */
+export interface InterruptedScan {
+  /** Checkpoint ID to pass when resuming. */
+  id: string;
+
+  /** Root directories the scan was started with. */
+  rootPaths: string[];
+
+  /** When the scan was started (seconds since the Unix epoch). */
+  createdAt: number;
+
+  /** When the checkpoint was last written (seconds since the Unix epoch). */
+  updatedAt: number;
+
+  /** Number of files discovered by the directory scan. */
+  filesDiscovered: number;
+
+  /** Number of files already hashed. */
+  hashesCompleted: number;
+}
+
+/**
+ * Wasted space attributed to one scan root. A group's first file is its
+ * keeper; every other copy counts toward the root it lies under.
+ */
+export interface RootWaste {
+  /** Root directory of the scan. */
+  root: string;
+
+  /** Bytes taken up by duplicate copies under the root. */
+  wastedSpace: number;
+}
+
+/** A completed scan recorded in the scan history. */
+export interface ScanHistoryEntry {
+  /** ID of the scan. */
+  id: string;
+
+  /** When the scan finished (seconds since the Unix epoch). */
+  finishedAt: number;
+
+  /** Options the scan was run with. */
+  options: ScanOptions;
+
+  /** Scan duration in milliseconds. */
+  durationMs: number;
+
+  /** Total number of files scanned. */
+  filesScanned: number;
+
+  /** Number of duplicate groups found. */
+  duplicateGroups: number;
+
+  /** Number of duplicate files (excluding one keeper per group). */
+  duplicatesFound: number;
+
+  /** Total wasted space in bytes. */
+  wastedSpace: number;
+
+  /** Number of files or directories that could not be processed. */
+  errorCount: number;
+
+  /** Wasted space per scanned root. */
+  wastedByRoot: RootWaste[];
+}
+
+/** One point of a wasted space trend. */
+export interface TrendPoint {
+  /** ID of the scan the point comes from. */
+  scanId: string;
+
+  /** When the scan finished (seconds since the Unix epoch). */
+  finishedAt: number;
+
+  /** Wasted space under the root at that time. */
+  wastedSpace: number;
+}
+
+/** Wasted space over time for one scan root. */
+export interface RootTrend {
+  /** Root directory. */
+  root: string;
+
+  /** Points from scans that included the root, oldest first. */
+  points: TrendPoint[];
+}
+
+/** Summary of a saved scan session. */
+export interface SessionSummary {
+  /** Session ID to pass when reopening. */
+  id: string;
+
+  /** Name given when saving, if any. */
+  name?: string | null;
+
+  /** Root directories of the scan. */
+  rootPaths: string[];
+
+  /** When the session was saved (seconds since the Unix epoch). */
+  savedAt: number;
+
+  /** Version of the app that saved the session. */
+  appVersion: string;
+
+  /** Number of duplicate groups in the result. */
+  duplicateGroups: number;
+
+  /** Total wasted space in bytes. */
+  totalWastedSpace: number;
+}
+
+/** A reopened scan session, checked against the disk. */
+export interface LoadedSession {
+  /** The session's summary. */
+  summary: SessionSummary;
+
+  /** Options the scan was run with. */
+  options: ScanOptions;
+
+  /** The saved result, with files that changed since marked `stale`. */
+  result: ScanResult;
+
+  /** Number of distinct files marked stale. */
+  staleFiles: number;
+}
+
+/**
+ * How a duplicate group changed between two scans:
+ * - added: the group only exists in the newer scan
+ * - grew: the group has more copies than before
+ * - shrank: the group has fewer copies, but still at least two
+ * - moved: the same number of copies, at different paths
+ * - resolved: the group no longer has duplicates
+ */
+export type GroupChange = 'added' | 'grew' | 'shrank' | 'moved' | 'resolved';
+
+/** A duplicate group that differs between two scans, matched by hash. */
+export interface GroupDiff {
+  /** Content hash shared by the group. */
+  hash: string;
+
+  /** Size of each file in bytes. */
+  size: number;
+
+  /** How the group changed. */
+  change: GroupChange;
+
+  /** Files in the newer scan, or in the older one for resolved groups. */
+  files: FileEntry[];
+
+  /** Paths that are only in the newer scan's group. */
+  addedPaths: string[];
+
+  /** Paths that are only in the older scan's group. */
+  removedPaths: string[];
+
+  /** Wasted space of the group in the older scan. */
+  wastedSpaceBefore: number;
+
+  /** Wasted space of the group in the newer scan. */
+  wastedSpaceAfter: number;
+}
+
+/** Differences between the duplicate groups of two scans. */
+export interface ScanDiff {
+  /** Groups that were added, changed or resolved, largest change first. */
+  groups: GroupDiff[];
+
+  /** Number of groups with exactly the same files in both scans. */
+  unchangedGroups: number;
+
+  /** Total wasted space in the older scan. */
+  wastedSpaceBefore: number;
+
+  /** Total wasted space in the newer scan. */
+  wastedSpaceAfter: number;
+
+  /** Change in total wasted space; negative when space was freed. */
+  wastedSpaceChange: number;
+}
+
 /** Result of a file deletion operation. */
 export interface DeleteResult {
   /** Paths of successfully deleted files. */